@@ -0,0 +1,20 @@
+use std::process::Command;
+
+/// Embeds the short git commit as `TODO_MD_GIT_COMMIT` when building from a
+/// checkout; source tarballs and nix builds without `.git` simply omit it.
+fn main() {
+    println!("cargo:rerun-if-changed=.git/HEAD");
+    println!("cargo:rerun-if-changed=.git/refs");
+
+    let output = Command::new("git")
+        .args(["rev-parse", "--short", "HEAD"])
+        .output();
+    if let Ok(output) = output
+        && output.status.success()
+    {
+        let commit = String::from_utf8_lossy(&output.stdout).trim().to_string();
+        if !commit.is_empty() {
+            println!("cargo:rustc-env=TODO_MD_GIT_COMMIT={commit}");
+        }
+    }
+}
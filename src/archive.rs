@@ -0,0 +1,140 @@
+use std::collections::HashSet;
+
+use chrono::{DateTime, Utc};
+
+use crate::date_parser::in_home_zone;
+use crate::frontmatter::frontmatter_line_count;
+use crate::storage::continuation_blocks;
+use crate::types::Todo;
+
+/// Moves every done todo, with its notes, out of `todo_content` and appends
+/// them to `archive_content` under an `## Archived <date>` header. Recurring
+/// todos that rolled forward are open again, so they stay. Returns the new
+/// todo and archive contents and the archived todos; when nothing is done
+/// both contents come back unchanged.
+pub fn archive_done(
+    todo_content: &str,
+    archive_content: &str,
+    now: DateTime<Utc>,
+) -> (String, String, Vec<Todo>) {
+    let notes = continuation_blocks(todo_content);
+    let mut archived = Vec::new();
+    let mut moved_lines = HashSet::new();
+    for (idx, line) in todo_content
+        .lines()
+        .enumerate()
+        .skip(frontmatter_line_count(todo_content))
+    {
+        if !line.trim_start().starts_with("- [") {
+            continue;
+        }
+        let Ok(todo) = Todo::try_from_str(line) else {
+            continue;
+        };
+        if !todo.done() {
+            continue;
+        }
+        moved_lines.insert(idx);
+        if let Some((_, block)) = notes.iter().find(|(todo_idx, _)| *todo_idx == idx) {
+            moved_lines.extend(block.iter().copied());
+        }
+        archived.push(todo);
+    }
+    if archived.is_empty() {
+        return (
+            todo_content.to_string(),
+            archive_content.to_string(),
+            archived,
+        );
+    }
+
+    let (moved, kept): (Vec<_>, Vec<_>) = todo_content
+        .lines()
+        .enumerate()
+        .partition(|(idx, _)| moved_lines.contains(idx));
+    let mut next_todo = kept
+        .into_iter()
+        .map(|(_, line)| line)
+        .collect::<Vec<_>>()
+        .join("\n");
+    if todo_content.ends_with('\n') && !next_todo.is_empty() {
+        next_todo.push('\n');
+    }
+
+    let mut next_archive = archive_content.to_string();
+    if !next_archive.is_empty() {
+        if !next_archive.ends_with('\n') {
+            next_archive.push('\n');
+        }
+        next_archive.push('\n');
+    }
+    next_archive.push_str(&format!(
+        "## Archived {}\n",
+        in_home_zone(now).format("%Y-%m-%d %H:%M")
+    ));
+    for (_, line) in moved {
+        next_archive.push_str(line);
+        next_archive.push('\n');
+    }
+    (next_todo, next_archive, archived)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::date_parser::pin_home_zone;
+
+    const CONTENT: &str = "# Todos\n\
+        \n\
+        ## Home\n\
+        - [x] Pay rent (id: 123e4567-e89b-12d3-a456-426614174000)\n\
+        \x20\x20\x20\x20paid by transfer\n\
+        - [_] Water plants (reccurence: daily) (due: 2026-02-24T09:00:00Z) (id: 123e4567-e89b-12d3-a456-426614174001)\n\
+        Some notes about the house.\n\
+        - [x] Call mom (id: 123e4567-e89b-12d3-a456-426614174002)\n";
+
+    fn now() -> DateTime<Utc> {
+        DateTime::parse_from_rfc3339("2026-02-23T18:00:00Z")
+            .expect("valid now")
+            .with_timezone(&Utc)
+    }
+
+    #[test]
+    fn moves_done_todos_and_keeps_everything_else() {
+        let _zone = pin_home_zone(chrono_tz::UTC);
+        let (next, archive, archived) = archive_done(CONTENT, "", now());
+
+        assert_eq!(archived.len(), 2);
+        assert_eq!(
+            next,
+            "# Todos\n\
+             \n\
+             ## Home\n\
+             - [_] Water plants (reccurence: daily) (due: 2026-02-24T09:00:00Z) (id: 123e4567-e89b-12d3-a456-426614174001)\n\
+             Some notes about the house.\n"
+        );
+        assert!(archive.starts_with("## Archived 2026-02-23 18:00\n"));
+        assert!(archive.ends_with(
+            "- [x] Pay rent (id: 123e4567-e89b-12d3-a456-426614174000)\n\
+             \x20\x20\x20\x20paid by transfer\n\
+             - [x] Call mom (id: 123e4567-e89b-12d3-a456-426614174002)\n"
+        ));
+    }
+
+    #[test]
+    fn appends_to_an_existing_archive() {
+        let earlier =
+            "## Archived 2026-01-01 09:00\n- [x] Old (id: 123e4567-e89b-12d3-a456-426614174009)";
+        let (_, archive, _) = archive_done(CONTENT, earlier, now());
+        assert!(archive.starts_with(&format!("{earlier}\n\n## Archived ")));
+        assert_eq!(archive.matches("- [x]").count(), 3);
+    }
+
+    #[test]
+    fn nothing_done_is_a_no_op() {
+        let open = "- [_] Water plants (id: 123e4567-e89b-12d3-a456-426614174001)\n";
+        let (next, archive, archived) = archive_done(open, "existing\n", now());
+        assert!(archived.is_empty());
+        assert_eq!((next.as_str(), archive.as_str()), (open, "existing\n"));
+    }
+}
@@ -0,0 +1,295 @@
+use anyhow::{anyhow, Context, Result};
+use serde::Deserialize;
+use serde_json::{json, Value};
+use uuid::Uuid;
+
+use crate::edit::{
+    add_todo, add_todo_to_section, complete_todo, parse_date_field, parse_recurrence_field,
+    remove_todo, update_todo,
+};
+use crate::storage::{parse_todo_content, resolve_id_prefix, validate_todo_content};
+use crate::types::Todo;
+
+#[derive(Debug, Deserialize)]
+#[serde(tag = "op", rename_all = "lowercase", deny_unknown_fields)]
+enum BatchCommand {
+    Add {
+        name: String,
+        due: Option<String>,
+        scheduled: Option<String>,
+        recurrence: Option<String>,
+        section: Option<String>,
+    },
+    Done {
+        id: String,
+    },
+    Edit {
+        id: String,
+        name: Option<String>,
+        due: Option<String>,
+        scheduled: Option<String>,
+        recurrence: Option<String>,
+    },
+    Rm {
+        id: String,
+        #[serde(default)]
+        purge: bool,
+    },
+}
+
+impl BatchCommand {
+    fn op(&self) -> &'static str {
+        match self {
+            BatchCommand::Add { .. } => "add",
+            BatchCommand::Done { .. } => "done",
+            BatchCommand::Edit { .. } => "edit",
+            BatchCommand::Rm { .. } => "rm",
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct BatchOutcome {
+    /// Content to write; `None` when anything failed and nothing may be written.
+    pub content: Option<String>,
+    /// One JSON object per input line.
+    pub results: Vec<Value>,
+    /// Todos removed by `rm` without `purge`, to be moved to the trash.
+    pub trashed: Vec<Todo>,
+}
+
+/// Applies the JSON commands in `input` (one per line) to `content`, in order:
+///
+/// - `{"op":"add","name":"...","due":"...","scheduled":"...","recurrence":"...","section":"..."}`
+/// - `{"op":"done","id":"<prefix>"}`
+/// - `{"op":"edit","id":"<prefix>", ...same optional fields as add but section}`
+/// - `{"op":"rm","id":"<prefix>","purge":false}`
+///
+/// Omitted fields are left unchanged; in `edit` an empty string clears a date
+/// or recurrence. All-or-nothing: any invalid line or failed command means
+/// nothing is written.
+pub fn run_batch(content: &str, input: &str) -> BatchOutcome {
+    let lines = input
+        .lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty())
+        .collect::<Vec<_>>();
+
+    let parsed = lines
+        .iter()
+        .map(|(_, line)| serde_json::from_str::<BatchCommand>(line))
+        .collect::<Vec<_>>();
+    if parsed.iter().any(Result::is_err) {
+        let results = lines
+            .iter()
+            .zip(&parsed)
+            .map(|((idx, _), command)| match command {
+                Ok(_) => json!({"line": idx + 1, "ok": false, "error": "not applied: batch has invalid lines"}),
+                Err(error) => json!({"line": idx + 1, "ok": false, "error": format!("invalid command: {error}")}),
+            })
+            .collect();
+        return BatchOutcome {
+            content: None,
+            results,
+            trashed: Vec::new(),
+        };
+    }
+
+    let mut working = content.to_string();
+    let mut results = Vec::new();
+    let mut trashed = Vec::new();
+    let mut failed = false;
+    for ((idx, _), command) in lines.iter().zip(parsed) {
+        let command = command.expect("parse errors handled above");
+        let line = idx + 1;
+        if failed {
+            results.push(json!({"line": line, "op": command.op(), "ok": false, "error": "not applied: an earlier command failed"}));
+            continue;
+        }
+        let op = command.op();
+        let to_trash = matches!(command, BatchCommand::Rm { purge: false, .. });
+        match apply(&working, command) {
+            Ok((next, todo)) => {
+                working = next;
+                if to_trash {
+                    trashed.push(todo.clone());
+                }
+                results.push(json!({
+                    "line": line,
+                    "op": op,
+                    "ok": true,
+                    "id": todo.id().to_string(),
+                    "todo": todo.to_line(),
+                }));
+            }
+            Err(error) => {
+                failed = true;
+                results.push(
+                    json!({"line": line, "op": op, "ok": false, "error": format!("{error:#}")}),
+                );
+            }
+        }
+    }
+
+    if !failed {
+        let issues = validate_todo_content(&working);
+        if !issues.is_empty() {
+            failed = true;
+            results.push(json!({"ok": false, "error": format!("result would be invalid: {}", issues.join("; "))}));
+        }
+    }
+
+    if failed {
+        trashed.clear();
+    }
+    BatchOutcome {
+        content: (!failed).then_some(working),
+        results,
+        trashed,
+    }
+}
+
+fn apply(content: &str, command: BatchCommand) -> Result<(String, Todo)> {
+    match command {
+        BatchCommand::Add {
+            name,
+            due,
+            scheduled,
+            recurrence,
+            section,
+        } => {
+            let name = name.trim().to_string();
+            if name.is_empty() {
+                return Err(anyhow!("name must not be empty"));
+            }
+            let mut todo = Todo::new(name);
+            todo.set_due_date(due.map(|raw| parse_date_field("due", &raw)).transpose()?);
+            todo.set_scheduled(
+                scheduled
+                    .map(|raw| parse_date_field("scheduled", &raw))
+                    .transpose()?,
+            );
+            let (recurrence, recurrence_end) = recurrence
+                .map(|raw| parse_recurrence_field(&raw))
+                .transpose()?
+                .unzip();
+            todo.set_recurence(recurrence);
+            todo.set_recurrence_end(recurrence_end.flatten());
+            let next = match section {
+                Some(section) => add_todo_to_section(content, &todo, &section),
+                None => add_todo(content, &todo),
+            };
+            Ok((next, todo))
+        }
+        BatchCommand::Done { id } => complete_todo(content, resolve(content, &id)?),
+        BatchCommand::Edit {
+            id,
+            name,
+            due,
+            scheduled,
+            recurrence,
+        } => {
+            let id = resolve(content, &id)?;
+            let name = name.map(|name| name.trim().to_string());
+            if name.as_deref() == Some("") {
+                return Err(anyhow!("name must not be empty"));
+            }
+            let due = due
+                .map(|raw| optional(&raw, |raw| parse_date_field("due", raw)))
+                .transpose()?;
+            let scheduled = scheduled
+                .map(|raw| optional(&raw, |raw| parse_date_field("scheduled", raw)))
+                .transpose()?;
+            let recurrence = recurrence
+                .map(|raw| optional(&raw, parse_recurrence_field))
+                .transpose()?;
+            update_todo(content, id, |todo| {
+                if let Some(name) = name {
+                    todo.set_name(name);
+                }
+                if let Some(due) = due {
+                    todo.set_due_date(due);
+                }
+                if let Some(scheduled) = scheduled {
+                    todo.set_scheduled(scheduled);
+                }
+                if let Some(recurrence) = recurrence {
+                    let (recurrence, recurrence_end) = recurrence.unzip();
+                    todo.set_recurence(recurrence);
+                    todo.set_recurrence_end(recurrence_end.flatten());
+                }
+            })
+        }
+        BatchCommand::Rm { id, .. } => remove_todo(content, resolve(content, &id)?),
+    }
+}
+
+/// An empty value clears the field.
+fn optional<T>(raw: &str, parse: impl Fn(&str) -> Result<T>) -> Result<Option<T>> {
+    if raw.trim().is_empty() {
+        return Ok(None);
+    }
+    parse(raw).map(Some)
+}
+
+fn resolve(content: &str, prefix: &str) -> Result<Uuid> {
+    let parsed = parse_todo_content(content);
+    resolve_id_prefix(parsed.todos_by_id.keys().copied(), prefix)
+        .with_context(|| format!("cannot resolve id `{prefix}`"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CONTENT: &str = "# Todos\n- [_] Water plants (id: 123e4567-e89b-42d3-a456-426614174000)\n- [_] Old (id: 123e4567-e89b-42d3-a456-426614174001)\n";
+
+    #[test]
+    fn applies_every_command_to_one_working_copy() {
+        let input = r#"{"op":"add","name":"Call mom","due":"2026-03-01T17:00:00Z"}
+{"op":"done","id":"123e4567-e89b-42d3-a456-426614174000"}
+{"op":"edit","id":"123e4567-e89b-42d3-a456-426614174001","name":"Renamed"}
+"#;
+        let outcome = run_batch(CONTENT, input);
+
+        let content = outcome.content.expect("batch succeeds");
+        assert!(content.contains("- [x] Water plants"));
+        assert!(content.contains("- [_] Renamed (updated: "));
+        assert!(content.contains("- [_] Call mom (due: "));
+        assert_eq!(outcome.results.len(), 3);
+        assert!(outcome.results.iter().all(|result| result["ok"] == true));
+    }
+
+    #[test]
+    fn invalid_line_rejects_the_whole_batch() {
+        let input = r#"{"op":"rm","id":"123e4567-e89b-42d3-a456-426614174001"}
+{"op":"explode"}
+"#;
+        let outcome = run_batch(CONTENT, input);
+
+        assert!(outcome.content.is_none());
+        assert_eq!(outcome.results[0]["ok"], false);
+        assert_eq!(outcome.results[1]["line"], 2);
+        assert!(outcome.results[1]["error"]
+            .as_str()
+            .expect("error")
+            .starts_with("invalid command"));
+    }
+
+    #[test]
+    fn failing_command_writes_nothing() {
+        let input = r#"{"op":"rm","id":"123e4567-e89b-42d3-a456-426614174001"}
+{"op":"done","id":"ffff"}
+{"op":"add","name":"Never"}
+"#;
+        let outcome = run_batch(CONTENT, input);
+
+        assert!(outcome.content.is_none());
+        assert_eq!(outcome.results[0]["ok"], true);
+        assert_eq!(outcome.results[1]["ok"], false);
+        assert!(outcome.results[2]["error"]
+            .as_str()
+            .expect("error")
+            .contains("earlier command failed"));
+    }
+}
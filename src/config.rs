@@ -1,42 +1,362 @@
 use std::collections::HashMap;
 use std::env;
 use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
 
-use anyhow::{bail, Context, Result};
+use anyhow::{anyhow, bail, Context, Result};
+use chrono::{FixedOffset, NaiveTime, Weekday};
+use chrono_tz::Tz;
+
+use crate::date_parser::{parse_timezone, parse_tz, BareTime, DateOptions, DateOrder, DueStyle};
+use crate::diff::ChangeSet;
+use crate::doctor::DoctorOptions;
+use crate::frontmatter::{parse_frontmatter, Frontmatter};
+use crate::recurrence_parser::Rollover;
+use crate::sync::render_commit_template;
+use crate::types::{CheckboxStyle, RecurrenceStyle};
 
 pub const DEFAULT_CONFIG_DIR_SUFFIX: &str = ".config/todos";
+/// The config dir's name under `XDG_CONFIG_HOME`.
+const XDG_CONFIG_DIR_NAME: &str = "todos";
+pub const ENV_EXAMPLE_FILE: &str = ".env.example";
+const BACKUP_DIR_NAME: &str = "backups";
+const DEFAULT_BACKUP_KEEP: usize = 10;
+pub const DEFAULT_GITIGNORE_ENTRIES: &[&str] = &[".env", "*.tmp", "backups/", ".todo_cache"];
+
+/// A recognized environment variable, for the generated `.env.example`.
+pub struct EnvVar {
+    pub key: &'static str,
+    pub example: &'static str,
+    pub description: &'static str,
+}
+
+/// Every variable `AppConfig::load` reads. Keep in step with `load`; a test
+/// checks that no key is missing.
+pub const ENV_VARS: &[EnvVar] = &[
+    EnvVar {
+        key: "TODOS_CONFIG_DIR",
+        example: "~/.config/todos",
+        description: "Config and git repo directory, default $XDG_CONFIG_HOME/todos (process environment only)",
+    },
+    EnvVar {
+        key: "TODOS_PROFILE",
+        example: "work",
+        description: "Profile to use: todo-<name>.md with overrides from .env.<name> (same as --profile)",
+    },
+    EnvVar {
+        key: "TODOS_FILE",
+        example: "~/.config/todos/todo.md",
+        description: "Todo file; must live inside the config dir to sync",
+    },
+    EnvVar {
+        key: "TODOS_GIT_REMOTE",
+        example: "git@github.com:you/todos.git",
+        description: "Remote that sync pulls from and pushes to",
+    },
+    EnvVar {
+        key: "TODOS_GIT_BRANCH",
+        example: "main",
+        description: "Branch to sync; setup detects the remote default when unset",
+    },
+    EnvVar {
+        key: "TODOS_GIT_AUTHOR_NAME",
+        example: "Your Name",
+        description: "Author name for sync commits",
+    },
+    EnvVar {
+        key: "TODOS_GIT_AUTHOR_EMAIL",
+        example: "you@example.com",
+        description: "Author email for sync commits",
+    },
+    EnvVar {
+        key: "GITHUB_TOKEN",
+        example: "ghp_...",
+        description: "Token used to create a missing GitHub repo during setup",
+    },
+    EnvVar {
+        key: "TODOS_GITIGNORE",
+        example: ".env,*.tmp,backups/,.todo_cache",
+        description: "Comma-separated .gitignore entries; .env is always kept",
+    },
+    EnvVar {
+        key: "TODOS_MAX_DUE_PER_DAY",
+        example: "5",
+        description: "Doctor warns above this many open todos due on one day; 0 disables",
+    },
+    EnvVar {
+        key: "TODOS_NEXT_WEEK_DAY",
+        example: "monday",
+        description: "Day \"next week\" resolves to",
+    },
+    EnvVar {
+        key: "TODOS_WEEK_START",
+        example: "monday",
+        description: "First day of the week: monday or sunday",
+    },
+    EnvVar {
+        key: "TODOS_TIMEZONE",
+        example: "+05:30",
+        description: "UTC or a fixed offset for dates; unset follows the host",
+    },
+    EnvVar {
+        key: "TODOS_TZ",
+        example: "America/New_York",
+        description: "IANA zone for dates, following daylight saving; replaces TODOS_TIMEZONE",
+    },
+    EnvVar {
+        key: "TODOS_ROLLOVER",
+        example: "strict",
+        description: "Completing an overdue recurring todo: strict steps once, from-now skips to the next future date",
+    },
+    EnvVar {
+        key: "TODOS_RECURRENCE_STYLE",
+        example: "compact",
+        description: "How recurrences are written: compact (monthly on 1st, weekdays) or verbose (monthly on the 1st)",
+    },
+    EnvVar {
+        key: "TODOS_CHECKBOX_STYLE",
+        example: "underscore",
+        description: "How open todos are written: underscore (- [_]) or space (- [ ])",
+    },
+    EnvVar {
+        key: "TODOS_DUE_SOON_DAYS",
+        example: "2",
+        description: "Open todos due within this many days past today are shown as due soon",
+    },
+    EnvVar {
+        key: "TODOS_FUZZY",
+        example: "on",
+        description: "off reads only exact day names and keywords instead of correcting typos",
+    },
+    EnvVar {
+        key: "TODOS_DATE_ORDER",
+        example: "mdy",
+        description: "How slash dates like 03/04/2026 are read: mdy (default, March 4) or dmy (3 April)",
+    },
+    EnvVar {
+        key: "TODOS_DUE_STYLE",
+        example: "absolute",
+        description: "relative writes open todos due this week as (due: tomorrow 9am); lossy, since the phrase is re-read against the clock on every load",
+    },
+    EnvVar {
+        key: "TODOS_BARE_TIME",
+        example: "next",
+        description: "Day for a time with no date like 9am: next (today unless already past, then tomorrow) or today",
+    },
+    EnvVar {
+        key: "TODOS_END_OF_DAY",
+        example: "17:00",
+        description: "Time the word eod stands for, as HH:MM",
+    },
+    EnvVar {
+        key: "TODOS_DEFAULT_DUE_TIME",
+        example: "09:00",
+        description: "Time given to due dates written without one, as HH:MM (default 23:59)",
+    },
+    EnvVar {
+        key: "TODOS_SKIP_INVALID",
+        example: "false",
+        description: "Let sync commit around unparseable or id-less lines",
+    },
+    EnvVar {
+        key: "TODOS_STRICT",
+        example: "false",
+        description: "Refuse to read todo.md while any line would change or lose data when rewritten (same as --strict)",
+    },
+    EnvVar {
+        key: "TODOS_FETCH_DEPTH",
+        example: "50",
+        description: "Shallow fetch depth for long histories",
+    },
+    EnvVar {
+        key: "TODOS_BACKUP_KEEP",
+        example: "10",
+        description: "Copies of todo.md that sync keeps in backups/ before changing it; 0 disables them",
+    },
+    EnvVar {
+        key: "TODOS_COMMIT_TEMPLATE",
+        example: "todos: {added} new, {completed} done ({line_summary})",
+        description: "Sync commit subject; placeholders {added} {updated} {deleted} {completed} {line_summary}",
+    },
+    EnvVar {
+        key: "TODOS_PRE_SYNC_HOOK",
+        example: "todo_md fmt --sort",
+        description: "Shell command run before sync commits; a nonzero exit aborts the sync",
+    },
+    EnvVar {
+        key: "TODOS_POST_SYNC_HOOK",
+        example: "notify-send \"todos synced\"",
+        description: "Shell command run after a successful sync push",
+    },
+];
+
+/// Commented reference of every variable, written by `setup`. Unlike `.env`
+/// it is synced, so it must never contain real values.
+pub fn env_example() -> String {
+    let mut out = String::from(
+        "# todo_md settings. Copy the ones you need into .env (which is never synced).\n",
+    );
+    for var in ENV_VARS {
+        out.push_str(&format!(
+            "\n# {}\n# {}={}\n",
+            var.description, var.key, var.example
+        ));
+    }
+    out
+}
+
+static PROFILE: OnceLock<String> = OnceLock::new();
+
+/// Selects a profile (`--profile`) for this process, ahead of
+/// `TODOS_PROFILE`. Only the first call takes effect.
+pub fn configure_profile(name: &str) {
+    let _ = PROFILE.set(name.to_string());
+}
 
 #[derive(Debug, Clone)]
 pub struct AppConfig {
     pub config_dir: PathBuf,
+    /// `--profile` or `TODOS_PROFILE`: a separate list in `todo-<name>.md`,
+    /// with its own trash, archive and `.env.<name>` overrides. `None` is the
+    /// original `todo.md`.
+    pub profile: Option<String>,
     pub todo_file: PathBuf,
+    /// Soft-deleted todos, kept next to `todo.md` and synced with it.
+    pub trash_file: PathBuf,
+    /// Completed todos moved out by `archive`, next to `todo.md` and synced
+    /// with it.
+    pub archive_file: PathBuf,
     pub env_file: PathBuf,
+    /// `.env.<profile>`, layered over `env_file`. Kept out of git like `.env`.
+    pub profile_env_file: Option<PathBuf>,
     pub git_remote: Option<String>,
     pub git_branch: String,
+    /// Whether `TODOS_GIT_BRANCH` was set; otherwise `setup` may replace the
+    /// "main" default with the remote's default branch.
+    pub git_branch_configured: bool,
     pub git_author_name: Option<String>,
     pub git_author_email: Option<String>,
     pub github_token: Option<String>,
+    pub gitignore_entries: Vec<String>,
+    pub max_due_per_day: usize,
+    pub next_week_day: Weekday,
+    pub week_start: Weekday,
+    /// `TODOS_TIMEZONE` as a fixed offset (`UTC`, `+05:30`). Due times, "today"
+    /// and recurrence defaults use it instead of the host zone, so a sync on a
+    /// UTC server matches the user's calendar. Unset follows the host.
+    pub timezone: Option<FixedOffset>,
+    /// `TODOS_TZ` as an IANA zone (`America/New_York`). Used like `timezone`,
+    /// but a 9pm due date stays 9pm across daylight-saving changes.
+    pub tz: Option<Tz>,
+    /// `TODOS_ROLLOVER`: how completing a recurring todo picks the next date.
+    pub rollover: Rollover,
+    /// `TODOS_RECURRENCE_STYLE`: how recurrences are written to todo.md.
+    pub recurrence_style: RecurrenceStyle,
+    /// `TODOS_CHECKBOX_STYLE`: `- [_]` or `- [ ]` for open todos.
+    pub checkbox_style: CheckboxStyle,
+    /// `TODOS_DUE_SOON_DAYS`: the "due soon" window shared by every view.
+    pub due_soon_days: u32,
+    /// `TODOS_FUZZY`: whether the date and recurrence parsers correct typos.
+    pub fuzzy: bool,
+    /// `TODOS_DATE_ORDER`: how slash-separated numeric dates are read.
+    pub date_order: DateOrder,
+    /// `TODOS_DUE_STYLE`: whether near due dates are written as phrases.
+    pub due_style: DueStyle,
+    /// `TODOS_BARE_TIME`: which day a time without a date lands on.
+    pub bare_time: BareTime,
+    /// `TODOS_END_OF_DAY`: the time "eod" stands for.
+    pub end_of_day: Option<NaiveTime>,
+    /// `TODOS_DEFAULT_DUE_TIME`: time of day for due dates written without
+    /// one. The todo file's frontmatter (`due_time: HH:MM`) wins over it.
+    pub default_due_time: Option<NaiveTime>,
+    /// `title` from the todo file's frontmatter.
+    pub title: Option<String>,
+    pub skip_invalid: bool,
+    /// `TODOS_STRICT`: fail instead of silently dropping anything on read.
+    pub strict: bool,
+    /// Shallow `git fetch --depth` for long histories. Sync only reads
+    /// `HEAD:todo.md`, but a rebase needs the merge base within this depth, and
+    /// history-walking commands only see what has been fetched.
+    pub fetch_depth: Option<u32>,
+    /// Where `sync` copies the todo file before changing it; kept out of git.
+    pub backup_dir: PathBuf,
+    /// `TODOS_BACKUP_KEEP`: how many of those copies to keep per todo file.
+    /// 0 turns them off.
+    pub backup_keep: usize,
+    /// Shell command run by `sync` once todo.md validates and before anything
+    /// is staged, with the todo file path in `TODO_FILE`. It may rewrite the
+    /// file, which is validated again; a nonzero exit aborts the sync. The same
+    /// trust caveats as `post_sync_hook` apply.
+    pub pre_sync_hook: Option<String>,
+    /// Shell command run by `sync` after a successful push, with the change
+    /// counts in `TODO_ADDED`, `TODO_UPDATED`, `TODO_DELETED`, `TODO_COMPLETED`
+    /// and `TODO_COMMITTED` (1 or 0) and the todo file path in `TODO_FILE`. It
+    /// runs with your full privileges from the config dir, so only set it to
+    /// commands you trust; since `.env` is never synced, a pulled change cannot
+    /// alter it.
+    pub post_sync_hook: Option<String>,
+    /// `TODOS_COMMIT_TEMPLATE`: the sync commit subject, checked for unknown
+    /// placeholders on load. `sync --aggressive` only amends commits with the
+    /// default subject, so a custom one never amends.
+    pub commit_template: Option<String>,
 }
 
 impl AppConfig {
     pub fn load() -> Result<Self> {
         let config_dir = resolve_config_dir()?;
         let env_file = config_dir.join(".env");
-        let env_map = load_optional_env_file(&env_file)?;
+        let mut env_map = load_optional_env_file(&env_file)?;
 
-        let todo_file =
-            resolve_path_override("TODOS_FILE", &env_map, Some(config_dir.join("todo.md")))?;
+        let profile = PROFILE
+            .get()
+            .cloned()
+            .or_else(|| env_value("TODOS_PROFILE", &env_map))
+            .map(|name| parse_profile_name(name.trim()))
+            .transpose()?;
+        let profile_env_file = profile
+            .as_ref()
+            .map(|name| config_dir.join(format!(".env.{name}")));
+        let profile_map = match &profile_env_file {
+            Some(path) => load_optional_env_file(path)?,
+            None => HashMap::new(),
+        };
+        env_map.extend(profile_map.clone());
+
+        // A profile only takes TODOS_FILE from its own overrides, so the base
+        // `.env` cannot point every profile at the same file.
+        let (todo_file, trash_file, archive_file) = match &profile {
+            Some(name) => {
+                let todo_file = match profile_map.get("TODOS_FILE") {
+                    Some(path) => expand_tilde(PathBuf::from(path))?,
+                    None => config_dir.join(format!("todo-{name}.md")),
+                };
+                let trash_file = todo_file.with_file_name(format!("trash-{name}.md"));
+                let archive_file = todo_file.with_file_name(format!("archive-{name}.md"));
+                (todo_file, trash_file, archive_file)
+            }
+            None => {
+                let todo_file = resolve_path_override(
+                    "TODOS_FILE",
+                    &env_map,
+                    Some(config_dir.join("todo.md")),
+                )?;
+                let trash_file = todo_file.with_file_name("trash.md");
+                let archive_file = todo_file.with_file_name("archive.md");
+                (todo_file, trash_file, archive_file)
+            }
+        };
 
         let git_remote = first_non_empty(
             env::var("TODOS_GIT_REMOTE").ok(),
             env_map.get("TODOS_GIT_REMOTE").cloned(),
         );
 
-        let git_branch = first_non_empty(
+        let configured_branch = first_non_empty(
             env::var("TODOS_GIT_BRANCH").ok(),
             env_map.get("TODOS_GIT_BRANCH").cloned(),
-        )
-        .unwrap_or_else(|| "main".to_string());
+        );
+        let git_branch_configured = configured_branch.is_some();
+        let git_branch = configured_branch.unwrap_or_else(|| "main".to_string());
 
         let git_author_name = first_non_empty(
             env::var("TODOS_GIT_AUTHOR_NAME").ok(),
@@ -53,25 +373,322 @@ impl AppConfig {
             env_map.get("GITHUB_TOKEN").cloned(),
         );
 
+        let mut gitignore_entries = env_value("TODOS_GITIGNORE", &env_map)
+            .map(|value| parse_gitignore_entries(&value))
+            .unwrap_or_else(|| {
+                DEFAULT_GITIGNORE_ENTRIES
+                    .iter()
+                    .map(|entry| entry.to_string())
+                    .collect::<Vec<_>>()
+            });
+        if let Some(name) = &profile {
+            gitignore_entries.push(format!(".env.{name}"));
+        }
+        let backup_entry = format!("{BACKUP_DIR_NAME}/");
+        if !gitignore_entries.contains(&backup_entry) {
+            gitignore_entries.push(backup_entry);
+        }
+
+        let max_due_per_day = env_value("TODOS_MAX_DUE_PER_DAY", &env_map)
+            .map(|value| {
+                value.trim().parse::<usize>().with_context(|| {
+                    format!("TODOS_MAX_DUE_PER_DAY must be a number, got `{value}`")
+                })
+            })
+            .transpose()?
+            .unwrap_or(DoctorOptions::default().max_due_per_day);
+
+        let next_week_day = env_value("TODOS_NEXT_WEEK_DAY", &env_map)
+            .map(|value| {
+                value
+                    .trim()
+                    .parse::<Weekday>()
+                    .map_err(|_| anyhow!("TODOS_NEXT_WEEK_DAY must be a weekday, got `{value}`"))
+            })
+            .transpose()?
+            .unwrap_or(DateOptions::default().next_week_day);
+
+        let week_start = env_value("TODOS_WEEK_START", &env_map)
+            .map(|value| match value.trim().parse::<Weekday>() {
+                Ok(day @ (Weekday::Mon | Weekday::Sun)) => Ok(day),
+                _ => Err(anyhow!(
+                    "TODOS_WEEK_START must be monday or sunday, got `{value}`"
+                )),
+            })
+            .transpose()?
+            .unwrap_or(DateOptions::default().week_start);
+
+        let timezone = env_value("TODOS_TIMEZONE", &env_map)
+            .map(|value| {
+                parse_timezone(&value).with_context(|| {
+                    format!("TODOS_TIMEZONE must be UTC or an offset like +05:30, got `{value}`")
+                })
+            })
+            .transpose()?;
+
+        let tz = env_value("TODOS_TZ", &env_map)
+            .map(|value| {
+                parse_tz(&value).with_context(|| {
+                    format!("TODOS_TZ must be an IANA zone like America/New_York, got `{value}`")
+                })
+            })
+            .transpose()?;
+        if tz.is_some() && timezone.is_some() {
+            bail!("set only one of TODOS_TZ and TODOS_TIMEZONE");
+        }
+
+        let rollover = env_value("TODOS_ROLLOVER", &env_map)
+            .map(|value| {
+                Rollover::parse(&value).with_context(|| {
+                    format!("TODOS_ROLLOVER must be strict or from-now, got `{value}`")
+                })
+            })
+            .transpose()?
+            .unwrap_or_default();
+
+        let recurrence_style = env_value("TODOS_RECURRENCE_STYLE", &env_map)
+            .map(|value| {
+                RecurrenceStyle::parse(&value).with_context(|| {
+                    format!("TODOS_RECURRENCE_STYLE must be compact or verbose, got `{value}`")
+                })
+            })
+            .transpose()?
+            .unwrap_or_default();
+
+        let checkbox_style = env_value("TODOS_CHECKBOX_STYLE", &env_map)
+            .map(|value| {
+                CheckboxStyle::parse(&value).with_context(|| {
+                    format!("TODOS_CHECKBOX_STYLE must be underscore or space, got `{value}`")
+                })
+            })
+            .transpose()?
+            .unwrap_or_default();
+
+        let due_soon_days = env_value("TODOS_DUE_SOON_DAYS", &env_map)
+            .map(|value| {
+                value
+                    .trim()
+                    .parse::<u32>()
+                    .with_context(|| format!("TODOS_DUE_SOON_DAYS must be a number, got `{value}`"))
+            })
+            .transpose()?
+            .unwrap_or(DateOptions::default().due_soon_days);
+
+        let fuzzy = env_value("TODOS_FUZZY", &env_map)
+            .map(|value| parse_bool("TODOS_FUZZY", &value))
+            .transpose()?
+            .unwrap_or(DateOptions::default().fuzzy);
+
+        let date_order = env_value("TODOS_DATE_ORDER", &env_map)
+            .map(|value| {
+                DateOrder::parse(&value)
+                    .with_context(|| format!("TODOS_DATE_ORDER must be mdy or dmy, got `{value}`"))
+            })
+            .transpose()?
+            .unwrap_or_default();
+
+        let due_style = env_value("TODOS_DUE_STYLE", &env_map)
+            .map(|value| {
+                DueStyle::parse(&value).with_context(|| {
+                    format!("TODOS_DUE_STYLE must be absolute or relative, got `{value}`")
+                })
+            })
+            .transpose()?
+            .unwrap_or_default();
+
+        let bare_time = env_value("TODOS_BARE_TIME", &env_map)
+            .map(|value| {
+                BareTime::parse(&value).with_context(|| {
+                    format!("TODOS_BARE_TIME must be next or today, got `{value}`")
+                })
+            })
+            .transpose()?
+            .unwrap_or_default();
+
+        let default_due_time = env_value("TODOS_DEFAULT_DUE_TIME", &env_map)
+            .map(|value| {
+                NaiveTime::parse_from_str(value.trim(), "%H:%M")
+                    .with_context(|| format!("TODOS_DEFAULT_DUE_TIME must be HH:MM, got `{value}`"))
+            })
+            .transpose()?;
+
+        let end_of_day = env_value("TODOS_END_OF_DAY", &env_map)
+            .map(|value| {
+                NaiveTime::parse_from_str(value.trim(), "%H:%M")
+                    .with_context(|| format!("TODOS_END_OF_DAY must be HH:MM, got `{value}`"))
+            })
+            .transpose()?;
+
+        // Frontmatter in the todo file wins over env settings for that file.
+        let frontmatter = read_frontmatter(&todo_file)?.unwrap_or_default();
+        let tz = tz.filter(|_| frontmatter.timezone.is_none());
+        let timezone = frontmatter.timezone.or(timezone);
+        let week_start = frontmatter.week_start.unwrap_or(week_start);
+
+        let skip_invalid = env_value("TODOS_SKIP_INVALID", &env_map)
+            .map(|value| parse_bool("TODOS_SKIP_INVALID", &value))
+            .transpose()?
+            .unwrap_or(false);
+
+        let strict = env_value("TODOS_STRICT", &env_map)
+            .map(|value| parse_bool("TODOS_STRICT", &value))
+            .transpose()?
+            .unwrap_or(false);
+
+        let fetch_depth = env_value("TODOS_FETCH_DEPTH", &env_map)
+            .map(|value| match value.trim().parse::<u32>() {
+                Ok(depth) if depth > 0 => Ok(depth),
+                _ => Err(anyhow!(
+                    "TODOS_FETCH_DEPTH must be a positive number, got `{value}`"
+                )),
+            })
+            .transpose()?;
+
+        let backup_keep = env_value("TODOS_BACKUP_KEEP", &env_map)
+            .map(|value| {
+                value
+                    .trim()
+                    .parse::<usize>()
+                    .with_context(|| format!("TODOS_BACKUP_KEEP must be a number, got `{value}`"))
+            })
+            .transpose()?
+            .unwrap_or(DEFAULT_BACKUP_KEEP);
+        let backup_dir = config_dir.join(BACKUP_DIR_NAME);
+
+        let pre_sync_hook = env_value("TODOS_PRE_SYNC_HOOK", &env_map);
+        let post_sync_hook = env_value("TODOS_POST_SYNC_HOOK", &env_map);
+
+        let commit_template = env_value("TODOS_COMMIT_TEMPLATE", &env_map)
+            .map(|template| {
+                render_commit_template(&template, &ChangeSet::default(), "").map(|_| template)
+            })
+            .transpose()?;
+
         Ok(Self {
             config_dir,
+            profile,
             todo_file,
+            trash_file,
+            archive_file,
             env_file,
+            profile_env_file,
             git_remote,
             git_branch,
+            git_branch_configured,
             git_author_name,
             git_author_email,
             github_token,
+            gitignore_entries,
+            max_due_per_day,
+            next_week_day,
+            week_start,
+            timezone,
+            tz,
+            rollover,
+            recurrence_style,
+            checkbox_style,
+            due_soon_days,
+            fuzzy,
+            date_order,
+            due_style,
+            bare_time,
+            end_of_day,
+            default_due_time: frontmatter.due_time.or(default_due_time),
+            title: frontmatter.title,
+            skip_invalid,
+            strict,
+            fetch_depth,
+            backup_dir,
+            backup_keep,
+            pre_sync_hook,
+            post_sync_hook,
+            commit_template,
         })
     }
+
+    pub fn date_options(&self) -> DateOptions {
+        DateOptions {
+            next_week_day: self.next_week_day,
+            week_start: self.week_start,
+            timezone: self.timezone,
+            tz: self.tz,
+            default_due_time: self
+                .default_due_time
+                .unwrap_or(DateOptions::default().default_due_time),
+            rollover: self.rollover,
+            recurrence_style: self.recurrence_style,
+            checkbox_style: self.checkbox_style,
+            due_soon_days: self.due_soon_days,
+            fuzzy: self.fuzzy,
+            date_order: self.date_order,
+            due_style: self.due_style,
+            bare_time: self.bare_time,
+            end_of_day: self.end_of_day.unwrap_or(DateOptions::default().end_of_day),
+        }
+    }
+
+    pub fn doctor_options(&self) -> DoctorOptions {
+        DoctorOptions {
+            max_due_per_day: self.max_due_per_day,
+        }
+    }
 }
 
-fn resolve_config_dir() -> Result<PathBuf> {
-    let default_dir = dirs::home_dir()
-        .map(|home| home.join(DEFAULT_CONFIG_DIR_SUFFIX))
-        .context("could not resolve home directory")?;
+fn read_frontmatter(todo_file: &Path) -> Result<Option<Frontmatter>> {
+    let content = match std::fs::read_to_string(todo_file) {
+        Ok(content) => content,
+        Err(error) if error.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+        Err(error) => {
+            return Err(error).with_context(|| format!("failed to read {}", todo_file.display()))
+        }
+    };
+    parse_frontmatter(content.trim_start_matches('\u{feff}'))
+        .with_context(|| format!("invalid frontmatter in {}", todo_file.display()))
+}
+
+/// Profile names become file names, so only letters, digits, `-` and `_`.
+fn parse_profile_name(name: &str) -> Result<String> {
+    if name.is_empty()
+        || !name
+            .chars()
+            .all(|ch| ch.is_ascii_alphanumeric() || ch == '-' || ch == '_')
+    {
+        bail!("profile must be letters, digits, `-` or `_`, got `{name}`");
+    }
+    Ok(name.to_string())
+}
+
+/// Splits a comma-separated `TODOS_GITIGNORE` value. `.env` is always kept so
+/// secrets never become syncable by overriding the list.
+fn parse_gitignore_entries(value: &str) -> Vec<String> {
+    let mut entries = vec![".env".to_string()];
+    for entry in value.split(',').map(str::trim).filter(|e| !e.is_empty()) {
+        if !entries.iter().any(|existing| existing == entry) {
+            entries.push(entry.to_string());
+        }
+    }
+    entries
+}
 
-    resolve_path_override("TODOS_CONFIG_DIR", &HashMap::new(), Some(default_dir))
+pub fn resolve_config_dir() -> Result<PathBuf> {
+    config_dir_from(
+        env::var("TODOS_CONFIG_DIR").ok(),
+        env::var("XDG_CONFIG_HOME").ok(),
+    )
+}
+
+/// `TODOS_CONFIG_DIR`, else `$XDG_CONFIG_HOME/todos`, else `~/.config/todos`.
+/// Empty values count as unset.
+fn config_dir_from(config_dir: Option<String>, xdg_config_home: Option<String>) -> Result<PathBuf> {
+    if let Some(dir) = first_non_empty(config_dir, None) {
+        return expand_tilde(PathBuf::from(dir));
+    }
+    if let Some(root) = first_non_empty(xdg_config_home, None) {
+        return Ok(expand_tilde(PathBuf::from(root))?.join(XDG_CONFIG_DIR_NAME));
+    }
+    dirs::home_dir()
+        .map(|home| home.join(DEFAULT_CONFIG_DIR_SUFFIX))
+        .context("could not resolve home directory")
 }
 
 fn resolve_path_override(
@@ -122,6 +739,19 @@ fn expand_tilde(path: PathBuf) -> Result<PathBuf> {
     Ok(cwd.join(path))
 }
 
+fn parse_bool(key: &str, value: &str) -> Result<bool> {
+    match value.trim().to_ascii_lowercase().as_str() {
+        "1" | "true" | "yes" | "on" => Ok(true),
+        "0" | "false" | "no" | "off" => Ok(false),
+        _ => bail!("{key} must be true or false, got `{value}`"),
+    }
+}
+
+/// Process environment wins over the `.env` file, matching the other options.
+fn env_value(key: &str, env_map: &HashMap<String, String>) -> Option<String> {
+    first_non_empty(env::var(key).ok(), env_map.get(key).cloned())
+}
+
 fn first_non_empty(first: Option<String>, second: Option<String>) -> Option<String> {
     [first, second]
         .into_iter()
@@ -132,9 +762,67 @@ fn first_non_empty(first: Option<String>, second: Option<String>) -> Option<Stri
 pub fn require_remote(config: &AppConfig) -> Result<&str> {
     let Some(remote) = config.git_remote.as_deref() else {
         bail!(
-            "missing git remote; set TODOS_GIT_REMOTE in {} or environment",
+            "missing git remote; run `todo_md setup <remote-url>` or set TODOS_GIT_REMOTE in {} or environment",
             config.env_file.display()
         );
     };
     Ok(remote)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn env_example_documents_every_key_read_by_load() {
+        let source = include_str!("config.rs");
+        let key_re =
+            regex::Regex::new(r#""((?:TODOS_|GITHUB_)[A-Z_]+)""#).expect("valid key regex");
+        let example = env_example();
+        for captures in key_re.captures_iter(source) {
+            assert!(
+                example.contains(&format!("# {}=", &captures[1])),
+                "{} is missing from ENV_VARS",
+                &captures[1]
+            );
+        }
+        assert!(example
+            .lines()
+            .all(|line| line.is_empty() || line.starts_with('#')));
+    }
+
+    #[test]
+    fn config_dir_prefers_override_then_xdg_then_home() {
+        let home = dirs::home_dir().expect("home dir");
+        let some = |value: &str| Some(value.to_string());
+
+        assert_eq!(
+            config_dir_from(some("/srv/todos"), some("/xdg")).unwrap(),
+            PathBuf::from("/srv/todos")
+        );
+        assert_eq!(
+            config_dir_from(None, some("/xdg")).unwrap(),
+            PathBuf::from("/xdg/todos")
+        );
+        assert_eq!(
+            config_dir_from(some(""), some("~/cfg")).unwrap(),
+            home.join("cfg").join("todos")
+        );
+        assert_eq!(
+            config_dir_from(None, some("  ")).unwrap(),
+            home.join(".config/todos")
+        );
+        assert_eq!(
+            config_dir_from(None, None).unwrap(),
+            home.join(".config/todos")
+        );
+    }
+
+    #[test]
+    fn profile_names_must_be_safe_file_names() {
+        assert_eq!(parse_profile_name("work_2-b").unwrap(), "work_2-b");
+        assert!(parse_profile_name("").is_err());
+        assert!(parse_profile_name("../work").is_err());
+        assert!(parse_profile_name("my work").is_err());
+    }
+}
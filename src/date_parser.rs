@@ -1,38 +1,294 @@
+use std::sync::OnceLock;
+
 use chrono::prelude::*;
-use chrono::{Duration, FixedOffset, NaiveDate, NaiveTime, TimeZone};
+use chrono::{Days, Duration, FixedOffset, Months, NaiveDate, NaiveTime, TimeZone};
+use chrono_tz::Tz;
 use regex::Regex;
-use strsim::normalized_levenshtein;
+use strsim::normalized_damerau_levenshtein;
+
+use crate::recurrence_parser::{last_day_of_month, Rollover};
+use crate::types::{CheckboxStyle, RecurrenceStyle, MONTH_NAMES};
+
+/// Knobs for resolving vague phrases. Installed once per process with
+/// [`configure`]; tests pass them explicitly.
+#[derive(Debug, Clone)]
+pub struct DateOptions {
+    /// Day of the following week that "next week" resolves to.
+    pub next_week_day: Weekday,
+    /// First day of the week (Monday or Sunday); sets where "next week" begins
+    /// and what "end of week" means.
+    pub week_start: Weekday,
+    /// Zone that dates without an explicit suffix are read and written in;
+    /// `None` follows the host.
+    pub timezone: Option<FixedOffset>,
+    /// Named zone (`TODOS_TZ`), ahead of `timezone`. Unlike a fixed offset it
+    /// follows daylight saving, so each date gets the offset in force on it.
+    pub tz: Option<Tz>,
+    /// Time given to dates written without one.
+    pub default_due_time: NaiveTime,
+    /// How completing a recurring todo picks its next due date.
+    pub rollover: Rollover,
+    /// How recurrences are written back to todo.md.
+    pub recurrence_style: RecurrenceStyle,
+    /// How open todos are written back to todo.md.
+    pub checkbox_style: CheckboxStyle,
+    /// How many days past today still count as "due soon".
+    pub due_soon_days: u32,
+    /// Whether near-miss spellings ("tomorow", "wensday") are corrected;
+    /// when off, only exact words and aliases are read.
+    pub fuzzy: bool,
+    /// How numeric dates like `03/04/2026` are read; month first by default.
+    pub date_order: DateOrder,
+    /// How due dates are written back to todo.md.
+    pub due_style: DueStyle,
+    /// Which day a bare time like "9am" lands on.
+    pub bare_time: BareTime,
+    /// The time "eod" stands for.
+    pub end_of_day: NaiveTime,
+}
+
+/// Day given to a time with no date word (`TODOS_BARE_TIME`). A time equal
+/// to the current minute and second has already passed.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum BareTime {
+    /// Today if the time is still ahead, otherwise tomorrow: "9am" typed at
+    /// 8pm means tomorrow morning.
+    #[default]
+    Next,
+    /// Always today, even when that is already past and so overdue.
+    Today,
+}
+
+impl BareTime {
+    pub fn parse(raw: &str) -> Option<BareTime> {
+        match raw.trim().to_ascii_lowercase().as_str() {
+            "next" => Some(BareTime::Next),
+            "today" => Some(BareTime::Today),
+            _ => None,
+        }
+    }
+}
+
+/// Field order of slash-separated numeric dates (`TODOS_DATE_ORDER`). ISO
+/// `2026-03-15` dates are unambiguous and read the same either way.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum DateOrder {
+    /// `03/15/2026` or `3/15`.
+    #[default]
+    MonthFirst,
+    /// `15/03/2026` or `15/3`.
+    DayFirst,
+}
+
+impl DateOrder {
+    pub fn parse(raw: &str) -> Option<DateOrder> {
+        match raw.trim().to_ascii_lowercase().as_str() {
+            "mdy" | "month-first" => Some(DateOrder::MonthFirst),
+            "dmy" | "day-first" => Some(DateOrder::DayFirst),
+            _ => None,
+        }
+    }
+}
+
+/// How `(due: ...)` is written (`TODOS_DUE_STYLE`).
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum DueStyle {
+    /// `2026-03-01 09:00 AM`.
+    #[default]
+    Absolute,
+    /// `tomorrow 9am` for open todos due within the week, absolute otherwise.
+    /// Lossy: the phrase is re-read against the clock on every load, so a
+    /// file left untouched overnight moves "tomorrow" a day later. Only
+    /// safe when something rewrites the file daily.
+    Relative,
+}
+
+impl DueStyle {
+    pub fn parse(raw: &str) -> Option<DueStyle> {
+        match raw.trim().to_ascii_lowercase().as_str() {
+            "absolute" => Some(DueStyle::Absolute),
+            "relative" => Some(DueStyle::Relative),
+            _ => None,
+        }
+    }
+}
+
+impl Default for DateOptions {
+    fn default() -> Self {
+        Self {
+            next_week_day: Weekday::Mon,
+            week_start: Weekday::Mon,
+            timezone: None,
+            tz: None,
+            default_due_time: NaiveTime::from_hms_opt(23, 59, 0).expect("valid time"),
+            rollover: Rollover::default(),
+            recurrence_style: RecurrenceStyle::default(),
+            checkbox_style: CheckboxStyle::default(),
+            due_soon_days: 2,
+            fuzzy: true,
+            date_order: DateOrder::default(),
+            due_style: DueStyle::default(),
+            bare_time: BareTime::default(),
+            end_of_day: NaiveTime::from_hms_opt(17, 0, 0).expect("valid time"),
+        }
+    }
+}
+
+static DATE_OPTIONS: OnceLock<DateOptions> = OnceLock::new();
+
+/// Sets the options used by [`parse_human_datetime`]. Only the first call takes
+/// effect, so it should happen once at startup after config is loaded.
+pub fn configure(options: DateOptions) {
+    let _ = DATE_OPTIONS.set(options);
+}
+
+fn current_options() -> &'static DateOptions {
+    DATE_OPTIONS.get_or_init(DateOptions::default)
+}
 
 pub fn parse_human_datetime(input: &str, now_utc: DateTime<Utc>) -> Option<DateTime<Utc>> {
     if let Ok(parsed) = DateTime::parse_from_rfc3339(input.trim()) {
         return Some(parsed.with_timezone(&Utc));
     }
 
-    let home_tz = Local::now().offset().fix();
-    parse_human_datetime_with_tz(input, now_utc, home_tz)
+    let home_tz = in_home_zone(now_utc).offset().fix();
+    parse_human_datetime_with_tz(input, now_utc, home_tz, current_options())
+}
+
+/// The configured `TODOS_TIMEZONE`, if any.
+pub fn configured_timezone() -> Option<FixedOffset> {
+    current_options().timezone
+}
+
+/// The configured `TODOS_ROLLOVER` mode.
+pub fn configured_rollover() -> Rollover {
+    current_options().rollover
+}
+
+/// The configured `TODOS_RECURRENCE_STYLE`.
+pub fn configured_recurrence_style() -> RecurrenceStyle {
+    current_options().recurrence_style
+}
+
+/// The configured `TODOS_CHECKBOX_STYLE`.
+pub fn configured_checkbox_style() -> CheckboxStyle {
+    current_options().checkbox_style
+}
+
+/// Whether the installed options allow fuzzy matching of words.
+pub fn configured_fuzzy() -> bool {
+    current_options().fuzzy
+}
+
+/// The installed "due soon" window, in days past today.
+pub fn configured_due_soon_days() -> u32 {
+    current_options().due_soon_days
+}
+
+/// The configured `TODOS_DUE_STYLE`.
+pub fn configured_due_style() -> DueStyle {
+    current_options().due_style
+}
+
+/// The configured `TODOS_TZ`, if any.
+pub fn configured_tz() -> Option<Tz> {
+    #[cfg(test)]
+    if let Some(tz) = TEST_HOME_ZONE.get() {
+        return Some(tz);
+    }
+    current_options().tz
+}
+
+#[cfg(test)]
+thread_local! {
+    static TEST_HOME_ZONE: std::cell::Cell<Option<Tz>> = const { std::cell::Cell::new(None) };
+}
+
+/// Makes `tz` the home zone on this test's thread until dropped, as if
+/// `TODOS_TZ` were set, so rendered dates do not depend on the host's `TZ`.
+#[cfg(test)]
+pub(crate) fn pin_home_zone(tz: Tz) -> HomeZoneGuard {
+    HomeZoneGuard(TEST_HOME_ZONE.replace(Some(tz)))
+}
+
+#[cfg(test)]
+pub(crate) struct HomeZoneGuard(Option<Tz>);
+
+#[cfg(test)]
+impl Drop for HomeZoneGuard {
+    fn drop(&mut self) {
+        TEST_HOME_ZONE.set(self.0);
+    }
+}
+
+/// `utc` in the configured zone, falling back to the host's local time.
+pub fn in_home_zone(utc: DateTime<Utc>) -> DateTime<FixedOffset> {
+    if let Some(tz) = configured_tz() {
+        return utc.with_timezone(&tz).fixed_offset();
+    }
+    match configured_timezone() {
+        Some(tz) => utc.with_timezone(&tz),
+        None => utc.with_timezone(&Local).fixed_offset(),
+    }
+}
+
+/// Parses a `TODOS_TZ` value, an IANA name such as `America/New_York`.
+pub fn parse_tz(raw: &str) -> Option<Tz> {
+    raw.trim().parse().ok()
+}
+
+/// Parses a `TODOS_TIMEZONE` value: `UTC` or an offset such as `+05:30`.
+pub fn parse_timezone(raw: &str) -> Option<FixedOffset> {
+    parse_timezone_token(&raw.trim().to_ascii_lowercase(), configured_fuzzy())
 }
 
 fn parse_human_datetime_with_tz(
     input: &str,
     now_utc: DateTime<Utc>,
     home_tz: FixedOffset,
+    options: &DateOptions,
 ) -> Option<DateTime<Utc>> {
     let normalized = normalize_input(input);
     if normalized.is_empty() {
         return None;
     }
 
-    let (value_without_tz, tz) = split_timezone_suffix(&normalized, home_tz);
+    let home_tz = options
+        .tz
+        .map_or(home_tz, |zone| now_utc.with_timezone(&zone).offset().fix());
+    let (value_without_tz, suffix_tz) = split_timezone_suffix(&normalized, options.fuzzy);
+    let tz = suffix_tz.unwrap_or(home_tz);
     let now_local = now_utc.with_timezone(&tz);
+    // An explicit suffix is a fixed offset; otherwise a named zone gives the
+    // target date its own offset, which differs from today's across DST.
+    let localize = |local_naive: NaiveDateTime| -> Option<DateTime<Utc>> {
+        match options.tz.filter(|_| suffix_tz.is_none()) {
+            Some(zone) => Some(zone.from_local_datetime(&local_naive).earliest()?.to_utc()),
+            None => Some(tz.from_local_datetime(&local_naive).single()?.to_utc()),
+        }
+    };
 
-    if let Some(explicit_date) = parse_explicit_date(&value_without_tz) {
-        let (hour, minute, _) = parse_time(&value_without_tz).unwrap_or((23, 59, false));
+    if let Some(explicit_date) = parse_explicit_date(
+        &value_without_tz,
+        now_local.date_naive(),
+        options.date_order,
+    ) {
+        let (hour, minute, _) = requested_time(&value_without_tz, options);
         let local_naive = explicit_date.and_time(NaiveTime::from_hms_opt(hour, minute, 0)?);
-        let local_dt = tz.from_local_datetime(&local_naive).single()?;
-        return Some(local_dt.with_timezone(&Utc));
+        return localize(local_naive);
+    }
+
+    let (hour, minute, has_time) = requested_time(&value_without_tz, options);
+    if let Some(phrase_date) = resolve_phrase(&value_without_tz, now_local.date_naive(), options) {
+        let local_naive = phrase_date.and_time(NaiveTime::from_hms_opt(hour, minute, 0)?);
+        return localize(local_naive);
+    }
+
+    if let Some(offset_date) = resolve_offset(&value_without_tz, now_local.date_naive(), options) {
+        let local_naive = offset_date.and_time(NaiveTime::from_hms_opt(hour, minute, 0)?);
+        return localize(local_naive);
     }
 
-    let (hour, minute, has_time) = parse_time(&value_without_tz).unwrap_or((23, 59, false));
     let target_date = resolve_date(
         &value_without_tz,
         now_local.date_naive(),
@@ -40,14 +296,93 @@ fn parse_human_datetime_with_tz(
         has_time,
         hour,
         minute,
+        options,
     )?;
 
     let local_naive = target_date.and_time(NaiveTime::from_hms_opt(hour, minute, 0)?);
-    let local_dt = tz.from_local_datetime(&local_naive).single()?;
-    Some(local_dt.with_timezone(&Utc))
+    localize(local_naive)
+}
+
+/// `today 5pm`, `tomorrow 9am` or `friday 9:30am` for a due date later this
+/// week, in the home zone; the inverse of [`parse_human_datetime`] for those
+/// cases. `None` when `target` is past, a week or more out, or would not
+/// read back as exactly `target` (e.g. it has seconds).
+pub fn format_relative_due(target: DateTime<Utc>, now: DateTime<Utc>) -> Option<String> {
+    let home_tz = in_home_zone(now).offset().fix();
+    format_relative_due_with_tz(target, now, home_tz, current_options())
+}
+
+fn format_relative_due_with_tz(
+    target: DateTime<Utc>,
+    now: DateTime<Utc>,
+    tz: FixedOffset,
+    options: &DateOptions,
+) -> Option<String> {
+    if target <= now {
+        return None;
+    }
+    let local = target.with_timezone(&tz);
+    let days_ahead = (local.date_naive() - now.with_timezone(&tz).date_naive()).num_days();
+    let day = match days_ahead {
+        0 => "today".to_string(),
+        1 => "tomorrow".to_string(),
+        2..=6 => local.format("%A").to_string().to_lowercase(),
+        _ => return None,
+    };
+    let (pm, hour) = local.time().hour12();
+    let suffix = if pm { "pm" } else { "am" };
+    let time = match local.minute() {
+        0 => format!("{hour}{suffix}"),
+        minute => format!("{hour}:{minute:02}{suffix}"),
+    };
+    let phrase = format!("{day} {time}");
+
+    // Only write what reads back to the same instant right now.
+    (parse_human_datetime_with_tz(&phrase, now, tz, options) == Some(target)).then_some(phrase)
+}
+
+/// Phrases `target` relative to `now`, e.g. "in 2 hours" or "3 days ago",
+/// using the largest unit that fits and rounding to the nearest whole unit.
+pub fn humanize_relative(target: DateTime<Utc>, now: DateTime<Utc>) -> String {
+    let delta_seconds = (target - now).num_seconds();
+    let magnitude = delta_seconds.unsigned_abs() as f64;
+
+    let minutes = (magnitude / 60.0).round() as u64;
+    if minutes == 0 {
+        return "now".to_string();
+    }
+
+    let hours = (magnitude / 3_600.0).round() as u64;
+    let days = (magnitude / 86_400.0).round() as u64;
+    let (amount, unit) = if minutes < 60 {
+        (minutes, "minute")
+    } else if hours < 24 {
+        (hours, "hour")
+    } else if days < 7 {
+        (days, "day")
+    } else if days < 30 {
+        ((days as f64 / 7.0).round() as u64, "week")
+    } else {
+        let months = (days as f64 / 30.44).round() as u64;
+        if months < 12 {
+            (months, "month")
+        } else {
+            ((days as f64 / 365.25).round().max(1.0) as u64, "year")
+        }
+    };
+
+    let plural = if amount == 1 { "" } else { "s" };
+    if delta_seconds > 0 {
+        format!("in {amount} {unit}{plural}")
+    } else {
+        format!("{amount} {unit}{plural} ago")
+    }
 }
 
-fn parse_explicit_date(value: &str) -> Option<NaiveDate> {
+/// `yyyy-mm-dd`, a slash date in `order`, or a month name with a day ("march
+/// 5", "5th mar 2027"). A date without a year is the next such day, today
+/// included.
+fn parse_explicit_date(value: &str, base_date: NaiveDate, order: DateOrder) -> Option<NaiveDate> {
     let ymd_re = Regex::new(r"\b(?P<y>\d{4})-(?P<m>\d{1,2})-(?P<d>\d{1,2})\b").expect("ymd regex");
     if let Some(captures) = ymd_re.captures(value) {
         let year: i32 = captures.name("y")?.as_str().parse().ok()?;
@@ -56,17 +391,67 @@ fn parse_explicit_date(value: &str) -> Option<NaiveDate> {
         return NaiveDate::from_ymd_opt(year, month, day);
     }
 
-    let mdy_re = Regex::new(r"\b(?P<m>\d{1,2})/(?P<d>\d{1,2})/(?P<y>\d{4})\b").expect("mdy regex");
-    if let Some(captures) = mdy_re.captures(value) {
-        let year: i32 = captures.name("y")?.as_str().parse().ok()?;
-        let month: u32 = captures.name("m")?.as_str().parse().ok()?;
-        let day: u32 = captures.name("d")?.as_str().parse().ok()?;
-        return NaiveDate::from_ymd_opt(year, month, day);
+    let slash_re = Regex::new(r"\b(?P<a>\d{1,2})/(?P<b>\d{1,2})(?:/(?P<y>\d{4}))?\b")
+        .expect("slash date regex");
+    if let Some(captures) = slash_re.captures(value) {
+        let a: u32 = captures.name("a")?.as_str().parse().ok()?;
+        let b: u32 = captures.name("b")?.as_str().parse().ok()?;
+        let (month, day) = match order {
+            DateOrder::MonthFirst => (a, b),
+            DateOrder::DayFirst => (b, a),
+        };
+        return dated_or_next(captures.name("y"), base_date, month, day);
+    }
+
+    let month_day_re = Regex::new(
+        r"\b(?:(?P<m1>[a-z]{3,9}) (?P<d1>\d{1,2})(?:st|nd|rd|th)?|(?P<d2>\d{1,2})(?:st|nd|rd|th)? (?:of )?(?P<m2>[a-z]{3,9}))(?:,? (?P<y>\d{4}))?\b",
+    )
+    .expect("month name regex");
+    for captures in month_day_re.captures_iter(value) {
+        let (Some(month), Some(day)) = (
+            captures.name("m1").or(captures.name("m2")),
+            captures.name("d1").or(captures.name("d2")),
+        ) else {
+            continue;
+        };
+        let Some(month) = month_number(month.as_str()) else {
+            continue;
+        };
+        let day: u32 = day.as_str().parse().ok()?;
+        return dated_or_next(captures.name("y"), base_date, month, day);
     }
 
     None
 }
 
+/// The given year, or else the next `month`/`day` on or after `base_date`.
+fn dated_or_next(
+    year: Option<regex::Match<'_>>,
+    base_date: NaiveDate,
+    month: u32,
+    day: u32,
+) -> Option<NaiveDate> {
+    match year {
+        Some(year) => NaiveDate::from_ymd_opt(year.as_str().parse().ok()?, month, day),
+        None => {
+            let this_year = NaiveDate::from_ymd_opt(base_date.year(), month, day)?;
+            if this_year >= base_date {
+                Some(this_year)
+            } else {
+                NaiveDate::from_ymd_opt(base_date.year() + 1, month, day)
+            }
+        }
+    }
+}
+
+/// 1-12 for a month name or an abbreviation of at least three letters.
+fn month_number(word: &str) -> Option<u32> {
+    MONTH_NAMES
+        .iter()
+        .position(|name| word.len() >= 3 && name.starts_with(word))
+        .map(|index| index as u32 + 1)
+}
+
 fn normalize_input(value: &str) -> String {
     value
         .trim()
@@ -77,28 +462,60 @@ fn normalize_input(value: &str) -> String {
         .join(" ")
 }
 
-fn split_timezone_suffix(value: &str, home_tz: FixedOffset) -> (String, FixedOffset) {
+/// Splits a trailing `UTC` or offset off `value`; `None` when there is none.
+fn split_timezone_suffix(value: &str, fuzzy: bool) -> (String, Option<FixedOffset>) {
     let tz_re = Regex::new(r"^(?P<rest>.*?)(?:\s+(?P<tz>utc|gmt|z|[+-]\d{2}:?\d{2}|[a-z]{2,8}))$")
         .expect("timezone parser regex must be valid");
 
     let Some(captures) = tz_re.captures(value) else {
-        return (value.to_string(), home_tz);
+        return (value.to_string(), None);
     };
 
     let tz_raw = captures.name("tz").map(|m| m.as_str()).unwrap_or_default();
-    let Some(tz) = parse_timezone_token(tz_raw) else {
-        return (value.to_string(), home_tz);
+    let Some(tz) = parse_timezone_token(tz_raw, fuzzy) else {
+        return (value.to_string(), None);
     };
     let rest = captures
         .name("rest")
         .map(|m| m.as_str().trim().to_string())
         .unwrap_or_else(|| value.to_string());
 
-    (rest, tz)
+    (rest, Some(tz))
 }
 
-fn parse_timezone_token(token: &str) -> Option<FixedOffset> {
-    let canonical_utc = fuzzy_match(token, &["utc", "gmt", "z"]);
+/// The time written in `value` as `(hour, minute, has_time)`, or the default
+/// due time with `has_time` false.
+fn requested_time(value: &str, options: &DateOptions) -> (u32, u32, bool) {
+    parse_time(value)
+        .or_else(|| named_time(value, options))
+        .unwrap_or(default_time(options))
+}
+
+/// "noon", "midnight" (the start of the day) and "eod". "midnite" is read as
+/// an alias. Short words are matched exactly, or "soon" would read as noon.
+fn named_time(value: &str, options: &DateOptions) -> Option<(u32, u32, bool)> {
+    let time = value
+        .split(' ')
+        .find_map(|token| match named_time_word(token, options.fuzzy)? {
+            "noon" => NaiveTime::from_hms_opt(12, 0, 0),
+            "eod" => Some(options.end_of_day),
+            _ => Some(NaiveTime::MIN),
+        })?;
+    Some((time.hour(), time.minute(), true))
+}
+
+fn named_time_word(token: &str, fuzzy: bool) -> Option<&'static str> {
+    let fuzzy = fuzzy && token.len() > 4;
+    fuzzy_match(token, &["noon", "midnight", "midnite", "eod"], fuzzy)
+}
+
+fn default_time(options: &DateOptions) -> (u32, u32, bool) {
+    let time = options.default_due_time;
+    (time.hour(), time.minute(), false)
+}
+
+fn parse_timezone_token(token: &str, fuzzy: bool) -> Option<FixedOffset> {
+    let canonical_utc = fuzzy_match(token, &["utc", "gmt", "z"], fuzzy);
     if canonical_utc.is_some() {
         return FixedOffset::east_opt(0);
     }
@@ -153,6 +570,105 @@ fn parse_time(value: &str) -> Option<(u32, u32, bool)> {
     Some((hour, minute, true))
 }
 
+/// Vague multi-word ranges, resolved to one concrete day:
+/// - "next week": `next_week_day` within the week after the current one.
+/// - "end of week" / "eow": the last day of the current week.
+/// - "this weekend" / "weekend": the coming Saturday, or today on Sat/Sun.
+/// - "next weekend": the Saturday after "this weekend".
+/// - "end of month" / "eom": the last day of the current month.
+/// - "end of next month": the last day of the following month.
+///
+/// Week boundaries follow `week_start`; weekends are always Saturday/Sunday.
+fn resolve_phrase(value: &str, base_date: NaiveDate, options: &DateOptions) -> Option<NaiveDate> {
+    let phrase_re =
+        Regex::new(r"\b(?P<phrase>next week|next weekend|this weekend|weekend|end of week|eow|end of month|eom|end of next month)\b")
+            .expect("phrase regex");
+    let phrase = phrase_re
+        .captures(value)?
+        .name("phrase")?
+        .as_str()
+        .to_string();
+
+    let days_from_monday = base_date.weekday().num_days_from_monday() as i64;
+    let this_saturday = if days_from_monday >= 5 {
+        base_date - Duration::days(days_from_monday - 5)
+    } else {
+        base_date + Duration::days(5 - days_from_monday)
+    };
+    let week_start_date =
+        base_date - Duration::days(days_since(base_date.weekday(), options.week_start));
+
+    match phrase.as_str() {
+        "next week" => {
+            let next_week_start = week_start_date + Duration::days(7);
+            Some(
+                next_week_start
+                    + Duration::days(days_since(options.next_week_day, options.week_start)),
+            )
+        }
+        "end of week" | "eow" => Some(week_start_date + Duration::days(6)),
+        "this weekend" | "weekend" => Some(this_saturday.max(base_date)),
+        "next weekend" => Some(this_saturday + Duration::days(7)),
+        "end of month" | "eom" => {
+            base_date.with_day(last_day_of_month(base_date.year(), base_date.month())?)
+        }
+        "end of next month" => {
+            let next_month = base_date.with_day(1)?.checked_add_months(Months::new(1))?;
+            next_month.with_day(last_day_of_month(next_month.year(), next_month.month())?)
+        }
+        _ => None,
+    }
+}
+
+/// "in 3 days", "in 2 weeks", "in 1 month", or back-dated as "2 days ago".
+/// Months keep the day of the month where they can and clamp to the last day
+/// otherwise, so "in 1 month" from Jan 31 is the end of February.
+fn resolve_offset(value: &str, base_date: NaiveDate, options: &DateOptions) -> Option<NaiveDate> {
+    let offset_re = Regex::new(r"\b(?P<in>in )?(?P<n>\d+) (?P<unit>[a-z]+)(?P<ago> ago)?\b")
+        .expect("offset regex");
+    let captures = offset_re.captures(value)?;
+    let ago = match (captures.name("in"), captures.name("ago")) {
+        (Some(_), None) => false,
+        (None, Some(_)) => true,
+        _ => return None,
+    };
+    let count: u32 = captures["n"].parse().ok()?;
+    let unit = fuzzy_match(
+        &captures["unit"],
+        &["day", "days", "week", "weeks", "month", "months"],
+        options.fuzzy,
+    )?;
+    let days = match unit.trim_end_matches('s') {
+        "day" => Days::new(count.into()),
+        "week" => Days::new(u64::from(count) * 7),
+        _ if ago => return base_date.checked_sub_months(Months::new(count)),
+        _ => return base_date.checked_add_months(Months::new(count)),
+    };
+    if ago {
+        base_date.checked_sub_days(days)
+    } else {
+        base_date.checked_add_days(days)
+    }
+}
+
+/// Days from `start` forward to `day`, in 0..7.
+fn days_since(day: Weekday, start: Weekday) -> i64 {
+    (day.num_days_from_monday() as i64 - start.num_days_from_monday() as i64).rem_euclid(7)
+}
+
+const DATE_KEYWORDS: &[&str] = &[
+    "today",
+    "tomorrow",
+    "yesterday",
+    "monday",
+    "tuesday",
+    "wednesday",
+    "thursday",
+    "friday",
+    "saturday",
+    "sunday",
+];
+
 fn resolve_date(
     value: &str,
     base_date: NaiveDate,
@@ -160,56 +676,86 @@ fn resolve_date(
     has_time: bool,
     hour: u32,
     minute: u32,
+    options: &DateOptions,
 ) -> Option<NaiveDate> {
+    // "friday at 3pm", "3pm on tuesday": connectors carry no date, and
+    // fuzzy matching must not read them as a day.
     let tokens = Regex::new(r"[a-z]+")
         .expect("token regex")
         .find_iter(value)
         .map(|m| m.as_str().to_string())
+        .filter(|token| !matches!(token.as_str(), "at" | "on" | "am" | "pm"))
         .collect::<Vec<_>>();
 
-    let mut date_keyword: Option<String> = None;
-    for token in tokens {
-        if let Some(keyword) = fuzzy_match(
-            &token,
-            &[
-                "today",
-                "tomorrow",
-                "monday",
-                "tuesday",
-                "wednesday",
-                "thursday",
-                "friday",
-                "saturday",
-                "sunday",
-            ],
-        ) {
-            date_keyword = Some(keyword.to_string());
-            break;
-        }
-    }
+    // "last friday" looks back, e.g. for `completed --since`.
+    let backwards = tokens.iter().any(|token| token == "last");
+    let is_qualifier =
+        |token: &str| fuzzy_match(token, &["this", "next", "last"], options.fuzzy).is_some();
+    let found = tokens
+        .iter()
+        .enumerate()
+        .find_map(|(index, token)| Some((index, fuzzy_match(token, DATE_KEYWORDS, false)?)))
+        .or_else(|| {
+            // A typo is only forgiven when it is the whole date, or "sunny
+            // afternoon" would read as sunday.
+            if !options.fuzzy {
+                return None;
+            }
+            let mut rest = (0..tokens.len()).filter(|&index| {
+                !is_qualifier(&tokens[index])
+                    && named_time_word(&tokens[index], options.fuzzy).is_none()
+            });
+            match (rest.next(), rest.next()) {
+                (Some(index), None) => {
+                    Some((index, fuzzy_match(&tokens[index], DATE_KEYWORDS, true)?))
+                }
+                _ => None,
+            }
+        });
+    let date_keyword = found.map(|(_, keyword)| keyword);
+    let qualifier = found
+        .and_then(|(index, _)| index.checked_sub(1))
+        .and_then(|previous| fuzzy_match(&tokens[previous], &["this", "next"], options.fuzzy));
 
     let requested_time = NaiveTime::from_hms_opt(hour, minute, 0)?;
-    let date = match date_keyword.as_deref() {
+    let date = match date_keyword {
         Some("today") => base_date,
         Some("tomorrow") => base_date + Duration::days(1),
+        Some("yesterday") => base_date - Duration::days(1),
+        Some(day_name) if backwards => {
+            let target_weekday = day_name_to_num(day_name)?;
+            let current_weekday = base_date.weekday().number_from_monday() as i64;
+            let delta_days = match (current_weekday - target_weekday + 7) % 7 {
+                0 => 7,
+                delta => delta,
+            };
+            base_date - Duration::days(delta_days)
+        }
         Some(day_name) => {
             let target_weekday = day_name_to_num(day_name)?;
             let current_weekday = base_date.weekday().number_from_monday() as i64;
             let mut delta_days = (target_weekday - current_weekday + 7) % 7;
-            if delta_days == 0 && (!has_time || requested_time <= now_time) {
+            // "this friday" is the coming one, today included; "next friday"
+            // is a week after the bare weekday.
+            if delta_days == 0
+                && qualifier != Some("this")
+                && (!has_time || requested_time <= now_time)
+            {
                 delta_days = 7;
             }
+            if qualifier == Some("next") {
+                delta_days += 7;
+            }
             base_date + Duration::days(delta_days)
         }
         None => {
-            if has_time {
-                if requested_time <= now_time {
-                    base_date + Duration::days(1)
-                } else {
-                    base_date
-                }
-            } else {
-                base_date
+            // Nothing recognizable: refuse rather than silently picking today.
+            if !has_time {
+                return None;
+            }
+            match options.bare_time {
+                BareTime::Next if requested_time <= now_time => base_date + Duration::days(1),
+                BareTime::Next | BareTime::Today => base_date,
             }
         }
     };
@@ -230,7 +776,9 @@ fn day_name_to_num(day: &str) -> Option<i64> {
     }
 }
 
-fn fuzzy_match<'a>(input: &str, choices: &'a [&'a str]) -> Option<&'a str> {
+/// Exact match first; with `fuzzy`, otherwise the closest choice if it is
+/// close enough.
+fn fuzzy_match<'a>(input: &str, choices: &'a [&'a str], fuzzy: bool) -> Option<&'a str> {
     let normalized = input.trim().to_ascii_lowercase();
     if normalized.is_empty() {
         return None;
@@ -239,11 +787,14 @@ fn fuzzy_match<'a>(input: &str, choices: &'a [&'a str]) -> Option<&'a str> {
     if let Some(exact) = choices.iter().copied().find(|choice| *choice == normalized) {
         return Some(exact);
     }
+    if !fuzzy {
+        return None;
+    }
 
     let mut best_choice = None;
     let mut best_score = 0.0;
     for choice in choices {
-        let score = normalized_levenshtein(&normalized, choice);
+        let score = normalized_damerau_levenshtein(&normalized, choice);
         if score > best_score {
             best_score = score;
             best_choice = Some(*choice);
@@ -273,27 +824,462 @@ mod tests {
 
     #[test]
     fn parses_today_keyword() {
-        let dt = parse_human_datetime_with_tz("today", now_utc(), et()).expect("parse today");
+        let dt = parse_human_datetime_with_tz("today", now_utc(), et(), &DateOptions::default())
+            .expect("parse today");
         assert_eq!(dt.to_rfc3339(), "2026-02-24T04:59:00+00:00");
     }
 
+    #[test]
+    fn named_zone_ignores_the_host_offset() {
+        let options = DateOptions {
+            tz: Some(chrono_tz::America::New_York),
+            ..DateOptions::default()
+        };
+        for host in [
+            et(),
+            FixedOffset::east_opt(0).expect("utc"),
+            FixedOffset::east_opt(9 * 3600).expect("jst"),
+        ] {
+            let parse = |input: &str| {
+                parse_human_datetime_with_tz(input, now_utc(), host, &options)
+                    .expect("parses")
+                    .to_rfc3339()
+            };
+            assert_eq!(parse("9pm"), "2026-02-24T02:00:00+00:00");
+            // After the switch to daylight time on 2026-03-08.
+            assert_eq!(parse("2026-03-10 9pm"), "2026-03-11T01:00:00+00:00");
+            assert_eq!(parse("9pm utc"), "2026-02-23T21:00:00+00:00");
+        }
+    }
+
+    #[test]
+    fn dates_without_a_time_use_the_default_due_time() {
+        let options = DateOptions {
+            default_due_time: NaiveTime::from_hms_opt(9, 0, 0).expect("valid time"),
+            ..DateOptions::default()
+        };
+        // Monday 2026-02-23 13:00 in New York.
+        let dt = parse_human_datetime_with_tz("friday", now_utc(), et(), &options)
+            .expect("parse friday");
+        assert_eq!(dt.to_rfc3339(), "2026-02-27T14:00:00+00:00");
+        // The default is not a written time, so the weekday picks the same
+        // day as with 23:59.
+        let dt = parse_human_datetime_with_tz("monday", now_utc(), et(), &options)
+            .expect("parse monday");
+        assert_eq!(dt.to_rfc3339(), "2026-03-02T14:00:00+00:00");
+    }
+
     #[test]
     fn parses_tomorrow_typo() {
-        let dt = parse_human_datetime_with_tz("tomorow", now_utc(), et()).expect("parse tomorrow");
+        let dt = parse_human_datetime_with_tz("tomorow", now_utc(), et(), &DateOptions::default())
+            .expect("parse tomorrow");
         assert_eq!(dt.to_rfc3339(), "2026-02-25T04:59:00+00:00");
     }
 
+    #[test]
+    fn last_weekday_and_yesterday_look_back() {
+        // Monday 2026-02-23.
+        let now = Utc
+            .with_ymd_and_hms(2026, 2, 23, 18, 0, 0)
+            .single()
+            .expect("now");
+        let tz = FixedOffset::east_opt(0).expect("utc");
+        let options = DateOptions::default();
+        let date = |input: &str| {
+            parse_human_datetime_with_tz(input, now, tz, &options)
+                .expect("parses")
+                .date_naive()
+        };
+
+        assert_eq!(
+            date("last friday"),
+            NaiveDate::from_ymd_opt(2026, 2, 20).expect("date")
+        );
+        assert_eq!(
+            date("last monday"),
+            NaiveDate::from_ymd_opt(2026, 2, 16).expect("date")
+        );
+        assert_eq!(
+            date("yesterday"),
+            NaiveDate::from_ymd_opt(2026, 2, 22).expect("date")
+        );
+        assert_eq!(
+            date("2 days ago"),
+            NaiveDate::from_ymd_opt(2026, 2, 21).expect("date")
+        );
+        assert_eq!(
+            date("1 month ago"),
+            NaiveDate::from_ymd_opt(2026, 1, 23).expect("date")
+        );
+        // Bare weekday names still look forward.
+        assert_eq!(
+            date("friday"),
+            NaiveDate::from_ymd_opt(2026, 2, 27).expect("date")
+        );
+    }
+
+    #[test]
+    fn yesterday_with_a_time_is_that_local_instant() {
+        let options = DateOptions::default();
+        let parse = |input: &str| {
+            parse_human_datetime_with_tz(input, now_utc(), et(), &options).map(|dt| dt.to_rfc3339())
+        };
+        assert_eq!(
+            parse("yesterday 3pm").as_deref(),
+            Some("2026-02-22T20:00:00+00:00")
+        );
+        assert_eq!(
+            parse("yesterdy at 9:30am").as_deref(),
+            Some("2026-02-22T14:30:00+00:00")
+        );
+        assert_eq!(
+            parse("3 days ago 8am").as_deref(),
+            Some("2026-02-20T13:00:00+00:00")
+        );
+    }
+
+    #[test]
+    fn numeric_dates_follow_the_configured_order() {
+        let tz = FixedOffset::east_opt(0).expect("utc");
+        let day_first = DateOptions {
+            date_order: DateOrder::DayFirst,
+            ..DateOptions::default()
+        };
+        let date = |input: &str, options: &DateOptions| {
+            parse_human_datetime_with_tz(input, now_utc(), tz, options).map(|dt| dt.date_naive())
+        };
+        let march_15 = NaiveDate::from_ymd_opt(2026, 3, 15);
+
+        assert_eq!(date("03/15/2026", &DateOptions::default()), march_15);
+        assert_eq!(date("15/03/2026", &DateOptions::default()), None);
+        assert_eq!(date("15/03/2026", &day_first), march_15);
+        assert_eq!(date("03/15/2026", &day_first), None);
+
+        // Without a year: the next such day, so a passed date means next year.
+        assert_eq!(date("15/3", &day_first), march_15);
+        assert_eq!(
+            date("1/2", &DateOptions::default()),
+            NaiveDate::from_ymd_opt(2027, 1, 2)
+        );
+        assert_eq!(DateOrder::parse("DMY"), Some(DateOrder::DayFirst));
+        assert_eq!(DateOrder::parse("ymd"), None);
+    }
+
+    #[test]
+    fn month_names_with_a_day_are_dates() {
+        let tz = FixedOffset::east_opt(0).expect("utc");
+        let parse = |input: &str| {
+            parse_human_datetime_with_tz(input, now_utc(), tz, &DateOptions::default())
+                .map(|dt| dt.to_rfc3339())
+        };
+
+        assert_eq!(
+            parse("march 5").as_deref(),
+            Some("2026-03-05T23:59:00+00:00")
+        );
+        assert_eq!(
+            parse("5th mar 9am").as_deref(),
+            Some("2026-03-05T09:00:00+00:00")
+        );
+        assert_eq!(
+            parse("Sept 1, 2027").as_deref(),
+            Some("2027-09-01T23:59:00+00:00")
+        );
+        assert_eq!(
+            parse("2026-03-01").as_deref(),
+            Some("2026-03-01T23:59:00+00:00")
+        );
+        // Already passed this year, so next year's.
+        assert_eq!(parse("jan 2").as_deref(), Some("2027-01-02T23:59:00+00:00"));
+        assert_eq!(parse("02/01").as_deref(), Some("2027-02-01T23:59:00+00:00"));
+        assert_eq!(
+            parse("february 23").as_deref(),
+            Some("2026-02-23T23:59:00+00:00")
+        );
+        assert_eq!(parse("march 32"), None);
+    }
+
+    #[test]
+    fn at_and_on_connectors_are_ignored() {
+        let tz = FixedOffset::east_opt(0).expect("utc");
+        let options = DateOptions::default();
+        let parse = |input: &str| {
+            parse_human_datetime_with_tz(input, now_utc(), tz, &options)
+                .expect("parses")
+                .to_rfc3339()
+        };
+
+        assert_eq!(parse("friday at 3pm"), "2026-02-27T15:00:00+00:00");
+        assert_eq!(parse("3pm on tuesday"), "2026-02-24T15:00:00+00:00");
+        assert_eq!(parse("on 3/15 at 9:30am"), "2026-03-15T09:30:00+00:00");
+        assert_eq!(parse("tomorrow at 9am"), parse("tomorrow 9am"));
+    }
+
+    #[test]
+    fn relative_due_dates_read_back_as_the_same_instant() {
+        let tz = FixedOffset::east_opt(0).expect("utc");
+        let options = DateOptions::default();
+        let at = |y, m, d, h, min, sec| Utc.with_ymd_and_hms(y, m, d, h, min, sec).unwrap();
+        let format = |target| format_relative_due_with_tz(target, now_utc(), tz, &options);
+
+        for (target, phrase) in [
+            (at(2026, 2, 23, 20, 30, 0), "today 8:30pm"),
+            (at(2026, 2, 24, 9, 0, 0), "tomorrow 9am"),
+            (at(2026, 2, 27, 17, 0, 0), "friday 5pm"),
+        ] {
+            assert_eq!(format(target).as_deref(), Some(phrase));
+            assert_eq!(
+                parse_human_datetime_with_tz(phrase, now_utc(), tz, &options),
+                Some(target)
+            );
+        }
+
+        // Past, a week or more out, or not expressible: stays absolute.
+        assert_eq!(format(at(2026, 2, 23, 9, 0, 0)), None);
+        assert_eq!(format(at(2026, 3, 2, 9, 0, 0)), None);
+        assert_eq!(format(at(2026, 2, 24, 9, 0, 30)), None);
+    }
+
+    #[test]
+    fn relative_due_dates_drift_when_read_later() {
+        let tz = FixedOffset::east_opt(0).expect("utc");
+        let options = DateOptions::default();
+        let target = Utc.with_ymd_and_hms(2026, 2, 24, 9, 0, 0).unwrap();
+        let phrase =
+            format_relative_due_with_tz(target, now_utc(), tz, &options).expect("near future");
+
+        // The same text read a day later lands a day later: the lossiness
+        // that keeps `TODOS_DUE_STYLE=relative` opt-in.
+        let next_day = now_utc() + Duration::days(1);
+        assert_eq!(
+            parse_human_datetime_with_tz(&phrase, next_day, tz, &options),
+            Some(target + Duration::days(1))
+        );
+        assert_eq!(DueStyle::parse("Relative"), Some(DueStyle::Relative));
+        assert_eq!(DueStyle::parse("rfc3339"), None);
+    }
+
+    #[test]
+    fn bare_times_resolve_by_the_configured_rule() {
+        // now_utc() is 18:00:00 exactly.
+        let tz = FixedOffset::east_opt(0).expect("utc");
+        let today_only = DateOptions {
+            bare_time: BareTime::Today,
+            ..DateOptions::default()
+        };
+        let parse = |input: &str, options: &DateOptions| {
+            parse_human_datetime_with_tz(input, now_utc(), tz, options)
+                .expect("parses")
+                .to_rfc3339()
+        };
+
+        // Equal to now counts as passed.
+        assert_eq!(
+            parse("6pm", &DateOptions::default()),
+            "2026-02-24T18:00:00+00:00"
+        );
+        assert_eq!(
+            parse("5:59pm", &DateOptions::default()),
+            "2026-02-24T17:59:00+00:00"
+        );
+        assert_eq!(
+            parse("6:01pm", &DateOptions::default()),
+            "2026-02-23T18:01:00+00:00"
+        );
+
+        assert_eq!(parse("6pm", &today_only), "2026-02-23T18:00:00+00:00");
+        assert_eq!(parse("9am", &today_only), "2026-02-23T09:00:00+00:00");
+        assert_eq!(parse("6:01pm", &today_only), "2026-02-23T18:01:00+00:00");
+        // A date word still wins.
+        assert_eq!(
+            parse("tomorrow 9am", &today_only),
+            "2026-02-24T09:00:00+00:00"
+        );
+        assert_eq!(BareTime::parse("Today"), Some(BareTime::Today));
+    }
+
+    #[test]
+    fn typos_are_rejected_when_fuzzy_matching_is_off() {
+        let strict = DateOptions {
+            fuzzy: false,
+            ..DateOptions::default()
+        };
+        assert_eq!(
+            parse_human_datetime_with_tz("tomorow", now_utc(), et(), &strict),
+            None
+        );
+        assert_eq!(
+            parse_human_datetime_with_tz("tuesdy", now_utc(), et(), &strict),
+            None
+        );
+        assert!(parse_human_datetime_with_tz("tuesday", now_utc(), et(), &strict).is_some());
+        assert_eq!(parse_timezone_token("utcc", false), None);
+        assert!(parse_timezone_token("utcc", true).is_some());
+    }
+
+    #[test]
+    fn parses_relative_offsets() {
+        let options = DateOptions::default();
+        let parse = |input: &str| {
+            parse_human_datetime_with_tz(input, now_utc(), et(), &options).map(|dt| dt.to_rfc3339())
+        };
+        assert_eq!(
+            parse("in 3 days").as_deref(),
+            Some("2026-02-27T04:59:00+00:00")
+        );
+        assert_eq!(
+            parse("in 3 dayz").as_deref(),
+            Some("2026-02-27T04:59:00+00:00")
+        );
+        assert_eq!(
+            parse("in 1 day").as_deref(),
+            Some("2026-02-25T04:59:00+00:00")
+        );
+        assert_eq!(
+            parse("in 2 weeks at 9am").as_deref(),
+            Some("2026-03-09T14:00:00+00:00")
+        );
+        assert_eq!(parse("in 3 fortnights"), None);
+
+        let strict = DateOptions {
+            fuzzy: false,
+            ..DateOptions::default()
+        };
+        assert!(parse_human_datetime_with_tz("in 3 dayz", now_utc(), et(), &strict).is_none());
+    }
+
+    #[test]
+    fn in_months_clamps_to_the_end_of_shorter_months() {
+        let options = DateOptions::default();
+        let utc = FixedOffset::east_opt(0).expect("valid offset");
+        let parse = |input: &str, now: &str| {
+            let now = DateTime::parse_from_rfc3339(now)
+                .expect("valid timestamp")
+                .with_timezone(&Utc);
+            parse_human_datetime_with_tz(input, now, utc, &options).map(|dt| dt.to_rfc3339())
+        };
+        assert_eq!(
+            parse("in 1 month", "2026-01-31T18:00:00Z").as_deref(),
+            Some("2026-02-28T23:59:00+00:00")
+        );
+        assert_eq!(
+            parse("in 1 month", "2028-01-31T18:00:00Z").as_deref(),
+            Some("2028-02-29T23:59:00+00:00")
+        );
+        assert_eq!(
+            parse("in 2 months 8am", "2026-01-31T18:00:00Z").as_deref(),
+            Some("2026-03-31T08:00:00+00:00")
+        );
+    }
+
+    #[test]
+    fn this_and_next_qualify_weekdays() {
+        // Monday 2026-02-23, 13:00 in the fixture zone.
+        let date = |input: &str| {
+            parse_human_datetime_with_tz(input, now_utc(), et(), &DateOptions::default())
+                .map(|dt| dt.with_timezone(&et()).date_naive())
+        };
+        let day = |month: u32, day: u32| NaiveDate::from_ymd_opt(2026, month, day);
+
+        assert_eq!(date("friday"), day(2, 27));
+        assert_eq!(date("this friday"), day(2, 27));
+        assert_eq!(date("next friday"), day(3, 6));
+        assert_eq!(date("nxt friady"), day(3, 6));
+        assert_eq!(date("monday"), day(3, 2));
+        assert_eq!(date("this monday"), day(2, 23));
+        assert_eq!(date("this monday 9am"), day(2, 23));
+        assert_eq!(date("next monday"), day(3, 9));
+        assert_eq!(date("next week"), day(3, 2));
+    }
+
+    #[test]
+    fn named_times_set_the_time_of_day() {
+        let options = DateOptions::default();
+        let parse = |input: &str, options: &DateOptions| {
+            parse_human_datetime_with_tz(input, now_utc(), et(), options).map(|dt| dt.to_rfc3339())
+        };
+
+        assert_eq!(
+            parse("today noon", &options).as_deref(),
+            Some("2026-02-23T17:00:00+00:00")
+        );
+        assert_eq!(
+            parse("friday midnight", &options).as_deref(),
+            Some("2026-02-27T05:00:00+00:00")
+        );
+        assert_eq!(
+            parse("friday midnite", &options).as_deref(),
+            Some("2026-02-27T05:00:00+00:00")
+        );
+        assert_eq!(
+            parse("friday midnigt", &options).as_deref(),
+            Some("2026-02-27T05:00:00+00:00")
+        );
+        assert_eq!(
+            parse("tomorrow eod", &options).as_deref(),
+            Some("2026-02-24T22:00:00+00:00")
+        );
+        // Noon has passed, so a bare "noon" is tomorrow's, and midnight on
+        // today's weekday is a week out.
+        assert_eq!(
+            parse("noon", &options).as_deref(),
+            Some("2026-02-24T17:00:00+00:00")
+        );
+        assert_eq!(
+            parse("monday midnight", &options).as_deref(),
+            Some("2026-03-02T05:00:00+00:00")
+        );
+
+        let late = DateOptions {
+            end_of_day: NaiveTime::from_hms_opt(18, 30, 0).expect("valid time"),
+            ..DateOptions::default()
+        };
+        assert_eq!(
+            parse("tomorrow eod", &late).as_deref(),
+            Some("2026-02-24T23:30:00+00:00")
+        );
+    }
+
     #[test]
     fn parses_weekday_typo() {
-        let dt = parse_human_datetime_with_tz("tuesdy", now_utc(), et()).expect("parse weekday");
+        let dt = parse_human_datetime_with_tz("tuesdy", now_utc(), et(), &DateOptions::default())
+            .expect("parse weekday");
         assert_eq!(dt.to_rfc3339(), "2026-02-25T04:59:00+00:00");
     }
 
+    #[test]
+    fn only_a_lone_word_is_read_as_a_misspelled_day() {
+        let parse = |value: &str| {
+            parse_human_datetime_with_tz(value, now_utc(), et(), &DateOptions::default())
+                .map(|dt| dt.to_rfc3339())
+        };
+        assert_eq!(
+            parse("mondey").as_deref(),
+            Some("2026-03-03T04:59:00+00:00")
+        );
+        assert_eq!(
+            parse("next fridy noon").as_deref(),
+            Some("2026-03-06T17:00:00+00:00")
+        );
+        assert_eq!(parse("monde"), None);
+        assert_eq!(parse("sunny"), None);
+        assert_eq!(parse("tidy"), None);
+        assert_eq!(parse("fridy report"), None);
+        assert_eq!(parse("sundy afternoon"), None);
+        assert_eq!(
+            parse("tidy up friday").as_deref(),
+            Some("2026-02-28T04:59:00+00:00")
+        );
+    }
+
     #[test]
     fn parses_time_with_spacing_variants() {
-        let a = parse_human_datetime_with_tz("9:00PM", now_utc(), et()).expect("parse A");
-        let b = parse_human_datetime_with_tz("9:00 pm", now_utc(), et()).expect("parse B");
-        let c = parse_human_datetime_with_tz("9:00pm", now_utc(), et()).expect("parse C");
+        let a = parse_human_datetime_with_tz("9:00PM", now_utc(), et(), &DateOptions::default())
+            .expect("parse A");
+        let b = parse_human_datetime_with_tz("9:00 pm", now_utc(), et(), &DateOptions::default())
+            .expect("parse B");
+        let c = parse_human_datetime_with_tz("9:00pm", now_utc(), et(), &DateOptions::default())
+            .expect("parse C");
         assert_eq!(a, b);
         assert_eq!(b, c);
         assert_eq!(a.to_rfc3339(), "2026-02-24T02:00:00+00:00");
@@ -301,14 +1287,181 @@ mod tests {
 
     #[test]
     fn parses_time_with_utc_suffix() {
-        let dt = parse_human_datetime_with_tz("9:00PM UTC", now_utc(), et()).expect("parse UTC");
+        let dt =
+            parse_human_datetime_with_tz("9:00PM UTC", now_utc(), et(), &DateOptions::default())
+                .expect("parse UTC");
         assert_eq!(dt.to_rfc3339(), "2026-02-23T21:00:00+00:00");
     }
 
+    #[test]
+    fn resolves_next_week_to_following_monday() {
+        let dt =
+            parse_human_datetime_with_tz("next week", now_utc(), et(), &DateOptions::default())
+                .expect("parse next week");
+        assert_eq!(dt.to_rfc3339(), "2026-03-03T04:59:00+00:00");
+    }
+
+    #[test]
+    fn resolves_next_week_to_configured_day_with_time() {
+        let options = DateOptions {
+            next_week_day: Weekday::Fri,
+            ..DateOptions::default()
+        };
+        let dt = parse_human_datetime_with_tz("next week 9am", now_utc(), et(), &options)
+            .expect("parse next week");
+        assert_eq!(dt.to_rfc3339(), "2026-03-06T14:00:00+00:00");
+    }
+
+    #[test]
+    fn resolves_this_and_next_weekend() {
+        let this =
+            parse_human_datetime_with_tz("this weekend", now_utc(), et(), &DateOptions::default())
+                .expect("parse this weekend");
+        assert_eq!(this.to_rfc3339(), "2026-03-01T04:59:00+00:00");
+
+        let next =
+            parse_human_datetime_with_tz("next weekend", now_utc(), et(), &DateOptions::default())
+                .expect("parse next weekend");
+        assert_eq!(next.to_rfc3339(), "2026-03-08T04:59:00+00:00");
+    }
+
+    #[test]
+    fn next_week_on_sunday_depends_on_week_start() {
+        let sunday = DateTime::parse_from_rfc3339("2026-03-01T18:00:00Z")
+            .expect("valid timestamp")
+            .with_timezone(&Utc);
+        let monday_start = DateOptions::default();
+        let sunday_start = DateOptions {
+            week_start: Weekday::Sun,
+            ..DateOptions::default()
+        };
+
+        let dt = parse_human_datetime_with_tz("next week", sunday, et(), &monday_start)
+            .expect("parse next week");
+        assert_eq!(dt.to_rfc3339(), "2026-03-03T04:59:00+00:00");
+
+        let dt = parse_human_datetime_with_tz("next week", sunday, et(), &sunday_start)
+            .expect("parse next week");
+        assert_eq!(dt.to_rfc3339(), "2026-03-10T04:59:00+00:00");
+    }
+
+    #[test]
+    fn end_of_month_handles_february() {
+        let utc = FixedOffset::east_opt(0).expect("utc");
+        let parse = |input: &str, now: &str| {
+            let now = DateTime::parse_from_rfc3339(now)
+                .expect("valid timestamp")
+                .with_timezone(&Utc);
+            parse_human_datetime_with_tz(input, now, utc, &DateOptions::default())
+                .map(|dt| dt.to_rfc3339())
+        };
+
+        assert_eq!(
+            parse("end of month", "2026-02-10T12:00:00Z").as_deref(),
+            Some("2026-02-28T23:59:00+00:00")
+        );
+        assert_eq!(
+            parse("eom 5pm", "2028-02-10T12:00:00Z").as_deref(),
+            Some("2028-02-29T17:00:00+00:00")
+        );
+        assert_eq!(
+            parse("end of next month", "2028-01-31T12:00:00Z").as_deref(),
+            Some("2028-02-29T23:59:00+00:00")
+        );
+        assert_eq!(
+            parse("end of next month", "2026-12-15T12:00:00Z").as_deref(),
+            Some("2027-01-31T23:59:00+00:00")
+        );
+    }
+
+    #[test]
+    fn end_of_week_follows_week_start() {
+        let monday_start = DateOptions::default();
+        let sunday_start = DateOptions {
+            week_start: Weekday::Sun,
+            ..DateOptions::default()
+        };
+
+        let dt =
+            parse_human_datetime_with_tz("eow", now_utc(), et(), &monday_start).expect("parse eow");
+        assert_eq!(dt.to_rfc3339(), "2026-03-02T04:59:00+00:00");
+
+        let dt = parse_human_datetime_with_tz("end of week", now_utc(), et(), &sunday_start)
+            .expect("parse end of week");
+        assert_eq!(dt.to_rfc3339(), "2026-03-01T04:59:00+00:00");
+    }
+
+    #[test]
+    fn this_weekend_on_sunday_is_today() {
+        let sunday = DateTime::parse_from_rfc3339("2026-03-01T18:00:00Z")
+            .expect("valid timestamp")
+            .with_timezone(&Utc);
+        let dt =
+            parse_human_datetime_with_tz("this weekend", sunday, et(), &DateOptions::default())
+                .expect("parse this weekend");
+        assert_eq!(dt.to_rfc3339(), "2026-03-02T04:59:00+00:00");
+    }
+
+    #[test]
+    fn rejects_unrecognized_input() {
+        assert!(
+            parse_human_datetime_with_tz("blargh", now_utc(), et(), &DateOptions::default())
+                .is_none()
+        );
+    }
+
+    #[test]
+    fn humanizes_future_and_past_offsets() {
+        let now = now_utc();
+        assert_eq!(humanize_relative(now, now), "now");
+        assert_eq!(
+            humanize_relative(now + Duration::minutes(1), now),
+            "in 1 minute"
+        );
+        assert_eq!(
+            humanize_relative(now + Duration::hours(2), now),
+            "in 2 hours"
+        );
+        assert_eq!(
+            humanize_relative(now - Duration::days(3), now),
+            "3 days ago"
+        );
+        assert_eq!(
+            humanize_relative(now + Duration::days(14), now),
+            "in 2 weeks"
+        );
+        assert_eq!(
+            humanize_relative(now - Duration::days(61), now),
+            "2 months ago"
+        );
+        assert_eq!(
+            humanize_relative(now + Duration::days(800), now),
+            "in 2 years"
+        );
+    }
+
+    #[test]
+    fn humanize_rounds_up_into_the_next_unit() {
+        let now = now_utc();
+        assert_eq!(
+            humanize_relative(now + Duration::minutes(59) + Duration::seconds(40), now),
+            "in 1 hour"
+        );
+        assert_eq!(
+            humanize_relative(now - Duration::hours(23) - Duration::minutes(40), now),
+            "1 day ago"
+        );
+    }
+
     #[test]
     fn parses_explicit_local_date_and_time() {
-        let dt = parse_human_datetime_with_tz("2026-02-24 9:00 pm", now_utc(), et())
-            .expect("parse explicit date/time");
+        let dt = parse_human_datetime_with_tz(
+            "2026-02-24 9:00 pm",
+            now_utc(),
+            et(),
+            &DateOptions::default(),
+        )
+        .expect("parse explicit date/time");
         assert_eq!(dt.to_rfc3339(), "2026-02-25T02:00:00+00:00");
     }
 }
@@ -0,0 +1,132 @@
+use std::collections::HashMap;
+
+use anyhow::Result;
+use chrono::SecondsFormat;
+
+use crate::edit::{line_todo_id, remove_todo};
+use crate::frontmatter::frontmatter_line_count;
+use crate::types::Todo;
+
+/// Open todos that read as the same task under different ids, e.g. both
+/// sides of a merge that each added it.
+#[derive(Debug, Clone)]
+pub struct DuplicateCluster {
+    /// In file order. Older lines carry no `created_at`, but `add` appends,
+    /// so the first todo is the oldest and is the one kept.
+    pub todos: Vec<Todo>,
+}
+
+impl DuplicateCluster {
+    pub fn survivor(&self) -> &Todo {
+        &self.todos[0]
+    }
+
+    pub fn extras(&self) -> &[Todo] {
+        &self.todos[1..]
+    }
+}
+
+/// Groups open todos by case-insensitive name and, with `match_due`, by due
+/// date as well. Only groups with more than one todo are returned, ordered
+/// by where their first todo appears.
+pub fn duplicate_clusters(content: &str, match_due: bool) -> Vec<DuplicateCluster> {
+    let mut clusters: Vec<DuplicateCluster> = Vec::new();
+    let mut index_by_key: HashMap<String, usize> = HashMap::new();
+
+    for line in content.lines().skip(frontmatter_line_count(content)) {
+        if line_todo_id(line).is_none() {
+            continue;
+        }
+        let Ok(todo) = Todo::try_from_str(line) else {
+            continue;
+        };
+        if todo.done() {
+            continue;
+        }
+
+        let mut key = todo.name().to_lowercase();
+        if match_due {
+            let due = todo
+                .due_date()
+                .map(|due| due.to_rfc3339_opts(SecondsFormat::Secs, true))
+                .unwrap_or_default();
+            key.push('\t');
+            key.push_str(&due);
+        }
+        match index_by_key.get(&key) {
+            Some(&index) => clusters[index].todos.push(todo),
+            None => {
+                index_by_key.insert(key, clusters.len());
+                clusters.push(DuplicateCluster { todos: vec![todo] });
+            }
+        }
+    }
+
+    clusters.retain(|cluster| cluster.todos.len() > 1);
+    clusters
+}
+
+/// Removes every todo but the survivor of each cluster. Returns the new
+/// content and the removed todos.
+pub fn merge_duplicates(
+    content: &str,
+    clusters: &[DuplicateCluster],
+) -> Result<(String, Vec<Todo>)> {
+    let mut next = content.to_string();
+    let mut removed = Vec::new();
+    for todo in clusters.iter().flat_map(DuplicateCluster::extras) {
+        let (updated, todo) = remove_todo(&next, todo.id())?;
+        next = updated;
+        removed.push(todo);
+    }
+    Ok((next, removed))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CONTENT: &str = "## Home\n\
+        - [_] Call  mom (due: 2026-03-01T12:00:00Z) (id: 123e4567-e89b-12d3-a456-426614174000)\n\
+        - [_] Pay rent (id: 123e4567-e89b-12d3-a456-426614174001)\n\
+        ## Merged from phone\n\
+        - [_] call mom (due: 2026-03-02T12:00:00Z) (id: 123e4567-e89b-12d3-a456-426614174002)\n\
+        - [x] Call mom (id: 123e4567-e89b-12d3-a456-426614174003)\n\
+        - [_] Call mom (due: 2026-03-01T12:00:00Z) (id: 123e4567-e89b-12d3-a456-426614174004)\n";
+
+    fn ids(cluster: &DuplicateCluster) -> Vec<String> {
+        cluster
+            .todos
+            .iter()
+            .map(|todo| todo.id().to_string()[34..].to_string())
+            .collect()
+    }
+
+    #[test]
+    fn clusters_open_todos_by_normalized_name() {
+        let clusters = duplicate_clusters(CONTENT, false);
+        assert_eq!(clusters.len(), 1);
+        assert_eq!(ids(&clusters[0]), ["00", "02", "04"]);
+        assert_eq!(clusters[0].survivor().name(), "Call mom");
+    }
+
+    #[test]
+    fn matching_due_dates_splits_clusters() {
+        let clusters = duplicate_clusters(CONTENT, true);
+        assert_eq!(clusters.len(), 1);
+        assert_eq!(ids(&clusters[0]), ["00", "04"]);
+    }
+
+    #[test]
+    fn merge_keeps_the_first_todo_of_each_cluster() {
+        let clusters = duplicate_clusters(CONTENT, false);
+        let (next, removed) = merge_duplicates(CONTENT, &clusters).expect("merge");
+
+        assert_eq!(removed.len(), 2);
+        assert!(next.contains("426614174000"));
+        assert!(!next.contains("426614174002"));
+        assert!(!next.contains("426614174004"));
+        assert!(next.contains("426614174003"));
+        assert!(duplicate_clusters(&next, false).is_empty());
+    }
+}
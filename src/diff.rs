@@ -1,5 +1,6 @@
 use std::collections::HashSet;
 
+use serde::Serialize;
 use similar::{Algorithm, TextDiff};
 use uuid::Uuid;
 
@@ -7,7 +8,8 @@ use crate::recurrence_parser::is_rollover_due_date;
 use crate::storage::ParsedTodoFile;
 use crate::types::Todo;
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
 pub enum ChangeKind {
     Added,
     Updated,
@@ -15,13 +17,77 @@ pub enum ChangeKind {
     Completed,
 }
 
-#[derive(Debug, Clone)]
+impl ChangeKind {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            ChangeKind::Added => "added",
+            ChangeKind::Updated => "updated",
+            ChangeKind::Deleted => "deleted",
+            ChangeKind::Completed => "completed",
+        }
+    }
+
+    /// Stable report order: added, completed, updated, deleted.
+    fn rank(self) -> u8 {
+        match self {
+            ChangeKind::Added => 0,
+            ChangeKind::Completed => 1,
+            ChangeKind::Updated => 2,
+            ChangeKind::Deleted => 3,
+        }
+    }
+}
+
+/// A field of a todo that an update can change.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TodoField {
+    Done,
+    Name,
+    DueDate,
+    Scheduled,
+    Recurrence,
+    RecurrenceEnd,
+    ExternalId,
+    Priority,
+    Notes,
+}
+
+impl TodoField {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            TodoField::Done => "done",
+            TodoField::Name => "name",
+            TodoField::DueDate => "due_date",
+            TodoField::Scheduled => "scheduled",
+            TodoField::Recurrence => "recurrence",
+            TodoField::RecurrenceEnd => "recurrence_end",
+            TodoField::ExternalId => "external_id",
+            TodoField::Priority => "priority",
+            TodoField::Notes => "notes",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
 pub struct TodoChange {
     pub id: Uuid,
     pub kind: ChangeKind,
+    /// What changed, for updates and completions; empty for added and
+    /// deleted todos.
+    pub fields: Vec<TodoField>,
 }
 
-#[derive(Debug, Clone)]
+/// `due_date, name`: changed fields, comma separated.
+pub fn fields_text(fields: &[TodoField]) -> String {
+    fields
+        .iter()
+        .map(|field| field.as_str())
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
 pub struct ChangeSet {
     pub added: usize,
     pub updated: usize,
@@ -34,6 +100,14 @@ impl ChangeSet {
     pub fn is_empty(&self) -> bool {
         self.added == 0 && self.updated == 0 && self.deleted == 0 && self.completed == 0
     }
+
+    /// Changes grouped by kind (see [`ChangeKind`]) then ordered by id, so
+    /// machine output is stable across runs.
+    pub fn sorted_changes(&self) -> Vec<&TodoChange> {
+        let mut changes = self.changes.iter().collect::<Vec<_>>();
+        changes.sort_by_key(|change| (change.kind.rank(), change.id));
+        changes
+    }
 }
 
 pub fn semantic_changes(previous: &ParsedTodoFile, current: &ParsedTodoFile) -> ChangeSet {
@@ -45,6 +119,7 @@ pub fn semantic_changes(previous: &ParsedTodoFile, current: &ParsedTodoFile) ->
         changes.push(TodoChange {
             id: *id,
             kind: ChangeKind::Added,
+            fields: Vec::new(),
         });
     }
 
@@ -52,6 +127,7 @@ pub fn semantic_changes(previous: &ParsedTodoFile, current: &ParsedTodoFile) ->
         changes.push(TodoChange {
             id: *id,
             kind: ChangeKind::Deleted,
+            fields: Vec::new(),
         });
     }
 
@@ -64,7 +140,8 @@ pub fn semantic_changes(previous: &ParsedTodoFile, current: &ParsedTodoFile) ->
             continue;
         };
 
-        if !todos_differ(previous_todo, current_todo) {
+        let fields = changed_fields(previous_todo, current_todo);
+        if fields.is_empty() {
             continue;
         }
 
@@ -74,7 +151,7 @@ pub fn semantic_changes(previous: &ParsedTodoFile, current: &ParsedTodoFile) ->
             ChangeKind::Updated
         };
 
-        changes.push(TodoChange { id, kind });
+        changes.push(TodoChange { id, kind, fields });
     }
 
     let added = changes
@@ -103,10 +180,19 @@ pub fn semantic_changes(previous: &ParsedTodoFile, current: &ParsedTodoFile) ->
     }
 }
 
-pub fn line_diff_summary(before: &str, after: &str) -> String {
+/// `+added/-removed` line counts. With `exclude_done`, checkboxes are
+/// normalized to open before diffing, so completing or reopening a todo counts
+/// as no change. That hides done-state churn but also real completions, which
+/// the semantic change counts still report.
+pub fn line_diff_summary(before: &str, after: &str, exclude_done: bool) -> String {
+    let (before, after) = if exclude_done {
+        (reopen_lines(before), reopen_lines(after))
+    } else {
+        (before.to_string(), after.to_string())
+    };
     let diff = TextDiff::configure()
         .algorithm(Algorithm::Myers)
-        .diff_lines(before, after);
+        .diff_lines(&before, &after);
 
     let mut added = 0;
     let mut removed = 0;
@@ -118,14 +204,65 @@ pub fn line_diff_summary(before: &str, after: &str) -> String {
         }
     }
 
-    format!("line diff (+{added}/-{removed})")
+    let label = if exclude_done {
+        "line diff excluding done"
+    } else {
+        "line diff"
+    };
+    format!("{label} (+{added}/-{removed})")
+}
+
+fn reopen_lines(content: &str) -> String {
+    content
+        .lines()
+        .map(|line| {
+            let indent = line.len() - line.trim_start().len();
+            match ["- [x]", "- [X]", "- [ ]"]
+                .iter()
+                .find_map(|marker| line[indent..].strip_prefix(marker))
+            {
+                Some(rest) => format!("{}- [_]{rest}\n", &line[..indent]),
+                None => format!("{line}\n"),
+            }
+        })
+        .collect()
 }
 
-fn todos_differ(previous: &Todo, current: &Todo) -> bool {
-    previous.done() != current.done()
-        || previous.due_date() != current.due_date()
-        || previous.recurence() != current.recurence()
-        || previous.name() != current.name()
+/// The fields that differ between two versions of a todo, in a fixed order.
+/// Timestamps are not compared; they change along with the fields they stamp.
+fn changed_fields(previous: &Todo, current: &Todo) -> Vec<TodoField> {
+    [
+        (TodoField::Done, previous.done() != current.done()),
+        (TodoField::Name, previous.name() != current.name()),
+        (
+            TodoField::DueDate,
+            previous.due_date() != current.due_date(),
+        ),
+        (
+            TodoField::Scheduled,
+            previous.scheduled() != current.scheduled(),
+        ),
+        (
+            TodoField::Recurrence,
+            previous.recurence() != current.recurence(),
+        ),
+        (
+            TodoField::RecurrenceEnd,
+            previous.recurrence_end() != current.recurrence_end(),
+        ),
+        (
+            TodoField::ExternalId,
+            previous.external_id() != current.external_id(),
+        ),
+        (
+            TodoField::Priority,
+            previous.priority() != current.priority(),
+        ),
+        (TodoField::Notes, previous.notes() != current.notes()),
+    ]
+    .into_iter()
+    .filter_map(|(field, changed)| changed.then_some(field))
+    .collect()
 }
 
 fn is_completion_transition(previous: &Todo, current: &Todo) -> bool {
@@ -147,13 +284,35 @@ fn is_completion_transition(previous: &Todo, current: &Todo) -> bool {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::storage::ParsedTodoFile;
+    use crate::storage::{parse_todo_content, ParsedTodoFile};
     use std::collections::HashMap;
 
+    #[test]
+    fn excluding_done_ignores_pure_checkbox_toggles() {
+        let before = "- [_] Pay rent (id: 123e4567-e89b-12d3-a456-426614174000)\n\
+                      - [x] Call mom (id: 123e4567-e89b-12d3-a456-426614174001)\n";
+        let after = "- [x] Pay rent (id: 123e4567-e89b-12d3-a456-426614174000)\n\
+                     - [_] Call mom (id: 123e4567-e89b-12d3-a456-426614174001)\n";
+
+        assert_eq!(line_diff_summary(before, after, false), "line diff (+2/-2)");
+        assert_eq!(
+            line_diff_summary(before, after, true),
+            "line diff excluding done (+0/-0)"
+        );
+
+        let renamed = after.replace("Call mom", "Call dad");
+        assert_eq!(
+            line_diff_summary(before, &renamed, true),
+            "line diff excluding done (+1/-1)"
+        );
+    }
+
     #[test]
     fn classifies_added_updated_and_deleted() {
         let old = ParsedTodoFile {
             content: "".to_string(),
+            order: Vec::new(),
+            stamp: None,
             todos_by_id: [
                 (
                     Uuid::parse_str("123e4567-e89b-12d3-a456-426614174000").expect("id"),
@@ -170,6 +329,8 @@ mod tests {
 
         let new = ParsedTodoFile {
             content: "".to_string(),
+            order: Vec::new(),
+            stamp: None,
             todos_by_id: [
                 (
                     Uuid::parse_str("123e4567-e89b-12d3-a456-426614174000").expect("id"),
@@ -195,6 +356,8 @@ mod tests {
     fn classifies_rollover_as_completion() {
         let old = ParsedTodoFile {
             content: "".to_string(),
+            order: Vec::new(),
+            stamp: None,
             todos_by_id: [(
                 Uuid::parse_str("123e4567-e89b-12d3-a456-426614174000").expect("id"),
                 Todo::from_str(
@@ -207,6 +370,8 @@ mod tests {
 
         let new = ParsedTodoFile {
             content: "".to_string(),
+            order: Vec::new(),
+            stamp: None,
             todos_by_id: [(
                 Uuid::parse_str("123e4567-e89b-12d3-a456-426614174000").expect("id"),
                 Todo::from_str(
@@ -221,4 +386,112 @@ mod tests {
         assert_eq!(changes.completed, 1);
         assert_eq!(changes.updated, 0);
     }
+
+    #[test]
+    fn priority_edits_count_as_updates() {
+        let old = parse_todo_content(
+            "- [_] Pay rent (priority: low) (id: 123e4567-e89b-12d3-a456-426614174000)\n",
+        );
+        let new = parse_todo_content(
+            "- [_] Pay rent (priority: p1) (id: 123e4567-e89b-12d3-a456-426614174000)\n",
+        );
+
+        let changes = semantic_changes(&old, &new);
+        assert_eq!(changes.updated, 1);
+        assert_eq!(semantic_changes(&old, &old).updated, 0);
+    }
+
+    #[test]
+    fn excluding_done_treats_both_open_markers_alike() {
+        let before = "- [ ] Pay rent (id: 123e4567-e89b-12d3-a456-426614174000)\n";
+        let after = "- [x] Pay rent (id: 123e4567-e89b-12d3-a456-426614174000)\n";
+        assert_eq!(
+            line_diff_summary(before, after, true),
+            "line diff excluding done (+0/-0)"
+        );
+    }
+
+    #[test]
+    fn due_date_edit_reports_only_due_date() {
+        let old = parse_todo_content(
+            "- [_] Pay rent (due: 2026-03-01T09:00:00Z) (id: 123e4567-e89b-12d3-a456-426614174000)\n",
+        );
+        let moved = parse_todo_content(
+            "- [_] Pay rent (due: 2026-03-02T09:00:00Z) (id: 123e4567-e89b-12d3-a456-426614174000)\n",
+        );
+        let renamed = parse_todo_content(
+            "- [_] Pay the rent (due: 2026-03-02T09:00:00Z) (id: 123e4567-e89b-12d3-a456-426614174000)\n",
+        );
+
+        let changes = semantic_changes(&old, &moved);
+        assert_eq!(changes.changes[0].fields, [TodoField::DueDate]);
+        let changes = semantic_changes(&old, &renamed);
+        assert_eq!(
+            changes.changes[0].fields,
+            [TodoField::Name, TodoField::DueDate]
+        );
+        assert_eq!(fields_text(&changes.changes[0].fields), "name, due_date");
+    }
+
+    #[test]
+    fn note_edits_count_as_updates() {
+        let old = parse_todo_content(
+            "- [_] Pay rent (id: 123e4567-e89b-12d3-a456-426614174000)\n  - landlord: Sam\n",
+        );
+        let edited = parse_todo_content(
+            "- [_] Pay rent (id: 123e4567-e89b-12d3-a456-426614174000)\n  - landlord: Alex\n",
+        );
+        let reindented = parse_todo_content(
+            "- [_] Pay rent (id: 123e4567-e89b-12d3-a456-426614174000)\n    - landlord: Sam\n",
+        );
+
+        assert_eq!(semantic_changes(&old, &edited).updated, 1);
+        assert_eq!(semantic_changes(&old, &reindented).updated, 0);
+    }
+
+    #[test]
+    fn tag_edits_count_as_updates() {
+        let old =
+            parse_todo_content("- [_] Pay rent #home (id: 123e4567-e89b-12d3-a456-426614174000)\n");
+        let retagged = parse_todo_content(
+            "- [_] Pay rent #home #money (id: 123e4567-e89b-12d3-a456-426614174000)\n",
+        );
+        let repeated = parse_todo_content(
+            "- [_] Pay rent #home #home (id: 123e4567-e89b-12d3-a456-426614174000)\n",
+        );
+
+        assert_eq!(semantic_changes(&old, &retagged).updated, 1);
+        assert_eq!(semantic_changes(&old, &repeated).updated, 0);
+    }
+
+    #[test]
+    fn classifies_interval_rollover_as_completion() {
+        let old = parse_todo_content(
+            "- [_] Change filter (due: 2026-02-25T14:00:00Z) (reccurence: every 10 days) (id: 123e4567-e89b-12d3-a456-426614174000)\n",
+        );
+        let rolled = parse_todo_content(
+            "- [_] Change filter (due: 2026-03-07T14:00:00Z) (reccurence: every 10 days) (id: 123e4567-e89b-12d3-a456-426614174000)\n",
+        );
+        let moved = parse_todo_content(
+            "- [_] Change filter (due: 2026-03-06T14:00:00Z) (reccurence: every 10 days) (id: 123e4567-e89b-12d3-a456-426614174000)\n",
+        );
+
+        let changes = semantic_changes(&old, &rolled);
+        assert_eq!((changes.completed, changes.updated), (1, 0));
+        let changes = semantic_changes(&old, &moved);
+        assert_eq!((changes.completed, changes.updated), (0, 1));
+    }
+
+    #[test]
+    fn classifies_quarterly_rollover_as_completion() {
+        let old = parse_todo_content(
+            "- [_] Review budget (due: 2025-11-30T09:00:00Z) (reccurence: quarterly) (id: 123e4567-e89b-12d3-a456-426614174000)\n",
+        );
+        let new = parse_todo_content(
+            "- [_] Review budget (due: 2026-02-28T09:00:00Z) (reccurence: quarterly) (id: 123e4567-e89b-12d3-a456-426614174000)\n",
+        );
+
+        let changes = semantic_changes(&old, &new);
+        assert_eq!((changes.completed, changes.updated), (1, 0));
+    }
 }
@@ -0,0 +1,680 @@
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt;
+
+use anyhow::{anyhow, Result};
+use chrono::prelude::*;
+
+use regex::Regex;
+use uuid::{Uuid, Version};
+
+use crate::date_parser::{humanize_relative, in_home_zone};
+use crate::edit::line_todo_id;
+use crate::frontmatter::frontmatter_line_count;
+use crate::storage::{
+    continuation_blocks, dropped_metadata, leading_whitespace, parse_todo_content,
+    validate_todo_content, validate_todo_issues, ParsedTodoFile,
+};
+use crate::types::{unwrap_id, Todo};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Severity {
+    Warning,
+    Error,
+}
+
+#[derive(Debug, Clone)]
+pub struct Finding {
+    pub severity: Severity,
+    pub message: String,
+}
+
+#[derive(Debug, Clone)]
+pub struct DoctorOptions {
+    /// More open todos than this due on one day triggers a warning; 0 disables.
+    pub max_due_per_day: usize,
+}
+
+impl Default for DoctorOptions {
+    fn default() -> Self {
+        Self { max_due_per_day: 5 }
+    }
+}
+
+impl fmt::Display for Finding {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.severity {
+            Severity::Warning => write!(f, "warning: {}", self.message),
+            Severity::Error => write!(f, "error: {}", self.message),
+        }
+    }
+}
+
+pub fn diagnose(content: &str, options: &DoctorOptions) -> Vec<Finding> {
+    let mut findings = validate_todo_content(content)
+        .into_iter()
+        .map(|message| Finding {
+            severity: Severity::Error,
+            message,
+        })
+        .collect::<Vec<_>>();
+
+    let parsed = parse_todo_content(content);
+    findings.extend(
+        id_version_warnings(content)
+            .into_iter()
+            .chain(name_spacing_warnings(content))
+            .chain(indentation_warnings(content))
+            .chain(id_wrapper_warnings(content))
+            .chain(external_id_warnings(&parsed))
+            .chain(planning_warnings(&parsed, options))
+            .chain(done_with_due_warnings(&parsed, Utc::now()))
+            .map(|message| Finding {
+                severity: Severity::Warning,
+                message,
+            }),
+    );
+
+    findings
+}
+
+/// How many checkbox (`- [`) lines parse cleanly: they match the todo format
+/// and have no validation issue of their own.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ParseCoverage {
+    pub parsed: usize,
+    pub total: usize,
+}
+
+impl ParseCoverage {
+    pub fn percent(&self) -> f64 {
+        if self.total == 0 {
+            return 100.0;
+        }
+        self.parsed as f64 * 100.0 / self.total as f64
+    }
+}
+
+impl fmt::Display for ParseCoverage {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "parse coverage: {}/{} todo lines ({:.1}%)",
+            self.parsed,
+            self.total,
+            self.percent()
+        )
+    }
+}
+
+pub fn parse_coverage(content: &str) -> ParseCoverage {
+    let issue_lines = validate_todo_issues(content)
+        .into_iter()
+        .map(|issue| issue.line)
+        .collect::<BTreeSet<_>>();
+    let mut coverage = ParseCoverage {
+        parsed: 0,
+        total: 0,
+    };
+    for (idx, line) in content
+        .lines()
+        .enumerate()
+        .skip(frontmatter_line_count(content))
+    {
+        if !line.trim_start().starts_with("- [") {
+            continue;
+        }
+        coverage.total += 1;
+        if Todo::try_from_str(line).is_ok() && !issue_lines.contains(&(idx + 1)) {
+            coverage.parsed += 1;
+        }
+    }
+    coverage
+}
+
+/// Decodes one todo line without touching any file, for `todo_md parse`.
+/// Lists what the parser extracted plus any metadata it had to drop.
+pub fn explain_line(line: &str) -> Result<String> {
+    let todo = Todo::try_from_str(line).map_err(|error| anyhow!("{error}"))?;
+    let fmt_date = |value: Option<DateTime<Utc>>| match value {
+        Some(value) => format!(
+            "{} ({})",
+            in_home_zone(value).format("%Y-%m-%d %I:%M %p %:z"),
+            value.to_rfc3339_opts(SecondsFormat::Secs, true)
+        ),
+        None => "-".to_string(),
+    };
+
+    let mut out = format!("name: {}\n", todo.name());
+    out.push_str(&format!(
+        "done: {}\n",
+        if todo.done() { "yes" } else { "no" }
+    ));
+    out.push_str(&format!("due: {}\n", fmt_date(todo.due_date())));
+    out.push_str(&format!("scheduled: {}\n", fmt_date(todo.scheduled())));
+    out.push_str(&format!(
+        "recurrence: {}\n",
+        todo.recurence()
+            .map_or("-".to_string(), |rule| rule.as_str())
+    ));
+    out.push_str(&format!(
+        "external id: {}\n",
+        todo.external_id().unwrap_or("-")
+    ));
+    match line_todo_id(line) {
+        Some(id) => out.push_str(&format!("id: {id}\n")),
+        None => out.push_str("id: - (a fresh one is assigned on every read until saved)\n"),
+    }
+    if !todo.done() && line.trim_start().starts_with("- [x]") {
+        out.push_str("note: checked recurring todo rolls over to its next due date when read\n");
+    }
+    if let Some((_, message)) = dropped_metadata(line, &todo) {
+        out.push_str(&format!("warning: {message}\n"));
+    }
+    Ok(out)
+}
+
+/// What completing `todo` at `now` would do, for `todo_md explain`. Follows
+/// the same rollover rules as [`Todo::complete`].
+pub fn explain_completion(todo: &Todo, now: DateTime<Utc>) -> String {
+    if let (Some(recurrence), Some(due)) = (todo.recurrence_text(), todo.due_date())
+        && let Some(next) = todo.next_occurrence(now)
+    {
+        let mut out = format!(
+            "rolling due date to {} ({}, {}); it stays open and recurs {}\n",
+            in_home_zone(next).format("%Y-%m-%d %I:%M %p"),
+            in_home_zone(next).format("%A"),
+            humanize_relative(next, now),
+            recurrence
+        );
+        if let Some(scheduled) = todo.scheduled() {
+            out.push_str(&format!(
+                "scheduled date moves to {}\n",
+                in_home_zone(scheduled + (next - due)).format("%Y-%m-%d %I:%M %p")
+            ));
+        }
+        return out;
+    }
+
+    if todo.done() {
+        "already done; completing it again leaves it done\n".to_string()
+    } else if let Some(recurrence) = todo.recurrence_text()
+        && todo.due_date().is_none()
+    {
+        format!("marking done; it recurs {recurrence} but has no due date to roll forward\n")
+    } else if let Some(recurrence) = todo.recurrence_text() {
+        format!("marking done; this is the last occurrence of {recurrence}\n")
+    } else {
+        "marking done\n".to_string()
+    }
+}
+
+pub fn has_errors(findings: &[Finding]) -> bool {
+    findings
+        .iter()
+        .any(|finding| finding.severity == Severity::Error)
+}
+
+/// Todo lines whose name has doubled spaces or tabs. They read the same as
+/// the single-spaced name; `fmt` or the next sync rewrites the line.
+pub fn name_spacing_warnings(content: &str) -> Vec<String> {
+    content
+        .lines()
+        .enumerate()
+        .skip(frontmatter_line_count(content))
+        .filter_map(|(idx, line)| {
+            if !line.trim_start().starts_with("- [") {
+                return None;
+            }
+            let todo = Todo::try_from_str(line).ok()?;
+            // Only whitespace is normalized, so a verbatim match means none was.
+            (!line.contains(todo.name().as_str())).then(|| {
+                format!(
+                    "line {}: name has irregular spacing; it is read as `{}` and `todo_md fmt` will rewrite it",
+                    idx + 1,
+                    todo.name()
+                )
+            })
+        })
+        .collect()
+}
+
+/// Ids read through padding or a backtick or quote wrapper, e.g. after an
+/// editor auto-formats the line. `doctor --fix`, `fmt` or the next sync
+/// rewrites them as `(id: <uuid>)`.
+pub fn id_wrapper_warnings(content: &str) -> Vec<String> {
+    let id_re = Regex::new(r"\(id:\s*([^)]*)\)").expect("valid id regex");
+    content
+        .lines()
+        .enumerate()
+        .skip(frontmatter_line_count(content))
+        .filter(|(_, line)| line.trim_start().starts_with("- ["))
+        .filter_map(|(idx, line)| {
+            let raw = id_re.captures(line)?.get(1)?.as_str();
+            let id = unwrap_id(raw);
+            (id != raw && Uuid::parse_str(id).is_ok()).then(|| {
+                format!(
+                    "line {}: malformed id wrapper around {id}; `doctor --fix` rewrites it as `(id: {id})`",
+                    idx + 1
+                )
+            })
+        })
+        .collect()
+}
+
+/// Indented note lines under a todo whose indentation mixes tabs and spaces,
+/// within one line or across the block. Tab width is ambiguous, so whether a
+/// note reads as attached depends on the viewer; `fmt` expands the tabs.
+pub fn indentation_warnings(content: &str) -> Vec<String> {
+    let lines = content.lines().collect::<Vec<_>>();
+    let mut warnings = Vec::new();
+    for (todo_idx, block) in continuation_blocks(content) {
+        let mut first_style: Option<(usize, char)> = None;
+        for idx in block {
+            let indent = leading_whitespace(lines[idx]);
+            if indent.contains('\t') && indent.contains(' ') {
+                warnings.push(format!(
+                    "line {}: indentation mixes tabs and spaces under the todo on line {}",
+                    idx + 1,
+                    todo_idx + 1
+                ));
+                continue;
+            }
+            let style = if indent.contains('\t') { '\t' } else { ' ' };
+            match first_style {
+                None => first_style = Some((idx, style)),
+                Some((first_idx, first)) if first != style => {
+                    warnings.push(format!(
+                        "line {}: indented with {} but line {} under the same todo uses {}",
+                        idx + 1,
+                        indent_name(style),
+                        first_idx + 1,
+                        indent_name(first)
+                    ));
+                }
+                Some(_) => {}
+            }
+        }
+    }
+    warnings
+}
+
+fn indent_name(style: char) -> &'static str {
+    if style == '\t' {
+        "tabs"
+    } else {
+        "spaces"
+    }
+}
+
+/// todo_md generates v4 (random) UUIDs, so other versions usually mean an id
+/// was typed by hand or imported from another tool.
+pub fn id_version_warnings(content: &str) -> Vec<String> {
+    content
+        .lines()
+        .enumerate()
+        .filter_map(|(idx, line)| {
+            let id = line_todo_id(line)?;
+            match id.get_version() {
+                Some(Version::Random) => None,
+                _ => Some(format!(
+                    "line {}: id {id} is not a v4 UUID (version {}); was it hand-entered or imported?",
+                    idx + 1,
+                    id.get_version_num()
+                )),
+            }
+        })
+        .collect()
+}
+
+/// External ids (`(ext: ...)`) shared by several todos. They are references
+/// into another system, so two todos pointing at one ticket is likely a copy.
+pub fn external_id_warnings(parsed: &ParsedTodoFile) -> Vec<String> {
+    let mut by_external_id: BTreeMap<&str, Vec<String>> = BTreeMap::new();
+    for todo in parsed.todos_by_id.values() {
+        if let Some(external_id) = todo.external_id() {
+            by_external_id
+                .entry(external_id)
+                .or_default()
+                .push(todo.name());
+        }
+    }
+
+    by_external_id
+        .into_iter()
+        .filter(|(_, names)| names.len() > 1)
+        .map(|(external_id, mut names)| {
+            names.sort();
+            format!(
+                "external id `{external_id}` is used by {} todos: {}",
+                names.len(),
+                names.join(", ")
+            )
+        })
+        .collect()
+}
+
+/// Heuristics for planning mistakes: overcommitted days, todos deferred past
+/// their due date, and what look like accidental duplicates (same name,
+/// different due dates).
+pub fn planning_warnings(parsed: &ParsedTodoFile, options: &DoctorOptions) -> Vec<String> {
+    let mut warnings = Vec::new();
+    let open = parsed
+        .todos_by_id
+        .values()
+        .filter(|todo| !todo.done())
+        .collect::<Vec<_>>();
+
+    if options.max_due_per_day > 0 {
+        let mut per_day: BTreeMap<NaiveDate, usize> = BTreeMap::new();
+        for todo in open.iter().filter(|todo| !todo.is_someday()) {
+            if let Some(due_date) = todo.due_date() {
+                *per_day
+                    .entry(in_home_zone(due_date).date_naive())
+                    .or_default() += 1;
+            }
+        }
+
+        for (day, count) in per_day {
+            if count > options.max_due_per_day {
+                warnings.push(format!(
+                    "{count} open todos due on {day} (more than {})",
+                    options.max_due_per_day
+                ));
+            }
+        }
+    }
+
+    for todo in open.iter().filter(|todo| todo.is_someday()) {
+        if todo.recurence().is_some() {
+            warnings.push(format!(
+                "`{}` is tagged #someday but recurs; someday items should not repeat",
+                todo.name()
+            ));
+        } else if todo.due_date().is_some() {
+            warnings.push(format!(
+                "`{}` is tagged #someday but has a due date; it will never show as overdue",
+                todo.name()
+            ));
+        }
+    }
+
+    for todo in &open {
+        if let (Some(scheduled), Some(due_date)) = (todo.scheduled(), todo.due_date())
+            && scheduled > due_date
+        {
+            warnings.push(format!(
+                "`{}` is scheduled to start after it is due",
+                todo.name()
+            ));
+        }
+    }
+
+    let mut dues_by_name: BTreeMap<String, BTreeSet<Option<DateTime<Utc>>>> = BTreeMap::new();
+    for todo in &open {
+        dues_by_name
+            .entry(todo.name().trim().to_lowercase())
+            .or_default()
+            .insert(todo.due_date());
+    }
+
+    for (name, dues) in dues_by_name {
+        if dues.len() > 1 {
+            warnings.push(format!(
+                "`{name}` appears {} times with different due dates; possible duplicate",
+                dues.len()
+            ));
+        }
+    }
+
+    warnings
+}
+
+/// Done, non-recurring todos whose due date is still ahead: done but
+/// scheduled, usually a stale edit. A past due date is just history.
+pub fn done_with_due_warnings(parsed: &ParsedTodoFile, now: DateTime<Utc>) -> Vec<String> {
+    let mut warnings = parsed
+        .todos_by_id
+        .values()
+        .filter(|todo| todo.done() && todo.recurence().is_none())
+        .filter_map(|todo| {
+            let due_date = todo.due_date().filter(|due_date| *due_date > now)?;
+            Some(format!(
+                "`{}` is done but still due {}; clear the due date or reopen it",
+                todo.name(),
+                humanize_relative(due_date, now)
+            ))
+        })
+        .collect::<Vec<_>>();
+    warnings.sort();
+    warnings
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::date_parser::pin_home_zone;
+
+    #[test]
+    fn warns_about_mixed_tab_and_space_continuations() {
+        let content = "- [_] Plan trip (id: 123e4567-e89b-42d3-a456-426614174000)\n\
+                       \x20\x20flights booked\n\
+                       \thotel pending\n\
+                       \x20\t visa?\n\
+                       - [_] Taxes (id: 123e4567-e89b-42d3-a456-426614174001)\n\
+                       \tforms in drawer\n\
+                       \tcall accountant\n";
+
+        assert_eq!(
+            indentation_warnings(content),
+            [
+                "line 3: indented with tabs but line 2 under the same todo uses spaces",
+                "line 4: indentation mixes tabs and spaces under the todo on line 1",
+            ]
+        );
+    }
+
+    #[test]
+    fn wrapped_ids_are_warnings_not_errors() {
+        let content = "- [_] Pay rent (id: `123e4567-e89b-42d3-a456-426614174000`)\n\
+                       - [_] Call mom (id: 123e4567-e89b-42d3-a456-426614174001 )\n\
+                       - [_] Book flights (id: 123e4567-e89b-42d3-a456-426614174002)\n";
+
+        let findings = diagnose(content, &DoctorOptions::default());
+        assert!(!has_errors(&findings));
+        assert_eq!(
+            id_wrapper_warnings(content),
+            [
+                "line 1: malformed id wrapper around 123e4567-e89b-42d3-a456-426614174000; `doctor --fix` rewrites it as `(id: 123e4567-e89b-42d3-a456-426614174000)`",
+                "line 2: malformed id wrapper around 123e4567-e89b-42d3-a456-426614174001; `doctor --fix` rewrites it as `(id: 123e4567-e89b-42d3-a456-426614174001)`",
+            ]
+        );
+    }
+
+    #[test]
+    fn warns_about_irregular_name_spacing() {
+        let content = "- [_] Pay  rent (id: 123e4567-e89b-42d3-a456-426614174000)\n\
+                       - [_] Call mom (id: 123e4567-e89b-42d3-a456-426614174001)\n";
+        let warnings = name_spacing_warnings(content);
+        assert_eq!(warnings.len(), 1);
+        assert!(warnings[0].starts_with("line 1: name has irregular spacing"));
+        assert!(warnings[0].contains("`Pay rent`"));
+    }
+
+    #[test]
+    fn warns_about_duplicate_external_ids() {
+        let content = "- [_] Fix login (ext: JIRA-123) (id: 123e4567-e89b-42d3-a456-426614174000)\n\
+                       - [_] Fix login again (ext: JIRA-123) (id: 123e4567-e89b-42d3-a456-426614174001)\n\
+                       - [_] Other (ext: JIRA-124) (id: 123e4567-e89b-42d3-a456-426614174002)\n";
+        assert_eq!(
+            external_id_warnings(&parse_todo_content(content)),
+            ["external id `JIRA-123` is used by 2 todos: Fix login, Fix login again"]
+        );
+    }
+
+    #[test]
+    fn explains_decoded_fields() {
+        let explained = explain_line(
+            "- [_] Pay rent (due: 2026-03-01T09:00:00Z) (reccurence: monthly on the 1st) (id: 123e4567-e89b-42d3-a456-426614174000)",
+        )
+        .expect("parses");
+        assert!(explained.contains("name: Pay rent\n"));
+        assert!(explained.contains("done: no\n"));
+        assert!(explained.contains("(2026-03-01T09:00:00Z)"));
+        assert!(explained.contains("scheduled: -\n"));
+        assert!(explained.contains("recurrence: monthly on 1st\n"));
+        assert!(explained.contains("id: 123e4567-e89b-42d3-a456-426614174000\n"));
+        assert!(!explained.contains("warning:"));
+    }
+
+    #[test]
+    fn explains_what_completion_does() {
+        let _zone = pin_home_zone(chrono_tz::UTC);
+        let now = Utc.with_ymd_and_hms(2026, 2, 23, 18, 0, 0).unwrap();
+        let recurring = Todo::from_str(
+            "- [_] Standup (due: 2026-02-23T09:00:00Z) (scheduled: 2026-02-22T09:00:00Z) (recurrence: weekly on monday) (id: 123e4567-e89b-42d3-a456-426614174000)",
+        );
+        assert_eq!(
+            explain_completion(&recurring, now),
+            "rolling due date to 2026-03-02 09:00 AM (Monday, in 1 week); it stays open and recurs weekly on monday\n\
+             scheduled date moves to 2026-03-01 09:00 AM\n"
+        );
+
+        let plain = Todo::from_str(
+            "- [_] Pay rent (due: 2026-02-28T09:00:00Z) (id: 123e4567-e89b-42d3-a456-426614174001)",
+        );
+        assert_eq!(explain_completion(&plain, now), "marking done\n");
+
+        let undated = Todo::from_str(
+            "- [_] Water plants (recurrence: daily) (id: 123e4567-e89b-42d3-a456-426614174002)",
+        );
+        assert_eq!(
+            explain_completion(&undated, now),
+            "marking done; it recurs daily but has no due date to roll forward\n"
+        );
+    }
+
+    #[test]
+    fn explains_dropped_metadata_and_parse_errors() {
+        let explained =
+            explain_line("- [_] Call mom (due: someday-ish)").expect("line still parses");
+        assert!(explained.contains("due: -\n"));
+        assert!(explained.contains("warning: due date `someday-ish` could not be parsed"));
+        assert!(explained.contains("id: - "));
+
+        let error = explain_line("* Call mom").expect_err("not a todo line");
+        assert_eq!(
+            error.to_string(),
+            "todo line does not match expected format"
+        );
+    }
+
+    #[test]
+    fn warns_when_too_many_todos_share_a_due_day() {
+        let content = "- [_] A (due: 2026-03-01T12:00:00Z) (id: 123e4567-e89b-12d3-a456-426614174000)\n\
+                       - [_] B (due: 2026-03-01T13:00:00Z) (id: 123e4567-e89b-12d3-a456-426614174001)\n\
+                       - [_] C (due: 2026-03-01T14:00:00Z) (id: 123e4567-e89b-12d3-a456-426614174002)\n";
+        let parsed = parse_todo_content(content);
+
+        let strict = planning_warnings(&parsed, &DoctorOptions { max_due_per_day: 2 });
+        assert_eq!(strict.len(), 1);
+        assert!(strict[0].contains("3 open todos due on"));
+
+        let relaxed = planning_warnings(&parsed, &DoctorOptions { max_due_per_day: 3 });
+        assert!(relaxed.is_empty());
+    }
+
+    #[test]
+    fn warns_on_same_name_with_different_due_dates() {
+        let content = "- [_] Renew passport (due: 2026-03-01T12:00:00Z) (id: 123e4567-e89b-42d3-a456-426614174000)\n\
+                       - [_] renew passport (due: 2026-04-01T12:00:00Z) (id: 123e4567-e89b-42d3-a456-426614174001)\n";
+        let findings = diagnose(content, &DoctorOptions::default());
+
+        assert!(!has_errors(&findings));
+        assert_eq!(findings.len(), 1);
+        assert!(findings[0].to_string().starts_with("warning: "));
+        assert!(findings[0].message.contains("possible duplicate"));
+    }
+
+    #[test]
+    fn warns_on_non_v4_ids() {
+        let content = "# Todos\n\
+                       - [_] Generated (id: 123e4567-e89b-42d3-a456-426614174000)\n\
+                       - [_] Hand typed (id: 123e4567-e89b-12d3-a456-426614174001)\n";
+        let findings = diagnose(content, &DoctorOptions::default());
+
+        assert!(!has_errors(&findings));
+        assert_eq!(findings.len(), 1);
+        assert!(findings[0].message.starts_with("line 3: "));
+        assert!(findings[0].message.contains("not a v4 UUID (version 1)"));
+    }
+
+    #[test]
+    fn malformed_id_is_an_error() {
+        let findings = diagnose("- [_] X (id: not-a-uuid)\n", &DoctorOptions::default());
+        assert!(has_errors(&findings));
+    }
+
+    #[test]
+    fn parse_coverage_counts_clean_checkbox_lines() {
+        let content = "# Todos\n\
+                       - [_] Good (id: 123e4567-e89b-42d3-a456-426614174000)\n\
+                       - [_] Bad due (due: blargh) (id: 123e4567-e89b-42d3-a456-426614174001)\n\
+                       - [?] Not a checkbox state (id: 123e4567-e89b-42d3-a456-426614174002)\n\
+                       - [x] Also good (id: 123e4567-e89b-42d3-a456-426614174003)\n";
+        let coverage = parse_coverage(content);
+
+        assert_eq!(
+            coverage,
+            ParseCoverage {
+                parsed: 2,
+                total: 4
+            }
+        );
+        assert_eq!(
+            coverage.to_string(),
+            "parse coverage: 2/4 todo lines (50.0%)"
+        );
+        assert_eq!(parse_coverage("# empty\n").percent(), 100.0);
+    }
+
+    #[test]
+    fn warns_when_scheduled_after_due() {
+        let content = "- [_] Backwards (due: 2026-03-01T12:00:00Z) (scheduled: 2026-03-05T12:00:00Z) (id: 123e4567-e89b-42d3-a456-426614174000)\n";
+        let warnings = planning_warnings(&parse_todo_content(content), &DoctorOptions::default());
+
+        assert_eq!(
+            warnings,
+            ["`Backwards` is scheduled to start after it is due"]
+        );
+    }
+
+    #[test]
+    fn warns_about_recurring_or_dated_someday_todos() {
+        let content = "- [_] Stretch #someday (due: 2026-03-01T12:00:00Z) (reccurence: daily) (id: 123e4567-e89b-42d3-a456-426614174000)\n\
+                       - [_] Novel #someday (due: 2026-03-01T12:00:00Z) (id: 123e4567-e89b-42d3-a456-426614174001)\n\
+                       - [_] Piano #someday (id: 123e4567-e89b-42d3-a456-426614174002)\n";
+        let warnings = planning_warnings(&parse_todo_content(content), &DoctorOptions::default());
+
+        assert_eq!(warnings.len(), 2);
+        assert!(warnings
+            .iter()
+            .any(|w| w.contains("`Stretch #someday`") && w.contains("recurs")));
+        assert!(warnings
+            .iter()
+            .any(|w| w.contains("`Novel #someday`") && w.contains("due date")));
+    }
+
+    #[test]
+    fn warns_about_done_todos_still_due_later() {
+        let content = "- [x] Book flights (due: 2026-03-01T12:00:00Z) (id: 123e4567-e89b-42d3-a456-426614174000)\n\
+                       - [x] Paid rent (due: 2026-02-20T12:00:00Z) (id: 123e4567-e89b-42d3-a456-426614174001)\n\
+                       - [x] Water plants (due: 2026-03-01T12:00:00Z) (reccurence: weekly) (id: 123e4567-e89b-42d3-a456-426614174002)\n\
+                       - [_] Renew passport (due: 2026-03-01T12:00:00Z) (id: 123e4567-e89b-42d3-a456-426614174003)\n";
+        let now = Utc.with_ymd_and_hms(2026, 2, 23, 18, 0, 0).unwrap();
+
+        assert_eq!(
+            done_with_due_warnings(&parse_todo_content(content), now),
+            ["`Book flights` is done but still due in 6 days; clear the due date or reopen it"]
+        );
+    }
+}
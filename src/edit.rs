@@ -0,0 +1,637 @@
+use anyhow::{anyhow, bail, Context, Result};
+use chrono::{DateTime, Datelike, Duration, Utc};
+use uuid::Uuid;
+
+use crate::date_parser::{in_home_zone, parse_human_datetime};
+use crate::frontmatter::frontmatter_line_count;
+use crate::list::is_overdue;
+use crate::recurrence_parser::{parse_reccurence, split_recurrence_end};
+use crate::storage::{
+    continuation_blocks, dropped_metadata, leading_whitespace, validate_todo_content,
+};
+use crate::types::{Reccurence, RecurrenceEnd, Todo};
+
+/// Id of the todo on `line`, if it is a todo line carrying an explicit id.
+pub fn line_todo_id(line: &str) -> Option<Uuid> {
+    if !line.trim_start().starts_with("- [") || !line.contains("(id:") {
+        return None;
+    }
+    Todo::try_from_str(line).ok().map(|todo| todo.id())
+}
+
+/// Rewrites the line holding `id` with `edit` applied to its todo, keeping the
+/// line's indentation and every other line untouched. Returns the new content
+/// and the edited todo.
+pub fn update_todo(
+    content: &str,
+    id: Uuid,
+    edit: impl FnOnce(&mut Todo),
+) -> Result<(String, Todo)> {
+    let mut edit = Some(edit);
+    let mut edited = None;
+    let mut lines = Vec::new();
+
+    for line in content.lines() {
+        if edited.is_none()
+            && line_todo_id(line) == Some(id)
+            && let Ok(mut todo) = Todo::try_from_str(line)
+            && let Some(edit) = edit.take()
+        {
+            edit(&mut todo);
+            let indent = &line[..line.len() - line.trim_start().len()];
+            lines.push(format!("{indent}{}", todo.to_line()));
+            edited = Some(todo);
+            continue;
+        }
+        lines.push(line.to_string());
+    }
+
+    let Some(todo) = edited else {
+        bail!("no todo with id {id} in todo.md");
+    };
+
+    let mut next = lines.join("\n");
+    if content.ends_with('\n') {
+        next.push('\n');
+    }
+    Ok((next, todo))
+}
+
+/// Marks the todo done, or rolls a recurring todo to its next due date.
+pub fn complete_todo(content: &str, id: Uuid) -> Result<(String, Todo)> {
+    update_todo(content, id, Todo::complete)
+}
+
+/// Completes `occurrences` occurrences of a recurring todo at once, e.g. when a
+/// chore was done early for the next date too. Each occurrence rolls the due
+/// date forward once.
+pub fn complete_occurrences(content: &str, id: Uuid, occurrences: u32) -> Result<(String, Todo)> {
+    if occurrences == 0 {
+        bail!("occurrences must be at least 1");
+    }
+    if occurrences > 1 {
+        let current = content
+            .lines()
+            .find(|line| line_todo_id(line) == Some(id))
+            .and_then(|line| Todo::try_from_str(line).ok());
+        if current.is_none_or(|todo| todo.recurence().is_none() || todo.due_date().is_none()) {
+            bail!("completing more than one occurrence needs a recurring todo with a due date");
+        }
+    }
+    update_todo(content, id, |todo| {
+        for _ in 0..occurrences {
+            todo.complete();
+        }
+    })
+}
+
+/// `done --log`: keeps a line per occurrence instead of rolling one line
+/// forward. The todo's line is marked done as the record of this occurrence,
+/// without its recurrence or external id, and the next occurrence goes under
+/// it (after any notes) as a new open todo with a fresh id, so a sync reports
+/// one completed and one added todo. A todo that would not roll forward is
+/// just completed. Returns the new content, the logged todo and the new one.
+pub fn complete_as_log(content: &str, id: Uuid) -> Result<(String, Todo, Option<Todo>)> {
+    let Some(todo) = content
+        .lines()
+        .find(|line| line_todo_id(line) == Some(id))
+        .and_then(|line| Todo::try_from_str(line).ok())
+    else {
+        bail!("no todo with id {id} in todo.md");
+    };
+    let mut rolled = todo.clone();
+    rolled.complete();
+    if rolled.done() {
+        let (next, logged) = complete_todo(content, id)?;
+        return Ok((next, logged, None));
+    }
+
+    let mut upcoming = Todo::new(todo.name());
+    upcoming.set_due_date(rolled.due_date());
+    upcoming.set_scheduled(rolled.scheduled());
+    upcoming.set_recurence(todo.recurence().cloned());
+    upcoming.set_recurrence_end(rolled.recurrence_end());
+    upcoming.set_external_id(todo.external_id().map(str::to_string));
+    upcoming.set_priority(todo.priority());
+
+    let (logged_content, logged) = update_todo(content, id, |todo| {
+        todo.set_recurence(None);
+        todo.set_recurrence_end(None);
+        todo.set_external_id(None);
+        todo.complete();
+    })?;
+    let mut lines = logged_content.lines().collect::<Vec<_>>();
+    let line_idx = lines
+        .iter()
+        .position(|line| line_todo_id(line) == Some(id))
+        .context("completed todo line vanished")?;
+    let insert_at = continuation_blocks(&logged_content)
+        .into_iter()
+        .find(|(idx, _)| *idx == line_idx)
+        .and_then(|(_, notes)| notes.last().copied())
+        .unwrap_or(line_idx)
+        + 1;
+    let line = format!(
+        "{}{}",
+        leading_whitespace(lines[line_idx]),
+        upcoming.to_line()
+    );
+    lines.insert(insert_at, &line);
+    let mut next = lines.join("\n");
+    if content.ends_with('\n') {
+        next.push('\n');
+    }
+    Ok((next, logged, Some(upcoming)))
+}
+
+/// Builds a new todo from `add` text: a name followed by any metadata the
+/// line format takes, e.g. `Pay rent (due: friday) (reccurence: monthly on
+/// 1st)`, with a fresh id. Fails on metadata the parser would drop, on an
+/// `(id: ...)` segment, since `add` assigns the id, and when the canonical
+/// line would not read back as the same todo.
+pub fn todo_from_text(text: &str) -> Result<Todo> {
+    let line = format!("- [_] {}", text.trim());
+    let parsed = Todo::try_from_str(&line).map_err(|error| anyhow!("{error}"))?;
+    if let Some((_, message)) = dropped_metadata(&line, &parsed) {
+        bail!("{message}");
+    }
+    if line.contains("(id:") {
+        bail!("`add` assigns the id itself; drop the `(id: ...)` segment");
+    }
+    if parsed.name().is_empty() {
+        bail!("name must not be empty");
+    }
+
+    let mut todo = Todo::new(parsed.name());
+    todo.set_due_date(parsed.due_date());
+    todo.set_scheduled(parsed.scheduled());
+    todo.set_recurence(parsed.recurence().cloned());
+    todo.set_recurrence_end(parsed.recurrence_end());
+    todo.set_external_id(parsed.external_id().map(str::to_string));
+    todo.set_priority(parsed.priority());
+
+    let written = todo.to_line();
+    let reread = Todo::try_from_str(&written).map_err(|error| anyhow!("{error}"))?;
+    if !validate_todo_content(&written).is_empty()
+        || reread.id() != todo.id()
+        || reread.name() != todo.name()
+        || reread.due_date() != todo.due_date()
+        || reread.scheduled() != todo.scheduled()
+        || reread.recurence() != todo.recurence()
+        || reread.recurrence_end() != todo.recurrence_end()
+        || reread.external_id() != todo.external_id()
+        || reread.priority() != todo.priority()
+    {
+        bail!("`{written}` would not read back as the same todo; nothing was written");
+    }
+    Ok(todo)
+}
+
+/// Appends `todo` as a new line at the end of `content`, ending with a
+/// newline only if `content` did (or was empty).
+pub fn add_todo(content: &str, todo: &Todo) -> String {
+    let mut next = content.to_string();
+    let trailing_newline = next.is_empty() || next.ends_with('\n');
+    if !trailing_newline {
+        next.push('\n');
+    }
+    next.push_str(&todo.to_line());
+    if trailing_newline {
+        next.push('\n');
+    }
+    next
+}
+
+/// Inserts `todo` at the end of the block under the `section` heading (any
+/// level, case-insensitive), above the blank lines that close it. A missing
+/// section is appended as a new `## section` heading holding the todo.
+pub fn add_todo_to_section(content: &str, todo: &Todo, section: &str) -> String {
+    let section = section.trim();
+    let mut lines = content.lines().collect::<Vec<_>>();
+    let start = lines
+        .iter()
+        .enumerate()
+        .skip(frontmatter_line_count(content))
+        .find(|(_, line)| {
+            heading_title(line).is_some_and(|title| title.eq_ignore_ascii_case(section))
+        })
+        .map(|(idx, _)| idx);
+
+    let Some(start) = start else {
+        let mut next = content.to_string();
+        if !next.is_empty() && !next.ends_with('\n') {
+            next.push('\n');
+        }
+        if !next.trim().is_empty() && !next.ends_with("\n\n") {
+            next.push('\n');
+        }
+        next.push_str(&format!("## {section}\n{}", todo.to_line()));
+        if content.is_empty() || content.ends_with('\n') {
+            next.push('\n');
+        }
+        return next;
+    };
+
+    let end = lines[start + 1..]
+        .iter()
+        .position(|line| heading_title(line).is_some())
+        .map_or(lines.len(), |offset| start + 1 + offset);
+    let mut insert_at = end;
+    while insert_at > start + 1 && lines[insert_at - 1].trim().is_empty() {
+        insert_at -= 1;
+    }
+    let line = todo.to_line();
+    lines.insert(insert_at, &line);
+    let mut next = lines.join("\n");
+    if content.ends_with('\n') {
+        next.push('\n');
+    }
+    next
+}
+
+/// Text of a Markdown heading line (`## Work` reads as `Work`).
+fn heading_title(line: &str) -> Option<&str> {
+    let rest = line.trim_start().strip_prefix('#')?.trim_start_matches('#');
+    (rest.is_empty() || rest.starts_with(char::is_whitespace)).then(|| rest.trim())
+}
+
+/// Deletes the line holding `id`. Returns the new content and the removed todo.
+pub fn remove_todo(content: &str, id: Uuid) -> Result<(String, Todo)> {
+    let mut removed = None;
+    let mut lines = Vec::new();
+    for line in content.lines() {
+        if removed.is_none()
+            && line_todo_id(line) == Some(id)
+            && let Ok(todo) = Todo::try_from_str(line)
+        {
+            removed = Some(todo);
+            continue;
+        }
+        lines.push(line);
+    }
+
+    let Some(todo) = removed else {
+        bail!("no todo with id {id} in todo.md");
+    };
+
+    let mut next = lines.join("\n");
+    if content.ends_with('\n') && !next.is_empty() {
+        next.push('\n');
+    }
+    Ok((next, todo))
+}
+
+/// Moves every overdue todo (see [`is_overdue`]) to `to`, in one pass. A scheduled
+/// date moves by the same amount. Recurring todos keep their series: rules
+/// tied to days of the week or month recompute from the rule, and a plain
+/// "monthly" is pinned to its original day first so the next occurrence does
+/// not drift. A plain "yearly" follows the new date. Returns the new content
+/// and the rescheduled todos.
+pub fn reschedule_overdue(
+    content: &str,
+    to: DateTime<Utc>,
+    now: DateTime<Utc>,
+) -> Result<(String, Vec<Todo>)> {
+    let overdue = content
+        .lines()
+        .filter_map(|line| {
+            let id = line_todo_id(line)?;
+            let todo = Todo::try_from_str(line).ok()?;
+            let due = todo.due_date()?;
+            is_overdue(&todo, now).then_some((id, due))
+        })
+        .collect::<Vec<_>>();
+
+    let mut next = content.to_string();
+    let mut moved = Vec::new();
+    for (id, due) in overdue {
+        let (updated, todo) = update_todo(&next, id, |todo| {
+            if todo.recurence() == Some(&Reccurence::Monthly(None)) {
+                todo.set_recurence(Some(Reccurence::Monthly(Some(in_home_zone(due).day()))));
+            }
+            todo.set_scheduled(todo.scheduled().map(|scheduled| scheduled + (to - due)));
+            todo.set_due_date(Some(to));
+        })?;
+        next = updated;
+        moved.push(todo);
+    }
+    Ok((next, moved))
+}
+
+/// A reschedule target: `+<n>d` for n days from today, or any due date phrase.
+pub fn parse_reschedule_target(raw: &str, now: DateTime<Utc>) -> Result<DateTime<Utc>> {
+    if let Some(days) = raw
+        .trim()
+        .strip_prefix('+')
+        .and_then(|rest| rest.strip_suffix('d'))
+    {
+        let days = days
+            .parse::<i64>()
+            .map_err(|_| anyhow!("`{raw}` is not a date or +<n>d"))?;
+        let today =
+            parse_human_datetime("today", now).ok_or_else(|| anyhow!("today is unparseable"))?;
+        return Ok(today + Duration::days(days));
+    }
+    parse_human_datetime(raw, now).ok_or_else(|| anyhow!("`{}` is not a date or +<n>d", raw.trim()))
+}
+
+/// Parses a human due/scheduled date, naming `field` in the error.
+pub fn parse_date_field(field: &str, raw: &str) -> Result<DateTime<Utc>> {
+    parse_human_datetime(raw, Utc::now())
+        .ok_or_else(|| anyhow!("{field} `{}` could not be parsed", raw.trim()))
+}
+
+/// A rule with an optional end condition, e.g. `daily x10`.
+pub fn parse_recurrence_field(raw: &str) -> Result<(Reccurence, Option<RecurrenceEnd>)> {
+    split_recurrence_end(raw)
+        .and_then(|(rule, end)| Some((parse_reccurence(rule, in_home_zone(Utc::now()))?, end)))
+        .ok_or_else(|| anyhow!("recurrence `{}` could not be parsed", raw.trim()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::date_parser::pin_home_zone;
+    use crate::diff::semantic_changes;
+    use crate::storage::parse_todo_content;
+    use crate::types::Priority;
+    use chrono::SecondsFormat;
+
+    const ID: &str = "123e4567-e89b-12d3-a456-426614174001";
+
+    fn utc(raw: &str) -> DateTime<Utc> {
+        DateTime::parse_from_rfc3339(raw)
+            .expect("valid date")
+            .with_timezone(&Utc)
+    }
+
+    #[test]
+    fn reschedules_only_open_overdue_todos() {
+        let content = "- [_] Late (due: 2026-02-20T09:00:00Z) (id: 123e4567-e89b-12d3-a456-426614174001)\n\
+                       - [_] Fine (due: 2026-02-25T09:00:00Z) (id: 123e4567-e89b-12d3-a456-426614174002)\n\
+                       - [x] Done (due: 2026-02-19T09:00:00Z) (id: 123e4567-e89b-12d3-a456-426614174003)\n\
+                       - [_] Undated (id: 123e4567-e89b-12d3-a456-426614174004)\n";
+        let now = utc("2026-02-23T18:00:00Z");
+        let to = utc("2026-02-23T23:59:00Z");
+
+        let (next, moved) = reschedule_overdue(content, to, now).expect("reschedule");
+        assert_eq!(moved.len(), 1);
+        assert_eq!(moved[0].name(), "Late");
+        assert_eq!(moved[0].due_date(), Some(to));
+        assert!(next.contains("Fine (due: 2026-02-25T09:00:00Z)"));
+        assert!(next.contains("Done (due: 2026-02-19T09:00:00Z)"));
+    }
+
+    #[test]
+    fn completing_one_todo_leaves_every_other_line_byte_for_byte() {
+        let content = "---\nformat: 2\n---\n\
+                       # Home  \n\
+                       - [_]   Spaced   out (id: 123e4567-e89b-12d3-a456-426614174000)\n\
+                       \x20   first note\n\
+                       - [_] Pay rent (id: 123e4567-e89b-12d3-a456-426614174001)\n\
+                       \x20     indented note\n\
+                       \x20\t tabbed note\n\
+                       - [_] no id yet (due: whenever\n\
+                       \n\
+                       \x20 - [x] Nested (id: 123e4567-e89b-12d3-a456-426614174002)\n";
+        let id = Uuid::parse_str("123e4567-e89b-12d3-a456-426614174001").expect("id");
+
+        let (next, todo) = update_todo(content, id, Todo::complete).expect("update");
+
+        assert!(todo.done());
+        let (before, after) = (content.lines(), next.lines());
+        assert_eq!(before.clone().count(), after.clone().count());
+        for (index, (old, new)) in before.zip(after).enumerate() {
+            if index == 6 {
+                assert_eq!(new, todo.to_line());
+            } else {
+                assert_eq!(old, new, "line {}", index + 1);
+            }
+        }
+        assert!(next.ends_with('\n'));
+    }
+
+    #[test]
+    fn reschedule_leaves_someday_todos_alone() {
+        let content = "- [_] Novel #someday (due: 2026-02-20T09:00:00Z) (id: 123e4567-e89b-12d3-a456-426614174001)\n";
+        let now = utc("2026-02-23T18:00:00Z");
+
+        let (next, moved) = reschedule_overdue(content, now, now).expect("reschedule");
+        assert!(moved.is_empty());
+        assert_eq!(next, content);
+    }
+
+    #[test]
+    fn rescheduling_recurring_todos_keeps_the_series() {
+        let _zone = pin_home_zone(chrono_tz::UTC);
+        let content = "- [_] Rent (due: 2026-02-15T09:00:00Z) (reccurence: monthly) (id: 123e4567-e89b-12d3-a456-426614174001)\n\
+                       - [_] Gym (due: 2026-02-16T09:00:00Z) (scheduled: 2026-02-15T09:00:00Z) (reccurence: weekly on monday) (id: 123e4567-e89b-12d3-a456-426614174002)\n";
+        let now = utc("2026-02-23T18:00:00Z");
+        let to = utc("2026-02-24T09:00:00Z");
+
+        let (next, moved) = reschedule_overdue(content, to, now).expect("reschedule");
+        assert_eq!(moved.len(), 2);
+
+        // Rent is pinned to the 15th, so completing it lands on 15 March.
+        let mut rent = moved[0].clone();
+        assert_eq!(rent.recurence(), Some(&Reccurence::Monthly(Some(15))));
+        rent.complete();
+        assert_eq!(rent.due_date(), Some(utc("2026-03-15T09:00:00Z")));
+
+        // Gym keeps its Monday rule and its scheduled lead of one day.
+        let mut gym = moved[1].clone();
+        assert_eq!(gym.scheduled(), Some(utc("2026-02-23T09:00:00Z")));
+        gym.complete();
+        assert_eq!(gym.due_date(), Some(utc("2026-03-02T09:00:00Z")));
+        assert!(next.contains("(reccurence: weekly on monday)"));
+    }
+
+    #[test]
+    fn parses_relative_reschedule_targets() {
+        let now = utc("2026-02-23T18:00:00Z");
+        let today = parse_reschedule_target("today", now).expect("today");
+        assert_eq!(
+            parse_reschedule_target("+1d", now).expect("+1d"),
+            today + Duration::days(1)
+        );
+        assert!(parse_reschedule_target("+xd", now).is_err());
+    }
+
+    #[test]
+    fn complete_rewrites_only_the_matching_line() {
+        let content = format!(
+            "# Todos\n  - [_] Write report (id: {ID})\n- [_] Other (id: 123e4567-e89b-12d3-a456-426614174002)\n"
+        );
+        let id = Uuid::parse_str(ID).expect("valid id");
+
+        let (next, todo) = complete_todo(&content, id).expect("complete");
+        assert!(todo.done());
+        let updated = todo.updated_at().to_rfc3339_opts(SecondsFormat::Secs, true);
+        assert_eq!(
+            next,
+            format!(
+                "# Todos\n  - [x] Write report (updated: {updated}) (id: {ID})\n- [_] Other (id: 123e4567-e89b-12d3-a456-426614174002)\n"
+            )
+        );
+    }
+
+    #[test]
+    fn complete_errors_for_unknown_id() {
+        let id = Uuid::parse_str(ID).expect("valid id");
+        assert!(complete_todo("- [_] No id here\n", id).is_err());
+    }
+
+    #[test]
+    fn add_and_remove_round_trip() {
+        let todo = Todo::from_str(&format!("- [_] New (id: {ID})"));
+        let added = add_todo("# Todos\n", &todo);
+        assert_eq!(added, format!("# Todos\n- [_] New (id: {ID})\n"));
+
+        let (removed, gone) = remove_todo(&added, todo.id()).expect("remove");
+        assert_eq!(removed, "# Todos\n");
+        assert_eq!(gone.name(), "New");
+
+        // A file without a final newline stays that way.
+        assert_eq!(
+            add_todo("# Todos", &todo),
+            format!("# Todos\n- [_] New (id: {ID})")
+        );
+    }
+
+    #[test]
+    fn add_text_takes_metadata_hints_and_a_fresh_id() {
+        let _zone = pin_home_zone(chrono_tz::UTC);
+        let todo = todo_from_text(
+            "Call bank (ask about fees) (due: 2026-03-06T17:00:00Z) (reccurence: monthly on 1st) (priority: p2)",
+        )
+        .expect("parses");
+        assert_eq!(todo.name(), "Call bank (ask about fees)");
+        assert_eq!(
+            todo.due_date().map(|due| due.to_rfc3339()),
+            Some("2026-03-06T17:00:00+00:00".to_string())
+        );
+        assert_eq!(todo.recurence(), Some(&Reccurence::Monthly(Some(1))));
+        assert_eq!(todo.priority(), Some(Priority::Medium));
+        assert_ne!(todo_from_text("Call bank").expect("parses").id(), todo.id());
+
+        assert!(todo_from_text("Pay rent (due: someday soon)").is_err());
+        assert!(todo_from_text(&format!("Pay rent (id: {ID})")).is_err());
+        assert!(todo_from_text("Pay rent (priority: urgent)").is_err());
+        assert!(todo_from_text("   ").is_err());
+    }
+
+    #[test]
+    fn log_completion_keeps_a_done_line_and_adds_the_next_occurrence() {
+        let content = format!(
+            "## Home\n\
+             - [_] Water plants (due: 2026-02-23T14:00:00Z) (reccurence: weekly on monday) (id: {ID})\n\
+             \x20\x20south window too\n\
+             - [_] Other (id: 123e4567-e89b-12d3-a456-426614174002)\n"
+        );
+        let id = Uuid::parse_str(ID).expect("valid id");
+        let (next, logged, upcoming) = complete_as_log(&content, id).expect("log");
+        let upcoming = upcoming.expect("recurring todo rolls");
+
+        assert!(logged.done());
+        assert_eq!(logged.recurence(), None);
+        assert_ne!(upcoming.id(), id);
+        assert_eq!(
+            next.lines().collect::<Vec<_>>(),
+            [
+                "## Home",
+                &logged.to_line(),
+                "  south window too",
+                &upcoming.to_line(),
+                "- [_] Other (id: 123e4567-e89b-12d3-a456-426614174002)",
+            ]
+        );
+        assert!(!upcoming.done());
+        assert!(upcoming.recurence().is_some());
+        assert!(upcoming.due_date() > logged.due_date());
+
+        let changes = semantic_changes(&parse_todo_content(&content), &parse_todo_content(&next));
+        assert_eq!((changes.completed, changes.added), (1, 1));
+        assert_eq!(changes.changes.len(), 2);
+    }
+
+    #[test]
+    fn log_completion_of_a_one_off_just_completes_it() {
+        let content = format!("- [_] Call mom (id: {ID})\n");
+        let id = Uuid::parse_str(ID).expect("valid id");
+        let (next, logged, upcoming) = complete_as_log(&content, id).expect("log");
+
+        assert!(upcoming.is_none());
+        assert!(logged.done());
+        let updated = logged
+            .updated_at()
+            .to_rfc3339_opts(SecondsFormat::Secs, true);
+        assert_eq!(
+            next,
+            format!("- [x] Call mom (updated: {updated}) (id: {ID})\n")
+        );
+    }
+
+    #[test]
+    fn adds_to_the_end_of_an_existing_section() {
+        let todo = Todo::from_str(&format!("- [_] New (id: {ID})"));
+        let content = "## Work\n- [_] Report\n  notes\n\n## Home\n- [_] Laundry\n";
+
+        assert_eq!(
+            add_todo_to_section(content, &todo, "work"),
+            format!(
+                "## Work\n- [_] Report\n  notes\n- [_] New (id: {ID})\n\n## Home\n- [_] Laundry\n"
+            )
+        );
+        assert_eq!(
+            add_todo_to_section(content, &todo, "Home"),
+            format!(
+                "## Work\n- [_] Report\n  notes\n\n## Home\n- [_] Laundry\n- [_] New (id: {ID})\n"
+            )
+        );
+    }
+
+    #[test]
+    fn adds_a_missing_section_at_the_end() {
+        let todo = Todo::from_str(&format!("- [_] New (id: {ID})"));
+
+        assert_eq!(
+            add_todo_to_section("## Home\n- [_] Laundry\n", &todo, "Work"),
+            format!("## Home\n- [_] Laundry\n\n## Work\n- [_] New (id: {ID})\n")
+        );
+        assert_eq!(
+            add_todo_to_section("- [_] Laundry #home\n", &todo, "Work"),
+            format!("- [_] Laundry #home\n\n## Work\n- [_] New (id: {ID})\n")
+        );
+        assert_eq!(
+            add_todo_to_section("- [_] Laundry #home", &todo, "Work"),
+            format!("- [_] Laundry #home\n\n## Work\n- [_] New (id: {ID})")
+        );
+        assert_eq!(
+            add_todo_to_section("", &todo, "Work"),
+            format!("## Work\n- [_] New (id: {ID})\n")
+        );
+    }
+
+    #[test]
+    fn completes_several_occurrences_ahead() {
+        let content = format!(
+            "- [_] Water plants (due: 2026-02-23T14:00:00Z) (reccurence: weekly on monday, thursday) (id: {ID})\n"
+        );
+        let id = Uuid::parse_str(ID).expect("valid id");
+
+        let (_, todo) = complete_occurrences(&content, id, 2).expect("complete twice");
+        assert!(!todo.done());
+        assert_eq!(
+            todo.due_date().expect("due").to_rfc3339(),
+            "2026-03-02T14:00:00+00:00"
+        );
+    }
+
+    #[test]
+    fn multiple_occurrences_require_recurrence() {
+        let id = Uuid::parse_str(ID).expect("valid id");
+        let content = format!("- [_] One-off (id: {ID})\n");
+        assert!(complete_occurrences(&content, id, 2).is_err());
+        assert!(complete_occurrences(&content, id, 1).is_ok());
+    }
+}
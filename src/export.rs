@@ -0,0 +1,458 @@
+use std::io::{self, Write};
+use std::process::{Command, Stdio};
+use std::thread;
+
+use anyhow::{bail, Context, Result};
+use chrono::prelude::*;
+
+use crate::date_parser::in_home_zone;
+use crate::types::{
+    DaysOfWeek, IntervalUnit, Reccurence, RecurrenceEnd, Todo, WeekdayOrdinal, WorkdayPosition,
+};
+
+/// Renders todos as Org-mode headings:
+///
+/// ```org
+/// * TODO Pay rent
+///   SCHEDULED: <2026-02-25 Wed 09:00> DEADLINE: <2026-03-01 Sun 09:00 +1m>
+///   :PROPERTIES:
+///   :ID: ...
+///   :RECURRENCE: monthly
+///   :END:
+/// ```
+///
+/// Repeaters go on the timestamps only when Org can express the recurrence
+/// (see [`org_repeater`]); otherwise the todo exports as a one-off and the
+/// `:RECURRENCE:` property keeps the original rule.
+pub fn org_document(todos: &[&Todo]) -> String {
+    let mut out = String::new();
+    for todo in todos {
+        let keyword = if todo.done() { "DONE" } else { "TODO" };
+        out.push_str(&format!("* {keyword} {}\n", todo.name()));
+
+        let repeater = todo.recurence().and_then(org_repeater);
+        let mut planning = Vec::new();
+        if let Some(scheduled) = todo.scheduled() {
+            planning.push(format!(
+                "SCHEDULED: {}",
+                org_timestamp(scheduled, repeater.as_deref())
+            ));
+        }
+        if let Some(due_date) = todo.due_date() {
+            planning.push(format!(
+                "DEADLINE: {}",
+                org_timestamp(due_date, repeater.as_deref())
+            ));
+        }
+        if !planning.is_empty() {
+            out.push_str(&format!("  {}\n", planning.join(" ")));
+        }
+
+        out.push_str("  :PROPERTIES:\n");
+        out.push_str(&format!("  :ID: {}\n", todo.id()));
+        if let Some(reccurence) = todo.recurrence_text() {
+            out.push_str(&format!("  :RECURRENCE: {reccurence}\n"));
+        }
+        out.push_str("  :END:\n");
+    }
+    out
+}
+
+/// Renders todos with a due date as an iCalendar VCALENDAR, one VTODO each.
+/// Undated todos are left out since calendars have nowhere to put them.
+/// Recurrences become an `RRULE` when [`ics_rrule`] can express them;
+/// otherwise the todo exports as a one-off and `X-TODO-MD-RECURRENCE` keeps
+/// the original rule. `now` stamps every VTODO. Recurring todos carry
+/// floating home-zone times so the rule expands on local days, not UTC ones.
+pub fn ics_document(todos: &[&Todo], now: DateTime<Utc>) -> String {
+    let mut lines = vec![
+        "BEGIN:VCALENDAR".to_string(),
+        "VERSION:2.0".to_string(),
+        "PRODID:-//todo_md//todo_md//EN".to_string(),
+    ];
+    for todo in todos {
+        let Some(due_date) = todo.due_date() else {
+            continue;
+        };
+        let rrule = todo
+            .recurence()
+            .and_then(ics_rrule)
+            .map(|rule| match todo.recurrence_end() {
+                Some(end) => format!("{rule};{}", ics_rrule_end(end)),
+                None => rule,
+            });
+
+        let time = |value: DateTime<Utc>| match rrule {
+            Some(_) => ics_local_timestamp(value),
+            None => ics_timestamp(value),
+        };
+
+        lines.push("BEGIN:VTODO".to_string());
+        lines.push(format!("UID:{}", todo.id()));
+        lines.push(format!("DTSTAMP:{}", ics_timestamp(now)));
+        lines.push(format!("SUMMARY:{}", ics_escape(&todo.name())));
+        // RRULE needs a DTSTART to expand from; a scheduled date after the
+        // due date would be rejected, so fall back to the due date.
+        match todo.scheduled().filter(|scheduled| *scheduled <= due_date) {
+            Some(scheduled) => lines.push(format!("DTSTART:{}", time(scheduled))),
+            None if rrule.is_some() => lines.push(format!("DTSTART:{}", time(due_date))),
+            None => {}
+        }
+        lines.push(format!("DUE:{}", time(due_date)));
+        if let Some(rrule) = rrule {
+            lines.push(format!("RRULE:{rrule}"));
+        } else if let Some(reccurence) = todo.recurrence_text() {
+            lines.push(format!("X-TODO-MD-RECURRENCE:{}", ics_escape(&reccurence)));
+        }
+        let status = if todo.done() {
+            "COMPLETED"
+        } else {
+            "NEEDS-ACTION"
+        };
+        lines.push(format!("STATUS:{status}"));
+        lines.push("END:VTODO".to_string());
+    }
+    lines.push("END:VCALENDAR".to_string());
+
+    lines.iter().map(|line| ics_fold(line)).collect()
+}
+
+/// The `RRULE` value for a recurrence, without any end condition. Every rule
+/// maps except that monthly and yearly dates past the 28th are skipped in
+/// short months by calendars, where todo_md clamps them.
+pub fn ics_rrule(reccurence: &Reccurence) -> Option<String> {
+    let rule = match reccurence {
+        Reccurence::Daily => "FREQ=DAILY".to_string(),
+        Reccurence::Weekly(days) if days.is_empty() => return None,
+        Reccurence::Weekly(days) => format!(
+            "FREQ=WEEKLY;BYDAY={}",
+            days.iter()
+                .map(|day| ics_weekday(*day))
+                .collect::<Vec<_>>()
+                .join(",")
+        ),
+        Reccurence::Monthly(None) => "FREQ=MONTHLY".to_string(),
+        Reccurence::Monthly(Some(day)) => format!("FREQ=MONTHLY;BYMONTHDAY={day}"),
+        Reccurence::MonthlyWorkday(position) => format!(
+            "FREQ=MONTHLY;BYDAY=MO,TU,WE,TH,FR;BYSETPOS={}",
+            match position {
+                WorkdayPosition::First => 1,
+                WorkdayPosition::Last => -1,
+            }
+        ),
+        Reccurence::MonthlyLastDay => "FREQ=MONTHLY;BYMONTHDAY=-1".to_string(),
+        Reccurence::MonthlyWeekday { ordinal, weekday } => format!(
+            "FREQ=MONTHLY;BYDAY={}{}",
+            ics_ordinal(*ordinal),
+            ics_weekday(*weekday)
+        ),
+        Reccurence::Quarterly => "FREQ=MONTHLY;INTERVAL=3".to_string(),
+        Reccurence::Yearly => "FREQ=YEARLY".to_string(),
+        Reccurence::YearlyWeekday {
+            month,
+            ordinal,
+            weekday,
+        } => format!(
+            "FREQ=YEARLY;BYMONTH={month};BYDAY={}{}",
+            ics_ordinal(*ordinal),
+            ics_weekday(*weekday)
+        ),
+        Reccurence::Interval { every, unit } => {
+            let freq = match unit {
+                IntervalUnit::Day => "DAILY",
+                IntervalUnit::Week => "WEEKLY",
+                IntervalUnit::Month => "MONTHLY",
+            };
+            format!("FREQ={freq};INTERVAL={every}")
+        }
+    };
+    Some(rule)
+}
+
+/// `UNTIL` must be a UTC date-time when `DTSTART` is one, so the home-zone
+/// last day is closed at its final second in the due date's offset.
+/// `UNTIL` is floating to match the floating `DTSTART` of recurring todos.
+fn ics_rrule_end(end: RecurrenceEnd) -> String {
+    match end {
+        RecurrenceEnd::Count(count) => format!("COUNT={count}"),
+        RecurrenceEnd::Until(date) => format!("UNTIL={}T235959", date.format("%Y%m%d")),
+    }
+}
+
+fn ics_weekday(day: DaysOfWeek) -> &'static str {
+    match day {
+        DaysOfWeek::Monday => "MO",
+        DaysOfWeek::Tuesday => "TU",
+        DaysOfWeek::Wednesday => "WE",
+        DaysOfWeek::Thursday => "TH",
+        DaysOfWeek::Friday => "FR",
+        DaysOfWeek::Saturday => "SA",
+        DaysOfWeek::Sunday => "SU",
+    }
+}
+
+fn ics_ordinal(ordinal: WeekdayOrdinal) -> i8 {
+    match ordinal {
+        WeekdayOrdinal::First => 1,
+        WeekdayOrdinal::Second => 2,
+        WeekdayOrdinal::Third => 3,
+        WeekdayOrdinal::Fourth => 4,
+        WeekdayOrdinal::Last => -1,
+    }
+}
+
+fn ics_timestamp(value: DateTime<Utc>) -> String {
+    value.format("%Y%m%dT%H%M%SZ").to_string()
+}
+
+fn ics_local_timestamp(value: DateTime<Utc>) -> String {
+    in_home_zone(value).format("%Y%m%dT%H%M%S").to_string()
+}
+
+fn ics_escape(text: &str) -> String {
+    text.replace('\\', "\\\\")
+        .replace(';', "\\;")
+        .replace(',', "\\,")
+        .replace('\n', "\\n")
+}
+
+/// Ends `line` with CRLF, folding it so no physical line exceeds 75 octets.
+fn ics_fold(line: &str) -> String {
+    let mut out = String::new();
+    let mut width = 0;
+    for ch in line.chars() {
+        if width + ch.len_utf8() > 75 {
+            out.push_str("\r\n ");
+            width = 1;
+        }
+        out.push(ch);
+        width += ch.len_utf8();
+    }
+    out.push_str("\r\n");
+    out
+}
+
+/// Runs `command` through `sh -c` with `input` (the [`export_lines`] JSONL)
+/// on stdin and returns what it printed, so users can write their own
+/// formats. Its stderr passes straight through. `TODO_MD_SCHEMA_VERSION`
+/// tells it which JSON fields to expect.
+///
+/// [`export_lines`]: crate::json::export_lines
+pub fn export_via(command: &str, input: &str) -> Result<Vec<u8>> {
+    let mut child = Command::new("sh")
+        .args(["-c", command])
+        .env(
+            "TODO_MD_SCHEMA_VERSION",
+            crate::json::SCHEMA_VERSION.to_string(),
+        )
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .spawn()
+        .with_context(|| format!("failed to run export command `{command}`"))?;
+
+    // Feed stdin from a thread so a command that writes before it has read
+    // everything cannot deadlock against us.
+    let mut stdin = child
+        .stdin
+        .take()
+        .context("export command stdin unavailable")?;
+    let input = input.to_string();
+    let writer = thread::spawn(move || match stdin.write_all(input.as_bytes()) {
+        // Commands may stop reading early; that is theirs to decide.
+        Err(error) if error.kind() == io::ErrorKind::BrokenPipe => Ok(()),
+        other => other,
+    });
+    let output = child
+        .wait_with_output()
+        .with_context(|| format!("failed to wait for export command `{command}`"))?;
+    writer
+        .join()
+        .map_err(|_| anyhow::anyhow!("export input writer panicked"))?
+        .with_context(|| format!("failed to write todos to export command `{command}`"))?;
+
+    match output.status.code() {
+        Some(0) => Ok(output.stdout),
+        Some(127) => bail!("export command `{command}` was not found"),
+        _ => bail!("export command `{command}` exited with {}", output.status),
+    }
+}
+
+/// Org repeaters shift by a fixed interval from the timestamp, like completing
+/// a todo_md todo does. Weekly rules on several days, nth-weekday,
+/// first/last-workday and last-day-of-month rules have no fixed interval, so
+/// they are not representable. Monthly on a specific day maps to `+1m`, which
+/// Org does not clamp to short months.
+pub fn org_repeater(reccurence: &Reccurence) -> Option<String> {
+    let repeater = match reccurence {
+        Reccurence::Daily => "+1d".to_string(),
+        Reccurence::Weekly(days) if days.len() == 1 => "+1w".to_string(),
+        Reccurence::Weekly(_) => return None,
+        Reccurence::Monthly(_) => "+1m".to_string(),
+        Reccurence::MonthlyWorkday(_)
+        | Reccurence::MonthlyLastDay
+        | Reccurence::MonthlyWeekday { .. } => return None,
+        Reccurence::Quarterly => "+3m".to_string(),
+        Reccurence::Yearly => "+1y".to_string(),
+        Reccurence::YearlyWeekday { .. } => return None,
+        Reccurence::Interval { every, unit } => format!("+{every}{}", &unit.as_str()[..1]),
+    };
+    Some(repeater)
+}
+
+fn org_timestamp(value: DateTime<Utc>, repeater: Option<&str>) -> String {
+    let local = in_home_zone(value);
+    match repeater {
+        Some(repeater) => format!("<{} {repeater}>", local.format("%Y-%m-%d %a %H:%M")),
+        None => format!("<{}>", local.format("%Y-%m-%d %a %H:%M")),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::date_parser::pin_home_zone;
+
+    #[test]
+    fn org_document_snapshot() {
+        let _zone = pin_home_zone(chrono_tz::UTC);
+        let rent = Todo::from_str(
+            "- [_] Pay rent (due: 2026-03-01T09:00:00Z) (scheduled: 2026-02-25T09:00:00Z) (reccurence: monthly) (id: 123e4567-e89b-42d3-a456-426614174000)",
+        );
+        let gym = Todo::from_str(
+            "- [_] Gym (due: 2026-02-23T18:00:00Z) (reccurence: weekly on monday, wednesday, friday) (id: 123e4567-e89b-42d3-a456-426614174001)",
+        );
+        let done = Todo::from_str("- [x] Book flights (id: 123e4567-e89b-42d3-a456-426614174002)");
+
+        let expected = "\
+* TODO Pay rent
+  SCHEDULED: <2026-02-25 Wed 09:00 +1m> DEADLINE: <2026-03-01 Sun 09:00 +1m>
+  :PROPERTIES:
+  :ID: 123e4567-e89b-42d3-a456-426614174000
+  :RECURRENCE: monthly
+  :END:
+* TODO Gym
+  DEADLINE: <2026-02-23 Mon 18:00>
+  :PROPERTIES:
+  :ID: 123e4567-e89b-42d3-a456-426614174001
+  :RECURRENCE: weekly on monday, wednesday, friday
+  :END:
+* DONE Book flights
+  :PROPERTIES:
+  :ID: 123e4567-e89b-42d3-a456-426614174002
+  :END:
+";
+        assert_eq!(org_document(&[&rent, &gym, &done]), expected);
+    }
+
+    #[test]
+    fn ics_document_exports_dated_todos_with_rrules() {
+        let _zone = pin_home_zone(chrono_tz::UTC);
+        let gym = Todo::from_str(
+            "- [_] Gym, legs (due: 2026-02-23T18:00:00Z) (reccurence: weekly on monday, thursday x4) (id: 123e4567-e89b-42d3-a456-426614174001)",
+        );
+        let undated = Todo::from_str("- [_] Read more (id: 123e4567-e89b-42d3-a456-426614174002)");
+        let now = Utc.with_ymd_and_hms(2026, 2, 23, 18, 0, 0).unwrap();
+
+        let expected = "BEGIN:VCALENDAR\r\n\
+VERSION:2.0\r\n\
+PRODID:-//todo_md//todo_md//EN\r\n\
+BEGIN:VTODO\r\n\
+UID:123e4567-e89b-42d3-a456-426614174001\r\n\
+DTSTAMP:20260223T180000Z\r\n\
+SUMMARY:Gym\\, legs\r\n\
+DTSTART:20260223T180000\r\n\
+DUE:20260223T180000\r\n\
+RRULE:FREQ=WEEKLY;BYDAY=MO,TH;COUNT=4\r\n\
+STATUS:NEEDS-ACTION\r\n\
+END:VTODO\r\n\
+END:VCALENDAR\r\n";
+        assert_eq!(ics_document(&[&gym, &undated], now), expected);
+    }
+
+    #[test]
+    fn ics_document_keeps_recurring_times_on_the_home_day() {
+        let _zone = pin_home_zone(chrono_tz::America::New_York);
+        // Monday 21:00 in New York is already Tuesday in UTC.
+        let standup = Todo::from_str(
+            "- [_] Standup (due: 2026-02-24T02:00:00Z) (reccurence: weekly on monday until 2026-03-30) (id: 123e4567-e89b-42d3-a456-426614174001)",
+        );
+        let flight = Todo::from_str(
+            "- [_] Flight (due: 2026-02-24T02:00:00Z) (id: 123e4567-e89b-42d3-a456-426614174002)",
+        );
+        let now = Utc.with_ymd_and_hms(2026, 2, 23, 18, 0, 0).unwrap();
+
+        let ics = ics_document(&[&standup, &flight], now);
+
+        assert!(ics.contains(
+            "DTSTART:20260223T210000\r\n\
+DUE:20260223T210000\r\n\
+RRULE:FREQ=WEEKLY;BYDAY=MO;UNTIL=20260330T235959\r\n"
+        ));
+        assert!(ics.contains("SUMMARY:Flight\r\nDUE:20260224T020000Z\r\n"));
+    }
+
+    #[test]
+    fn ics_rrule_covers_monthly_variants() {
+        let rule = |raw: &str| {
+            let todo = Todo::from_str(&format!(
+                "- [_] Task (due: 2026-03-01T09:00:00Z) (reccurence: {raw}) (id: 123e4567-e89b-42d3-a456-426614174000)"
+            ));
+            todo.recurence().and_then(ics_rrule)
+        };
+        assert_eq!(
+            rule("weekly on monday, thursday").as_deref(),
+            Some("FREQ=WEEKLY;BYDAY=MO,TH")
+        );
+        assert_eq!(
+            rule("monthly on the last day").as_deref(),
+            Some("FREQ=MONTHLY;BYMONTHDAY=-1")
+        );
+        assert_eq!(
+            rule("monthly on the first friday").as_deref(),
+            Some("FREQ=MONTHLY;BYDAY=1FR")
+        );
+        assert_eq!(
+            rule("every 2 weeks").as_deref(),
+            Some("FREQ=WEEKLY;INTERVAL=2")
+        );
+    }
+
+    #[test]
+    fn ics_lines_are_folded_at_75_octets() {
+        let folded = ics_fold(&"x".repeat(80));
+        assert_eq!(
+            folded,
+            format!("{}\r\n {}\r\n", "x".repeat(75), "x".repeat(5))
+        );
+    }
+
+    #[test]
+    fn export_via_pipes_jsonl_through_the_command() {
+        let todo = Todo::from_str(
+            "- [_] Pay rent (due: 2026-03-01T12:00:00Z) (id: 123e4567-e89b-12d3-a456-426614174000)",
+        );
+        let input = crate::json::export_lines(&[&todo]).expect("jsonl");
+        assert_eq!(input.lines().count(), 1);
+
+        let output = export_via("cat", &input).expect("cat");
+        assert_eq!(String::from_utf8(output).expect("utf-8"), input);
+
+        let output = export_via("echo $TODO_MD_SCHEMA_VERSION; wc -l", &input).expect("wc");
+        assert_eq!(
+            String::from_utf8(output)
+                .expect("utf-8")
+                .split_whitespace()
+                .collect::<Vec<_>>(),
+            ["1", "1"]
+        );
+    }
+
+    #[test]
+    fn export_via_reports_failing_and_missing_commands() {
+        let error = export_via("exit 3", "").expect_err("failing command");
+        assert!(error.to_string().contains("exited with"));
+
+        let error = export_via("todo_md_no_such_exporter 2>/dev/null", "").expect_err("missing");
+        assert!(error.to_string().contains("was not found"));
+    }
+}
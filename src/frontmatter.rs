@@ -0,0 +1,197 @@
+use anyhow::{bail, Context, Result};
+use chrono::{FixedOffset, NaiveTime, Weekday};
+
+use crate::date_parser::parse_timezone;
+
+/// Version of the todo line format this build writes. Bumped when `to_line`
+/// changes in a way older builds would misread; `todo_md upgrade` rewrites a
+/// file to it. Files without a `format` key predate versioning: version 1.
+pub const LINE_FORMAT_VERSION: u32 = 2;
+
+/// Document-level settings from a `---` block at the top of todo.md. They
+/// override the matching env settings for that file.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Frontmatter {
+    pub title: Option<String>,
+    pub timezone: Option<FixedOffset>,
+    /// Time of day given to due dates written without one (default 11:59 PM).
+    pub due_time: Option<NaiveTime>,
+    pub week_start: Option<Weekday>,
+    /// `format`: the line format version the file was last upgraded to.
+    pub format: Option<u32>,
+}
+
+/// Number of lines taken by a leading frontmatter block, including both `---`
+/// fences; 0 when the file has none. Todo scanners skip these lines.
+pub fn frontmatter_line_count(content: &str) -> usize {
+    let mut lines = content.lines();
+    if lines.next().map(str::trim_end) != Some("---") {
+        return 0;
+    }
+    lines
+        .position(|line| matches!(line.trim_end(), "---" | "..."))
+        .map_or(0, |closing| closing + 2)
+}
+
+/// Reads the flat `key: value` pairs this app understands. Other keys are
+/// left for other markdown tools; values for known keys must be valid.
+pub fn parse_frontmatter(content: &str) -> Result<Option<Frontmatter>> {
+    let count = frontmatter_line_count(content);
+    if count == 0 {
+        return Ok(None);
+    }
+
+    let mut frontmatter = Frontmatter::default();
+    for (idx, line) in content.lines().enumerate().take(count - 1).skip(1) {
+        let Some((key, value)) = line.split_once(':') else {
+            continue;
+        };
+        let value = value.trim().trim_matches(|c| c == '"' || c == '\'');
+        let context = || format!("todo.md frontmatter line {}", idx + 1);
+        match key.trim() {
+            "title" => frontmatter.title = Some(value.to_string()),
+            "timezone" => {
+                frontmatter.timezone = Some(
+                    parse_timezone(value)
+                        .with_context(|| {
+                            format!("timezone must be UTC or an offset like +05:30, got `{value}`")
+                        })
+                        .with_context(context)?,
+                );
+            }
+            "due_time" => {
+                frontmatter.due_time = Some(
+                    NaiveTime::parse_from_str(value, "%H:%M")
+                        .with_context(|| format!("due_time must be HH:MM, got `{value}`"))
+                        .with_context(context)?,
+                );
+            }
+            "week_start" => {
+                frontmatter.week_start = match value.parse::<Weekday>() {
+                    Ok(day @ (Weekday::Mon | Weekday::Sun)) => Some(day),
+                    _ => bail!(
+                        "{}: week_start must be monday or sunday, got `{value}`",
+                        context()
+                    ),
+                };
+            }
+            "format" => {
+                let version = value
+                    .parse::<u32>()
+                    .with_context(|| format!("format must be a number, got `{value}`"))
+                    .with_context(context)?;
+                if version > LINE_FORMAT_VERSION {
+                    bail!(
+                        "{}: todo.md uses line format {version}, but this todo_md only understands up to {LINE_FORMAT_VERSION}; update todo_md",
+                        context()
+                    );
+                }
+                frontmatter.format = Some(version);
+            }
+            _ => {}
+        }
+    }
+    Ok(Some(frontmatter))
+}
+
+/// Sets `key: value` in the frontmatter, replacing an existing entry or
+/// adding one (and the block itself when the file has none).
+pub fn set_frontmatter_value(content: &str, key: &str, value: &str) -> String {
+    let entry = format!("{key}: {value}");
+    let count = frontmatter_line_count(content);
+    if count == 0 {
+        return format!("---\n{entry}\n---\n{content}");
+    }
+
+    let mut lines = content.lines().map(str::to_string).collect::<Vec<_>>();
+    let existing = (1..count - 1).find(|&idx| {
+        lines[idx]
+            .split_once(':')
+            .is_some_and(|(name, _)| name.trim() == key)
+    });
+    match existing {
+        Some(idx) => lines[idx] = entry,
+        None => lines.insert(count - 1, entry),
+    }
+    let mut next = lines.join("\n");
+    if content.ends_with('\n') {
+        next.push('\n');
+    }
+    next
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::{parse_todo_content, validate_todo_content};
+
+    const CONTENT: &str = "---\n\
+        title: Home\n\
+        timezone: +05:30\n\
+        due_time: 18:00\n\
+        week_start: sunday\n\
+        tags:\n\
+        - [x] not a todo\n\
+        ---\n\
+        - [_] Pay rent (id: 123e4567-e89b-12d3-a456-426614174000)\n";
+
+    #[test]
+    fn parses_known_keys() {
+        let frontmatter = parse_frontmatter(CONTENT)
+            .expect("valid frontmatter")
+            .expect("has frontmatter");
+        assert_eq!(frontmatter.title.as_deref(), Some("Home"));
+        assert_eq!(
+            frontmatter.timezone,
+            FixedOffset::east_opt(5 * 3600 + 30 * 60)
+        );
+        assert_eq!(frontmatter.due_time, NaiveTime::from_hms_opt(18, 0, 0));
+        assert_eq!(frontmatter.week_start, Some(Weekday::Sun));
+        assert_eq!(frontmatter_line_count(CONTENT), 8);
+    }
+
+    #[test]
+    fn frontmatter_lines_are_not_todos() {
+        let parsed = parse_todo_content(CONTENT);
+        assert_eq!(parsed.todos_by_id.len(), 1);
+        assert!(validate_todo_content(CONTENT).is_empty());
+    }
+
+    #[test]
+    fn only_a_leading_closed_block_counts() {
+        assert_eq!(frontmatter_line_count("# Todos\n---\ntitle: x\n---\n"), 0);
+        assert_eq!(frontmatter_line_count("---\ntitle: x\n"), 0);
+        assert!(parse_frontmatter("- [_] a\n").expect("no block").is_none());
+        assert!(parse_frontmatter("---\nweek_start: friday\n---\n").is_err());
+    }
+
+    #[test]
+    fn newer_line_formats_are_refused() {
+        let current = format!("---\nformat: {LINE_FORMAT_VERSION}\n---\n");
+        let parsed = parse_frontmatter(&current).expect("current format");
+        assert_eq!(
+            parsed.and_then(|frontmatter| frontmatter.format),
+            Some(LINE_FORMAT_VERSION)
+        );
+
+        let newer = format!("---\nformat: {}\n---\n", LINE_FORMAT_VERSION + 1);
+        let error = parse_frontmatter(&newer).expect_err("newer format");
+        assert!(format!("{error:#}").contains("update todo_md"));
+    }
+
+    #[test]
+    fn sets_frontmatter_values_in_place_or_adds_a_block() {
+        assert_eq!(
+            set_frontmatter_value("- [_] a\n", "format", "2"),
+            "---\nformat: 2\n---\n- [_] a\n"
+        );
+        assert_eq!(
+            set_frontmatter_value("---\ntitle: Home\nformat: 1\n---\n- [_] a\n", "format", "2"),
+            "---\ntitle: Home\nformat: 2\n---\n- [_] a\n"
+        );
+        assert_eq!(
+            set_frontmatter_value("---\ntitle: Home\n---\n", "format", "2"),
+            "---\ntitle: Home\nformat: 2\n---\n"
+        );
+    }
+}
@@ -0,0 +1,616 @@
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
+
+use anyhow::{bail, Result};
+use chrono::{DateTime, Datelike, Duration, NaiveDate, Utc, Weekday};
+use serde_json::{json, Value};
+use uuid::Uuid;
+
+use crate::config::AppConfig;
+use crate::date_parser::in_home_zone;
+use crate::diff::{fields_text, semantic_changes, ChangeKind, TodoField};
+use crate::storage::{parse_todo_content, read_todo_file, ParsedTodoFile};
+use crate::sync::{git_show_or_empty, run_git, run_git_checked, todo_path_relative_to_repo};
+
+/// A semantic change with the todo's name resolved.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NamedChange {
+    pub kind: ChangeKind,
+    pub id: Uuid,
+    pub name: String,
+    /// See [`crate::diff::TodoChange::fields`].
+    pub fields: Vec<TodoField>,
+}
+
+impl NamedChange {
+    /// `<kind>\t<id>\t<name>`.
+    pub fn porcelain(&self) -> String {
+        format!("{}\t{}\t{}", self.kind.as_str(), self.id, self.name)
+    }
+
+    /// `Updated: <name> (<id>): due_date, name`, or without the fields when
+    /// there are none.
+    pub fn human(&self) -> String {
+        let line = format!("{:?}: {} ({})", self.kind, self.name, self.id);
+        if self.fields.is_empty() {
+            return line;
+        }
+        format!("{line}: {}", fields_text(&self.fields))
+    }
+
+    pub fn to_json(&self) -> Value {
+        json!({
+            "kind": self.kind.as_str(),
+            "id": self.id.to_string(),
+            "name": self.name,
+            "fields": self.fields.iter().map(|field| field.as_str()).collect::<Vec<_>>(),
+        })
+    }
+}
+
+/// A commit that touched todo.md and what it changed.
+#[derive(Debug, Clone)]
+pub struct LogEntry {
+    pub commit: String,
+    pub subject: String,
+    pub changes: Vec<NamedChange>,
+}
+
+impl LogEntry {
+    pub fn to_json(&self) -> Value {
+        json!({
+            "commit": self.commit,
+            "subject": self.subject,
+            "changes": self.changes.iter().map(NamedChange::to_json).collect::<Vec<_>>(),
+        })
+    }
+}
+
+/// Changes from `older` to `newer`, grouped by kind then ordered by id. Names
+/// come from the newer revision, except for deleted todos.
+pub fn named_changes(older: &ParsedTodoFile, newer: &ParsedTodoFile) -> Vec<NamedChange> {
+    let change_set = semantic_changes(older, newer);
+    change_set
+        .sorted_changes()
+        .into_iter()
+        .map(|change| {
+            let source = if change.kind == ChangeKind::Deleted {
+                older
+            } else {
+                newer
+            };
+            NamedChange {
+                kind: change.kind,
+                id: change.id,
+                name: source
+                    .todos_by_id
+                    .get(&change.id)
+                    .map(|todo| todo.name().to_string())
+                    .unwrap_or_default(),
+                fields: change.fields.clone(),
+            }
+        })
+        .collect()
+}
+
+/// Uncommitted changes: the working todo.md against `HEAD`.
+pub fn status(config: &AppConfig) -> Result<Vec<NamedChange>> {
+    let todo_rel = todo_path_relative_to_repo(config)?;
+    let committed = git_show_or_empty(&config.config_dir, &format!("HEAD:{todo_rel}"))?;
+    let current = read_todo_file(&config.todo_file)?;
+    Ok(named_changes(&parse_todo_content(&committed), &current))
+}
+
+/// What origin changed that the next pull will bring in: `origin/<branch>`
+/// against the last commit it shares with `HEAD`, so local commits that
+/// have not been pushed are not reported as reverted. Call after fetching.
+pub fn incoming(config: &AppConfig) -> Result<Vec<NamedChange>> {
+    let repo_dir = &config.config_dir;
+    let remote_ref = format!("origin/{}", config.git_branch);
+    if !run_git(
+        repo_dir,
+        ["rev-parse", "--verify", "-q", remote_ref.as_str()],
+    )?
+    .status
+    .success()
+    {
+        return Ok(Vec::new());
+    }
+    let merge_base = run_git(repo_dir, ["merge-base", "HEAD", remote_ref.as_str()])?;
+    let base = if merge_base.status.success() {
+        String::from_utf8_lossy(&merge_base.stdout)
+            .trim()
+            .to_string()
+    } else {
+        // No shared history (or no HEAD yet): everything on origin is new.
+        String::new()
+    };
+
+    let todo_rel = todo_path_relative_to_repo(config)?;
+    let base_content = match base.as_str() {
+        "" => String::new(),
+        base => git_show_or_empty(repo_dir, &format!("{base}:{todo_rel}"))?,
+    };
+    let remote_content = git_show_or_empty(repo_dir, &format!("{remote_ref}:{todo_rel}"))?;
+    Ok(incoming_changes(&base_content, &remote_content))
+}
+
+/// [`incoming`] for two snapshots of todo.md: the shared base and origin's.
+pub fn incoming_changes(base: &str, remote: &str) -> Vec<NamedChange> {
+    named_changes(&parse_todo_content(base), &parse_todo_content(remote))
+}
+
+/// The last `limit` commits that touched todo.md, newest first.
+pub fn log(config: &AppConfig, limit: usize) -> Result<Vec<LogEntry>> {
+    let todo_rel = todo_path_relative_to_repo(config)?;
+    let limit = limit.to_string();
+    let listing = run_git_checked(
+        &config.config_dir,
+        [
+            "log",
+            "-n",
+            limit.as_str(),
+            "--format=%H%x09%s",
+            "--",
+            todo_rel.as_str(),
+        ],
+    )?;
+
+    let mut entries = Vec::new();
+    for line in listing.lines().filter(|line| !line.is_empty()) {
+        let (commit, subject) = line.split_once('\t').unwrap_or((line, ""));
+        // A root commit has no parent, so everything in it reads as added.
+        let older = git_show_or_empty(&config.config_dir, &format!("{commit}^:{todo_rel}"))?;
+        let newer = git_show_or_empty(&config.config_dir, &format!("{commit}:{todo_rel}"))?;
+        entries.push(LogEntry {
+            commit: commit.to_string(),
+            subject: subject.to_string(),
+            changes: named_changes(&parse_todo_content(&older), &parse_todo_content(&newer)),
+        });
+    }
+    Ok(entries)
+}
+
+/// Markdown changelog of todo changes between two git revisions.
+pub fn changelog(config: &AppConfig, from: &str, to: &str) -> Result<String> {
+    let todo_rel = todo_path_relative_to_repo(config)?;
+    let older = read_revision(config, from, &todo_rel)?;
+    let newer = read_revision(config, to, &todo_rel)?;
+    Ok(render_changelog(
+        &format!("Changes from {from} to {to}"),
+        &older,
+        &newer,
+    ))
+}
+
+fn read_revision(config: &AppConfig, rev: &str, todo_rel: &str) -> Result<ParsedTodoFile> {
+    let commit = format!("{rev}^{{commit}}");
+    let verify = run_git(
+        &config.config_dir,
+        ["rev-parse", "--verify", "--quiet", commit.as_str()],
+    )?;
+    if !verify.status.success() {
+        bail!("unknown git revision `{rev}`");
+    }
+
+    let content = git_show_or_empty(&config.config_dir, &format!("{rev}:{todo_rel}"))?;
+    Ok(parse_todo_content(&content))
+}
+
+/// A todo completion and when it happened.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Completion {
+    pub id: Uuid,
+    pub name: String,
+    pub completed_at: DateTime<Utc>,
+}
+
+/// Todos completed at or after `since`: completions found in commits to
+/// todo.md, dated by their commit, plus completions not yet synced (see
+/// [`unsynced_completions`]). Each todo appears once, at its latest
+/// completion, oldest first.
+pub fn completed_since(config: &AppConfig, since: DateTime<Utc>) -> Result<Vec<Completion>> {
+    let todo_rel = todo_path_relative_to_repo(config)?;
+    // Not `git log --since`: it stops at the first older commit, and commits
+    // backdated with `sync --date` are out of order.
+    let listing = run_git_checked(
+        &config.config_dir,
+        ["log", "--format=%H%x09%cI", "--", todo_rel.as_str()],
+    )?;
+
+    let mut completions = Vec::new();
+    for line in listing.lines().filter(|line| !line.is_empty()) {
+        let Some((commit, date)) = line.split_once('\t') else {
+            continue;
+        };
+        let Ok(date) = DateTime::parse_from_rfc3339(date) else {
+            continue;
+        };
+        if date.to_utc() < since {
+            continue;
+        }
+        let older = git_show_or_empty(&config.config_dir, &format!("{commit}^:{todo_rel}"))?;
+        let newer = git_show_or_empty(&config.config_dir, &format!("{commit}:{todo_rel}"))?;
+        completions.extend(completions_in(
+            &parse_todo_content(&older),
+            &parse_todo_content(&newer),
+            date.to_utc(),
+        ));
+    }
+
+    let committed = git_show_or_empty(&config.config_dir, &format!("HEAD:{todo_rel}"))?;
+    let current = read_todo_file(&config.todo_file)?;
+    let modified = fs::metadata(&config.todo_file)
+        .and_then(|metadata| metadata.modified())
+        .map(DateTime::<Utc>::from)
+        .unwrap_or_else(|_| Utc::now());
+    completions.extend(unsynced_completions(
+        &parse_todo_content(&committed),
+        &current,
+        modified,
+    ));
+
+    Ok(latest_completions(
+        completions
+            .into_iter()
+            .filter(|completion| completion.completed_at >= since),
+    ))
+}
+
+/// Completions in `current` that are not in `committed`, dated by each
+/// todo's recorded `updated:` stamp, or `modified` (todo.md's modification
+/// time) for a line without one.
+fn unsynced_completions(
+    committed: &ParsedTodoFile,
+    current: &ParsedTodoFile,
+    modified: DateTime<Utc>,
+) -> Vec<Completion> {
+    completions_in(committed, current, modified)
+        .into_iter()
+        .map(|mut completion| {
+            if let Some(updated_at) = current
+                .todos_by_id
+                .get(&completion.id)
+                .and_then(|todo| todo.recorded_updated_at())
+            {
+                completion.completed_at = updated_at;
+            }
+            completion
+        })
+        .collect()
+}
+
+fn completions_in(
+    older: &ParsedTodoFile,
+    newer: &ParsedTodoFile,
+    completed_at: DateTime<Utc>,
+) -> Vec<Completion> {
+    named_changes(older, newer)
+        .into_iter()
+        .filter(|change| change.kind == ChangeKind::Completed)
+        .map(|change| Completion {
+            id: change.id,
+            name: change.name,
+            completed_at,
+        })
+        .collect()
+}
+
+/// One completion per id, the most recent, ordered oldest first.
+fn latest_completions(completions: impl IntoIterator<Item = Completion>) -> Vec<Completion> {
+    let mut by_id: HashMap<Uuid, Completion> = HashMap::new();
+    for completion in completions {
+        match by_id.get(&completion.id) {
+            Some(existing) if existing.completed_at >= completion.completed_at => {}
+            _ => {
+                by_id.insert(completion.id, completion);
+            }
+        }
+    }
+    let mut latest = by_id.into_values().collect::<Vec<_>>();
+    latest.sort_by(|a, b| {
+        a.completed_at
+            .cmp(&b.completed_at)
+            .then_with(|| a.name.cmp(&b.name))
+    });
+    latest
+}
+
+/// Completions per day (in the configured zone) from commits to todo.md made
+/// on or after `since`.
+pub fn completions_by_day(
+    config: &AppConfig,
+    since: NaiveDate,
+) -> Result<BTreeMap<NaiveDate, usize>> {
+    let todo_rel = todo_path_relative_to_repo(config)?;
+    // A full walk, as in `completed_since`: backdated commits are out of order.
+    let listing = run_git_checked(
+        &config.config_dir,
+        ["log", "--format=%H%x09%cI", "--", todo_rel.as_str()],
+    )?;
+
+    let mut counts = BTreeMap::new();
+    for line in listing.lines().filter(|line| !line.is_empty()) {
+        let Some((commit, date)) = line.split_once('\t') else {
+            continue;
+        };
+        let Ok(date) = DateTime::parse_from_rfc3339(date) else {
+            continue;
+        };
+        let day = in_home_zone(date.to_utc()).date_naive();
+        if day < since {
+            continue;
+        }
+        let older = git_show_or_empty(&config.config_dir, &format!("{commit}^:{todo_rel}"))?;
+        let newer = git_show_or_empty(&config.config_dir, &format!("{commit}:{todo_rel}"))?;
+        let completed =
+            semantic_changes(&parse_todo_content(&older), &parse_todo_content(&newer)).completed;
+        if completed > 0 {
+            *counts.entry(day).or_default() += completed;
+        }
+    }
+    Ok(counts)
+}
+
+/// Contributions-graph style grid: one row per weekday starting at
+/// `week_start`, one column per week from `start` to `end`.
+pub fn render_heatmap(
+    counts: &BTreeMap<NaiveDate, usize>,
+    start: NaiveDate,
+    end: NaiveDate,
+    week_start: Weekday,
+) -> String {
+    let offset =
+        (7 + start.weekday().num_days_from_monday() - week_start.num_days_from_monday()) % 7;
+    let first = start - Duration::days(i64::from(offset));
+    let weeks = (end - first).num_days() / 7 + 1;
+
+    let mut out = String::new();
+    let mut day = week_start;
+    for row in 0..7 {
+        out.push_str(&format!("{day:<4}"));
+        for week in 0..weeks {
+            let date = first + Duration::days(week * 7 + row);
+            let cell = if date < start || date > end {
+                ' '
+            } else {
+                heat(counts.get(&date).copied().unwrap_or(0))
+            };
+            out.push(cell);
+        }
+        out.push('\n');
+        day = day.succ();
+    }
+
+    let total: usize = counts.range(start..=end).map(|(_, count)| count).sum();
+    out.push_str(&format!(
+        "{total} completion(s) from {start} to {end}; . 0  - 1  + 2-3  * 4-5  # 6+\n"
+    ));
+    out
+}
+
+fn heat(count: usize) -> char {
+    match count {
+        0 => '.',
+        1 => '-',
+        2..=3 => '+',
+        4..=5 => '*',
+        _ => '#',
+    }
+}
+
+/// Groups changes by kind. Names come from the newer revision, except for
+/// removed todos which only exist in the older one.
+pub fn render_changelog(title: &str, older: &ParsedTodoFile, newer: &ParsedTodoFile) -> String {
+    let change_set = semantic_changes(older, newer);
+    let mut out = format!("## {title}\n");
+
+    let sections = [
+        ("Added", ChangeKind::Added),
+        ("Completed", ChangeKind::Completed),
+        ("Updated", ChangeKind::Updated),
+        ("Removed", ChangeKind::Deleted),
+    ];
+    for (heading, kind) in sections {
+        let mut names = change_set
+            .changes
+            .iter()
+            .filter(|change| change.kind == kind)
+            .filter_map(|change| {
+                let source = if kind == ChangeKind::Deleted {
+                    older
+                } else {
+                    newer
+                };
+                source.todos_by_id.get(&change.id).map(|todo| todo.name())
+            })
+            .collect::<Vec<_>>();
+        if names.is_empty() {
+            continue;
+        }
+
+        names.sort();
+        out.push_str(&format!("\n### {heading}\n"));
+        for name in names {
+            out.push_str(&format!("- {name}\n"));
+        }
+    }
+
+    if change_set.is_empty() {
+        out.push_str("\nNo todo changes.\n");
+    }
+
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn completions_keep_the_latest_per_todo() {
+        let open = parse_todo_content(
+            "- [_] Water plants (due: 2026-02-16T09:00:00Z) (reccurence: weekly on monday) (id: 123e4567-e89b-12d3-a456-426614174000)\n\
+             - [_] Ship v1 (id: 123e4567-e89b-12d3-a456-426614174001)\n",
+        );
+        let rolled = parse_todo_content(
+            "- [_] Water plants (due: 2026-02-23T09:00:00Z) (reccurence: weekly on monday) (id: 123e4567-e89b-12d3-a456-426614174000)\n\
+             - [_] Ship v1 (id: 123e4567-e89b-12d3-a456-426614174001)\n",
+        );
+        let done = parse_todo_content(
+            "- [_] Water plants (due: 2026-03-02T09:00:00Z) (reccurence: weekly on monday) (id: 123e4567-e89b-12d3-a456-426614174000)\n\
+             - [x] Ship v1 (id: 123e4567-e89b-12d3-a456-426614174001)\n",
+        );
+        let monday = DateTime::parse_from_rfc3339("2026-02-16T18:00:00Z")
+            .expect("valid date")
+            .to_utc();
+        let next_monday = monday + Duration::weeks(1);
+
+        let completions = latest_completions(
+            completions_in(&open, &rolled, monday)
+                .into_iter()
+                .chain(completions_in(&rolled, &done, next_monday)),
+        );
+
+        assert_eq!(completions.len(), 2);
+        assert!(completions
+            .iter()
+            .all(|completion| completion.completed_at == next_monday));
+        assert_eq!(completions[0].name, "Ship v1");
+        assert_eq!(completions[1].name, "Water plants");
+    }
+
+    #[test]
+    fn unsynced_completions_use_their_updated_stamp() {
+        let committed = parse_todo_content(
+            "- [_] Ship v1 (id: 123e4567-e89b-12d3-a456-426614174000)\n\
+             - [_] Call mom (id: 123e4567-e89b-12d3-a456-426614174001)\n",
+        );
+        let current = parse_todo_content(
+            "- [x] Ship v1 (updated: 2026-02-20T10:00:00Z) (id: 123e4567-e89b-12d3-a456-426614174000)\n\
+             - [x] Call mom (id: 123e4567-e89b-12d3-a456-426614174001)\n",
+        );
+        let modified = DateTime::parse_from_rfc3339("2026-02-23T18:00:00Z")
+            .expect("valid date")
+            .to_utc();
+
+        let completions = latest_completions(unsynced_completions(&committed, &current, modified));
+
+        assert_eq!(
+            completions
+                .iter()
+                .map(|completion| (
+                    completion.name.as_str(),
+                    completion.completed_at.to_rfc3339()
+                ))
+                .collect::<Vec<_>>(),
+            [
+                ("Ship v1", "2026-02-20T10:00:00+00:00".to_string()),
+                ("Call mom", "2026-02-23T18:00:00+00:00".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn groups_changes_by_kind_with_names() {
+        let older = parse_todo_content(
+            "- [_] Ship v1 (id: 123e4567-e89b-12d3-a456-426614174000)\n\
+             - [_] Old idea (id: 123e4567-e89b-12d3-a456-426614174001)\n\
+             - [_] Write docs (id: 123e4567-e89b-12d3-a456-426614174002)\n",
+        );
+        let newer = parse_todo_content(
+            "- [x] Ship v1 (id: 123e4567-e89b-12d3-a456-426614174000)\n\
+             - [_] Write better docs (id: 123e4567-e89b-12d3-a456-426614174002)\n\
+             - [_] Plan v2 (id: 123e4567-e89b-12d3-a456-426614174003)\n",
+        );
+
+        assert_eq!(
+            render_changelog("Changes from v1 to v2", &older, &newer),
+            "## Changes from v1 to v2\n\n\
+             ### Added\n- Plan v2\n\n\
+             ### Completed\n- Ship v1\n\n\
+             ### Updated\n- Write better docs\n\n\
+             ### Removed\n- Old idea\n"
+        );
+    }
+
+    #[test]
+    fn incoming_changes_are_the_collaborators_edits() {
+        let base = "- [_] Ship v1 (id: 123e4567-e89b-12d3-a456-426614174000)\n\
+                    - [_] Old idea (id: 123e4567-e89b-12d3-a456-426614174001)\n";
+        let remote = "- [x] Ship v1 (id: 123e4567-e89b-12d3-a456-426614174000)\n\
+                      - [_] Old idea (id: 123e4567-e89b-12d3-a456-426614174001)\n\
+                      - [_] Plan v2 (id: 123e4567-e89b-12d3-a456-426614174002)\n";
+
+        let changes = incoming_changes(base, remote);
+        let summary = changes
+            .iter()
+            .map(NamedChange::porcelain)
+            .collect::<Vec<_>>();
+        assert_eq!(
+            summary,
+            [
+                "added\t123e4567-e89b-12d3-a456-426614174002\tPlan v2",
+                "completed\t123e4567-e89b-12d3-a456-426614174000\tShip v1",
+            ]
+        );
+        assert!(incoming_changes(remote, remote).is_empty());
+    }
+
+    #[test]
+    fn named_changes_are_grouped_by_kind_then_id() {
+        let older = parse_todo_content(
+            "- [_] Ship v1 (id: 123e4567-e89b-12d3-a456-426614174000)\n\
+             - [_] Old idea (id: 123e4567-e89b-12d3-a456-426614174001)\n",
+        );
+        let newer = parse_todo_content(
+            "- [_] Zeta (id: 123e4567-e89b-12d3-a456-426614174009)\n\
+             - [x] Ship v1 (id: 123e4567-e89b-12d3-a456-426614174000)\n\
+             - [_] Alpha (id: 123e4567-e89b-12d3-a456-426614174003)\n",
+        );
+
+        let lines = named_changes(&older, &newer)
+            .iter()
+            .map(NamedChange::porcelain)
+            .collect::<Vec<_>>();
+        assert_eq!(
+            lines,
+            vec![
+                "added\t123e4567-e89b-12d3-a456-426614174003\tAlpha",
+                "added\t123e4567-e89b-12d3-a456-426614174009\tZeta",
+                "completed\t123e4567-e89b-12d3-a456-426614174000\tShip v1",
+                "deleted\t123e4567-e89b-12d3-a456-426614174001\tOld idea",
+            ]
+        );
+    }
+
+    #[test]
+    fn heatmap_places_days_by_weekday_and_week() {
+        let date = |day| NaiveDate::from_ymd_opt(2026, 2, day).expect("valid date");
+        let counts = BTreeMap::from([(date(2), 1), (date(4), 3), (date(11), 7)]);
+
+        // Mon 2 Feb to Sun 15 Feb: two full weeks.
+        assert_eq!(
+            render_heatmap(&counts, date(2), date(15), Weekday::Mon),
+            "Mon -.\n\
+             Tue ..\n\
+             Wed +#\n\
+             Thu ..\n\
+             Fri ..\n\
+             Sat ..\n\
+             Sun ..\n\
+             11 completion(s) from 2026-02-02 to 2026-02-15; . 0  - 1  + 2-3  * 4-5  # 6+\n"
+        );
+
+        // Starting mid-week leaves earlier days blank; Sunday-first rows.
+        let grid = render_heatmap(&counts, date(4), date(7), Weekday::Sun);
+        assert!(grid.starts_with("Sun  \nMon  \nTue  \nWed +\n"));
+    }
+
+    #[test]
+    fn reports_when_nothing_changed() {
+        let parsed = parse_todo_content("- [_] Same (id: 123e4567-e89b-12d3-a456-426614174000)\n");
+        let rendered = render_changelog("Changes", &parsed, &parsed);
+        assert!(rendered.ends_with("No todo changes.\n"));
+    }
+}
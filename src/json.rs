@@ -0,0 +1,348 @@
+use std::collections::HashMap;
+
+use anyhow::{anyhow, bail, Context, Result};
+use chrono::{DateTime, SecondsFormat, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use uuid::Uuid;
+
+use crate::date_parser::in_home_zone;
+use crate::diff::{semantic_changes, ChangeSet};
+use crate::edit::line_todo_id;
+use crate::recurrence_parser::{parse_reccurence, split_recurrence_end};
+use crate::storage::parse_todo_content;
+use crate::types::Todo;
+
+/// Version of the JSON document written by `export --json`. Bump it whenever a
+/// field changes meaning or a required field is added, and teach
+/// `import_document` to migrate the previous version.
+pub const SCHEMA_VERSION: u64 = 1;
+
+#[derive(Debug, Serialize)]
+struct ExportDocument {
+    schema_version: u64,
+    todos: Vec<TodoRecord>,
+}
+
+#[derive(Debug, Serialize)]
+struct TodoRecord {
+    id: String,
+    name: String,
+    done: bool,
+    due_date: Option<String>,
+    scheduled: Option<String>,
+    recurrence: Option<String>,
+    external_id: Option<String>,
+    created_at: String,
+    updated_at: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct ImportRecord {
+    /// A fresh id is generated when missing, so hand-written imports only need
+    /// a name.
+    #[serde(default)]
+    id: Option<String>,
+    name: String,
+    #[serde(default)]
+    done: bool,
+    #[serde(default)]
+    due_date: Option<String>,
+    #[serde(default)]
+    scheduled: Option<String>,
+    #[serde(default, alias = "reccurence")]
+    recurrence: Option<String>,
+    #[serde(default)]
+    external_id: Option<String>,
+    /// Missing timestamps read as now, like a line without them.
+    #[serde(default)]
+    created_at: Option<String>,
+    #[serde(default)]
+    updated_at: Option<String>,
+}
+
+fn todo_record(todo: &Todo) -> TodoRecord {
+    TodoRecord {
+        id: todo.id().to_string(),
+        name: todo.name(),
+        done: todo.done(),
+        due_date: todo.due_date().map(rfc3339),
+        scheduled: todo.scheduled().map(rfc3339),
+        recurrence: todo.recurrence_text(),
+        external_id: todo.external_id().map(str::to_string),
+        created_at: rfc3339(todo.created_at()),
+        updated_at: rfc3339(todo.updated_at()),
+    }
+}
+
+/// Serializes `todos` as a versioned export document, ordered by due date
+/// (undated last) and then id so repeated exports diff cleanly. `pretty`
+/// indents the output.
+pub fn export_document(todos: &[&Todo], pretty: bool) -> Result<String> {
+    let mut todos = todos.to_vec();
+    todos.sort_by_key(|todo| (todo.due_date().is_none(), todo.due_date(), todo.id()));
+    let document = ExportDocument {
+        schema_version: SCHEMA_VERSION,
+        todos: todos.iter().map(|todo| todo_record(todo)).collect(),
+    };
+    if pretty {
+        serde_json::to_string_pretty(&document)
+    } else {
+        serde_json::to_string(&document)
+    }
+    .context("failed to serialize todos as json")
+}
+
+/// One todo object per line, with the same fields as [`export_document`], for
+/// streaming to `export --via` commands.
+pub fn export_lines(todos: &[&Todo]) -> Result<String> {
+    let mut out = String::new();
+    for todo in todos {
+        out.push_str(
+            &serde_json::to_string(&todo_record(todo))
+                .context("failed to serialize todo as json")?,
+        );
+        out.push('\n');
+    }
+    Ok(out)
+}
+
+/// Parses an export document into todos.
+///
+/// A bare array of todos is the unversioned format written before
+/// `schema_version` existed and is read as version 0. Documents from a newer
+/// schema are rejected rather than guessed at.
+pub fn import_document(input: &str) -> Result<Vec<Todo>> {
+    let value: Value = serde_json::from_str(input).context("import is not valid json")?;
+    let (version, todos) = match value {
+        Value::Array(todos) => (0, Value::Array(todos)),
+        Value::Object(mut document) => {
+            let version = document
+                .get("schema_version")
+                .ok_or_else(|| anyhow!("import document has no `schema_version`"))?
+                .as_u64()
+                .ok_or_else(|| anyhow!("`schema_version` must be a non-negative integer"))?;
+            let todos = document
+                .remove("todos")
+                .ok_or_else(|| anyhow!("import document has no `todos` array"))?;
+            (version, todos)
+        }
+        _ => bail!("import must be a json object with `schema_version` and `todos`"),
+    };
+
+    if version > SCHEMA_VERSION {
+        bail!(
+            "import uses schema_version {version}, but this todo_md only understands up to {SCHEMA_VERSION}; upgrade todo_md to import it"
+        );
+    }
+
+    // Versions 0 and 1 share the record layout; 0 only lacks the envelope.
+    let Value::Array(records) = todos else {
+        bail!("import `todos` is not an array of todo objects");
+    };
+    records
+        .into_iter()
+        .enumerate()
+        .map(|(index, record)| {
+            serde_json::from_value::<ImportRecord>(record)
+                .map_err(anyhow::Error::from)
+                .and_then(todo_from_record)
+                .with_context(|| format!("invalid todo at index {index}"))
+        })
+        .collect()
+}
+
+/// Merges `imported` into `content`: a todo whose id already exists replaces
+/// that line, anything else is appended. Returns the merged content and what
+/// changed, counted the same way as `diff`, so rewriting a line with
+/// identical text is not an update and checking one off is a completion.
+pub fn merge_into_content(content: &str, imported: &[Todo]) -> (String, ChangeSet) {
+    let mut by_id = imported
+        .iter()
+        .map(|todo| (todo.id(), todo))
+        .collect::<HashMap<_, _>>();
+    let mut lines = Vec::new();
+
+    for line in content.lines() {
+        match line_todo_id(line).and_then(|id| by_id.remove(&id)) {
+            Some(todo) => lines.push(todo.to_line()),
+            None => lines.push(line.to_string()),
+        }
+    }
+
+    for todo in imported {
+        if by_id.remove(&todo.id()).is_some() {
+            lines.push(todo.to_line());
+        }
+    }
+
+    let mut merged = lines.join("\n");
+    if !merged.is_empty() {
+        merged.push('\n');
+    }
+    let changes = semantic_changes(&parse_todo_content(content), &parse_todo_content(&merged));
+    (merged, changes)
+}
+
+fn todo_from_record(record: ImportRecord) -> Result<Todo> {
+    let id = match record.id {
+        Some(raw) => {
+            Uuid::parse_str(raw.trim()).map_err(|_| anyhow!("`{raw}` is not a valid id"))?
+        }
+        None => Uuid::new_v4(),
+    };
+    let name = record.name.trim().to_string();
+    if name.is_empty() {
+        bail!("name must not be empty");
+    }
+    let due_date = record
+        .due_date
+        .map(|raw| parse_rfc3339("due_date", &raw))
+        .transpose()?;
+    let scheduled = record
+        .scheduled
+        .map(|raw| parse_rfc3339("scheduled", &raw))
+        .transpose()?;
+    let (recurence, recurrence_end) = match record.recurrence {
+        Some(raw) => split_recurrence_end(&raw)
+            .and_then(|(rule, end)| {
+                Some((Some(parse_reccurence(rule, in_home_zone(Utc::now()))?), end))
+            })
+            .ok_or_else(|| anyhow!("recurrence `{raw}` is not recognised"))?,
+        None => (None, None),
+    };
+    let created_at = record
+        .created_at
+        .map(|raw| parse_rfc3339("created_at", &raw))
+        .transpose()?;
+    let updated_at = record
+        .updated_at
+        .map(|raw| parse_rfc3339("updated_at", &raw))
+        .transpose()?;
+
+    let mut todo = Todo::from_parts(id, name, record.done, due_date, scheduled, recurence);
+    todo.set_recurrence_end(recurrence_end);
+    todo.set_external_id(
+        record
+            .external_id
+            .map(|ext| ext.trim().to_string())
+            .filter(|ext| !ext.is_empty()),
+    );
+    todo.set_timestamps(
+        created_at.or(todo.recorded_created_at()),
+        updated_at.or(todo.recorded_updated_at()),
+    );
+    Ok(todo)
+}
+
+fn parse_rfc3339(field: &str, raw: &str) -> Result<DateTime<Utc>> {
+    DateTime::parse_from_rfc3339(raw)
+        .map(|value| value.with_timezone(&Utc))
+        .map_err(|_| anyhow!("{field} `{raw}` is not RFC3339"))
+}
+
+fn rfc3339(value: DateTime<Utc>) -> String {
+    value.to_rfc3339_opts(SecondsFormat::Secs, true)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ID: &str = "123e4567-e89b-12d3-a456-426614174001";
+
+    #[test]
+    fn export_round_trips_through_import() {
+        let todo = Todo::from_str(&format!(
+            "- [_] Pay rent (due: 2026-03-01 09:00 AM) (scheduled: 2026-02-25 09:00 AM) (reccurence: monthly) (created: 2026-02-01T08:00:00Z) (updated: 2026-02-20T08:30:00Z) (id: {ID})"
+        ));
+        let exported = export_document(&[&todo], false).expect("export");
+        assert!(exported.starts_with(r#"{"schema_version":1,"#));
+
+        let imported = import_document(&exported).expect("import");
+        assert_eq!(imported.len(), 1);
+        assert_eq!(imported[0].to_line(), todo.to_line());
+    }
+
+    #[test]
+    fn export_orders_by_due_date_then_id() {
+        let undated = Todo::from_str("- [_] Someday (id: 123e4567-e89b-12d3-a456-426614174000)");
+        let later = Todo::from_str(
+            "- [_] Later (due: 2026-03-02T09:00:00Z) (id: 123e4567-e89b-12d3-a456-426614174001)",
+        );
+        let sooner_b = Todo::from_str(
+            "- [_] Sooner b (due: 2026-03-01T09:00:00Z) (id: 123e4567-e89b-12d3-a456-426614174003)",
+        );
+        let sooner_a = Todo::from_str(
+            "- [_] Sooner a (due: 2026-03-01T09:00:00Z) (id: 123e4567-e89b-12d3-a456-426614174002)",
+        );
+
+        let exported =
+            export_document(&[&undated, &later, &sooner_b, &sooner_a], true).expect("export");
+        let value: Value = serde_json::from_str(&exported).expect("valid json");
+        let names = value["todos"]
+            .as_array()
+            .expect("todos array")
+            .iter()
+            .map(|todo| todo["name"].as_str().unwrap_or_default())
+            .collect::<Vec<_>>();
+        assert_eq!(names, ["Sooner a", "Sooner b", "Later", "Someday"]);
+        assert!(exported.contains("\n  \"schema_version\": 1"));
+    }
+
+    #[test]
+    fn unversioned_array_is_migrated() {
+        let input = format!(r#"[{{"id":"{ID}","name":"Legacy","done":true,"reccurence":null}}]"#);
+        let imported = import_document(&input).expect("import legacy array");
+        assert_eq!(imported[0].name(), "Legacy");
+        assert!(imported[0].done());
+    }
+
+    #[test]
+    fn future_schema_version_is_rejected() {
+        let error = import_document(r#"{"schema_version":2,"todos":[]}"#)
+            .expect_err("newer schema must fail");
+        assert!(error.to_string().contains("upgrade todo_md"));
+    }
+
+    #[test]
+    fn missing_schema_version_is_rejected() {
+        let error = import_document(r#"{"todos":[]}"#).expect_err("missing version must fail");
+        assert!(error.to_string().contains("schema_version"));
+    }
+
+    #[test]
+    fn merge_updates_known_ids_and_appends_new_ones() {
+        let content = format!("# Todos\n- [_] Old name (id: {ID})\n");
+        let renamed = Todo::from_str(&format!("- [_] New name (id: {ID})"));
+        let added = Todo::from_str("- [_] Brand new (id: 123e4567-e89b-12d3-a456-426614174002)");
+
+        let (merged, changes) = merge_into_content(&content, &[renamed, added]);
+        assert_eq!(
+            merged,
+            format!(
+                "# Todos\n- [_] New name (id: {ID})\n- [_] Brand new (id: 123e4567-e89b-12d3-a456-426614174002)\n"
+            )
+        );
+        assert_eq!((changes.added, changes.updated), (1, 1));
+    }
+
+    #[test]
+    fn missing_ids_are_generated() {
+        let imported = import_document(r#"[{"name":"Water plants"},{"name":"Call mom"}]"#)
+            .expect("import without ids");
+        assert_eq!(imported.len(), 2);
+        assert_ne!(imported[0].id(), imported[1].id());
+
+        let (merged, changes) = merge_into_content("", &imported);
+        assert_eq!(changes.added, 2);
+        assert!(merged.contains("- [_] Water plants (created: "));
+    }
+
+    #[test]
+    fn malformed_entry_names_its_index() {
+        let input = format!(r#"[{{"id":"{ID}","name":"Fine"}},{{"name":"Bad","done":"yes"}}]"#);
+        let error = import_document(&input).expect_err("bad entry must fail");
+        assert_eq!(error.to_string(), "invalid todo at index 1");
+    }
+}
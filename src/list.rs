@@ -0,0 +1,911 @@
+use std::cmp::Ordering;
+
+use anyhow::{bail, Result};
+use chrono::prelude::*;
+
+use crate::date_parser::{configured_due_soon_days, humanize_relative, in_home_zone};
+use crate::storage::ParsedTodoFile;
+use crate::types::Todo;
+
+/// Returns todos in the order `list` prints them: `#pinned` items first, then
+/// within each group dated items by due date, undated items last, ties broken
+/// by name and then id so output is stable.
+pub fn sorted_todos(parsed: &ParsedTodoFile) -> Vec<&Todo> {
+    let mut todos = parsed.todos_in_order();
+    todos.sort_by(|a, b| compare_for_list(a, b));
+    todos
+}
+
+/// Which todos `list` includes with respect to `#someday` items.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ListScope {
+    /// Everything except someday items (the default).
+    Active,
+    /// Only someday items (`--someday`).
+    Someday,
+    /// Everything (`--all`).
+    All,
+}
+
+impl ListScope {
+    fn includes(self, todo: &Todo) -> bool {
+        match self {
+            ListScope::Active => !todo.is_someday(),
+            ListScope::Someday => todo.is_someday(),
+            ListScope::All => true,
+        }
+    }
+}
+
+/// The todos `list` shows, in order; `done --line` indexes into the same view.
+pub fn visible_todos(
+    parsed: &ParsedTodoFile,
+    available_only: bool,
+    scope: ListScope,
+    now: DateTime<Utc>,
+) -> Vec<&Todo> {
+    sorted_todos(parsed)
+        .into_iter()
+        .filter(|todo| scope.includes(todo))
+        .filter(|todo| !available_only || is_available(todo, now))
+        .collect()
+}
+
+/// Open, dated and past due. Someday items are never overdue.
+pub fn is_overdue(todo: &Todo, now: DateTime<Utc>) -> bool {
+    !todo.done() && !todo.is_someday() && todo.due_date().is_some_and(|due| due < now)
+}
+
+/// Open, not overdue and due by the end of the configured
+/// `TODOS_DUE_SOON_DAYS` window. Every view buckets "soon" through this.
+pub fn is_due_soon(todo: &Todo, now: DateTime<Utc>) -> bool {
+    is_due_soon_within(todo, now, configured_due_soon_days())
+}
+
+/// [`is_due_soon`] with an explicit window: due today counts with `days` = 0.
+pub fn is_due_soon_within(todo: &Todo, now: DateTime<Utc>, days: u32) -> bool {
+    if todo.done() || todo.is_someday() {
+        return false;
+    }
+    let Some(due) = todo.due_date().filter(|due| *due >= now) else {
+        return false;
+    };
+    let today = in_home_zone(now).date_naive();
+    let last_day = today + chrono::Days::new(u64::from(days));
+    in_home_zone(due).date_naive() <= last_day
+}
+
+/// How long an overdue todo has been waiting, for `list --group-by age`.
+/// Ordered most neglected first.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum OverdueAge {
+    OverMonth,
+    OverWeek,
+    Week,
+    Day,
+}
+
+impl OverdueAge {
+    /// Buckets by [`days_late`].
+    fn of(late: chrono::Duration) -> OverdueAge {
+        match days_late(late) {
+            ..=1 => OverdueAge::Day,
+            2..=7 => OverdueAge::Week,
+            8..=30 => OverdueAge::OverWeek,
+            _ => OverdueAge::OverMonth,
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            OverdueAge::Day => "1 day",
+            OverdueAge::Week => "2-7 days",
+            OverdueAge::OverWeek => "over a week",
+            OverdueAge::OverMonth => "over a month",
+        }
+    }
+}
+
+/// Whole days late, rounded up: a todo due an hour ago is 1 day overdue.
+fn days_late(late: chrono::Duration) -> i64 {
+    (late.num_seconds() + 86_399) / 86_400
+}
+
+/// The `overdue` report: overdue todos among `todos`, latest first, with
+/// how many days late each is (see [`days_late`]). Recurring todos count
+/// from their current due date.
+pub fn overdue_by_lateness<'a>(todos: &[&'a Todo], now: DateTime<Utc>) -> Vec<(&'a Todo, i64)> {
+    let mut overdue = todos
+        .iter()
+        .copied()
+        .filter(|todo| is_overdue(todo, now))
+        .filter_map(|todo| Some((todo, days_late(now - todo.due_date()?))))
+        .collect::<Vec<_>>();
+    overdue.sort_by_key(|(todo, _)| todo.due_date());
+    overdue
+}
+
+/// Overdue todos among `todos` grouped by [`OverdueAge`], most neglected
+/// bucket first; each bucket keeps the input order. Empty buckets are left out.
+pub fn group_overdue_by_age<'a>(
+    todos: &[&'a Todo],
+    now: DateTime<Utc>,
+) -> Vec<(OverdueAge, Vec<&'a Todo>)> {
+    let mut groups: Vec<(OverdueAge, Vec<&'a Todo>)> = Vec::new();
+    for todo in todos.iter().copied().filter(|todo| is_overdue(todo, now)) {
+        let Some(due) = todo.due_date() else {
+            continue;
+        };
+        let age = OverdueAge::of(now - due);
+        match groups.iter_mut().find(|(bucket, _)| *bucket == age) {
+            Some((_, members)) => members.push(todo),
+            None => groups.push((age, vec![todo])),
+        }
+    }
+    groups.sort_by_key(|(bucket, _)| *bucket);
+    groups
+}
+
+/// One row of the `tags` report.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TagSummary {
+    /// The first spelling seen; case variants are counted together.
+    pub tag: String,
+    pub open: usize,
+    pub overdue: usize,
+    pub done: usize,
+}
+
+/// Every tag used by `todos` with its counts, most open todos first, then by
+/// tag name.
+pub fn tag_summaries(todos: &[&Todo], now: DateTime<Utc>) -> Vec<TagSummary> {
+    let mut summaries: Vec<TagSummary> = Vec::new();
+    for todo in todos {
+        for tag in todo.tags() {
+            let index = match summaries
+                .iter()
+                .position(|summary| summary.tag.to_lowercase() == tag.to_lowercase())
+            {
+                Some(index) => index,
+                None => {
+                    summaries.push(TagSummary {
+                        tag,
+                        open: 0,
+                        overdue: 0,
+                        done: 0,
+                    });
+                    summaries.len() - 1
+                }
+            };
+            let summary = &mut summaries[index];
+            if todo.done() {
+                summary.done += 1;
+            } else {
+                summary.open += 1;
+                if is_overdue(todo, now) {
+                    summary.overdue += 1;
+                }
+            }
+        }
+    }
+    summaries.sort_by(|a, b| {
+        b.open
+            .cmp(&a.open)
+            .then_with(|| a.tag.to_lowercase().cmp(&b.tag.to_lowercase()))
+    });
+    summaries
+}
+
+fn compare_for_list(a: &Todo, b: &Todo) -> Ordering {
+    let by_due = match (a.due_date(), b.due_date()) {
+        (Some(a_due), Some(b_due)) => a_due.cmp(&b_due),
+        (Some(_), None) => Ordering::Less,
+        (None, Some(_)) => Ordering::Greater,
+        (None, None) => Ordering::Equal,
+    };
+
+    b.is_pinned()
+        .cmp(&a.is_pinned())
+        .then(by_due)
+        .then_with(|| a.name().cmp(&b.name()))
+        .then_with(|| a.id().cmp(&b.id()))
+}
+
+/// `list --due-before`: due before `cutoff`, or undated. Undated todos stay
+/// in the view and, as everywhere in `list`, sort after dated ones.
+pub fn is_due_before(todo: &Todo, cutoff: DateTime<Utc>) -> bool {
+    todo.due_date().is_none_or(|due| due < cutoff)
+}
+
+/// False while a todo's scheduled (defer) date is still in the future.
+pub fn is_available(todo: &Todo, now: DateTime<Utc>) -> bool {
+    todo.scheduled().is_none_or(|scheduled| scheduled <= now)
+}
+
+pub fn human_line(todo: &Todo, now: DateTime<Utc>) -> String {
+    format!(
+        "[{}] {}",
+        if todo.done() { "x" } else { " " },
+        todo_summary(todo, now)
+    )
+}
+
+/// Like [`human_line`], but the checkbox is replaced by a fixed-width column of
+/// status emoji so names stay aligned even though emoji render double-width.
+pub fn emoji_line(todo: &Todo, now: DateTime<Utc>) -> String {
+    let markers = status_markers(todo, now);
+    let padding = MARKER_COLUMN_WIDTH.saturating_sub(display_width(&markers));
+    format!(
+        "{markers}{} {}",
+        " ".repeat(padding),
+        todo_summary(todo, now)
+    )
+}
+
+const MARKER_COLUMN_WIDTH: usize = 4;
+
+/// ✅ done, 🔴 overdue, ⏰ due later today, 🟡 due soon, 🔁 recurring.
+pub fn status_markers(todo: &Todo, now: DateTime<Utc>) -> String {
+    let mut markers = String::new();
+    if todo.done() {
+        markers.push('✅');
+    } else if let Some(due_date) = todo.due_date() {
+        if is_overdue(todo, now) {
+            markers.push('🔴');
+        } else if due_date >= now
+            && in_home_zone(due_date).date_naive() == in_home_zone(now).date_naive()
+        {
+            markers.push('⏰');
+        } else if is_due_soon(todo, now) {
+            markers.push('🟡');
+        }
+    }
+
+    if todo.recurence().is_some() {
+        markers.push('🔁');
+    }
+    markers
+}
+
+/// Terminal column count: emoji and East Asian wide characters take two
+/// columns, joiners, variation selectors and combining marks none.
+fn display_width(value: &str) -> usize {
+    value.chars().map(char_width).sum()
+}
+
+fn char_width(ch: char) -> usize {
+    match ch as u32 {
+        0x0300..=0x036F | 0x200B..=0x200F | 0x20D0..=0x20FF | 0xFE00..=0xFE0F => 0,
+        // Emoji-presentation symbols below the emoji planes.
+        0x231A..=0x231B
+        | 0x23E9..=0x23EC
+        | 0x23F0
+        | 0x23F3
+        | 0x25FD..=0x25FE
+        | 0x2614..=0x2615
+        | 0x2648..=0x2653
+        | 0x267F
+        | 0x2693
+        | 0x26A1
+        | 0x26AA..=0x26AB
+        | 0x26BD..=0x26BE
+        | 0x26C4..=0x26C5
+        | 0x26CE
+        | 0x26D4
+        | 0x26EA
+        | 0x26F2..=0x26F3
+        | 0x26F5
+        | 0x26FA
+        | 0x26FD
+        | 0x2705
+        | 0x270A..=0x270B
+        | 0x2728
+        | 0x274C
+        | 0x274E
+        | 0x2753..=0x2755
+        | 0x2757
+        | 0x2795..=0x2797
+        | 0x27B0
+        | 0x27BF
+        | 0x2B1B..=0x2B1C
+        | 0x2B50
+        | 0x2B55 => 2,
+        // Hangul, CJK, kana, fullwidth forms.
+        0x1100..=0x115F
+        | 0x2E80..=0x303E
+        | 0x3041..=0x33FF
+        | 0x3400..=0x4DBF
+        | 0x4E00..=0x9FFF
+        | 0xA000..=0xA4CF
+        | 0xAC00..=0xD7A3
+        | 0xF900..=0xFAFF
+        | 0xFE30..=0xFE4F
+        | 0xFF00..=0xFF60
+        | 0xFFE0..=0xFFE6
+        | 0x20000..=0x3FFFD => 2,
+        // Emoji blocks.
+        0x1F300..=0x1F64F | 0x1F680..=0x1F6FF | 0x1F7E0..=0x1F7EB | 0x1F900..=0x1FAFF => 2,
+        _ => 1,
+    }
+}
+
+fn todo_summary(todo: &Todo, now: DateTime<Utc>) -> String {
+    let id = todo.id().to_string();
+    let mut line = format!("{} {}", &id[..8], todo.name());
+
+    if let Some(due_date) = todo.due_date() {
+        let local_due = in_home_zone(due_date);
+        line.push_str(&format!(
+            " (due: {}, {})",
+            local_due.format("%Y-%m-%d %I:%M %p"),
+            humanize_relative(due_date, now)
+        ));
+    }
+
+    if let Some(scheduled) = todo.scheduled() {
+        let local_scheduled = in_home_zone(scheduled);
+        line.push_str(&format!(
+            " (scheduled: {})",
+            local_scheduled.format("%Y-%m-%d %I:%M %p")
+        ));
+    }
+
+    if let Some(reccurence) = todo.recurrence_text() {
+        line.push_str(&format!(" (reccurence: {reccurence})"));
+    }
+
+    if let Some(priority) = todo.priority() {
+        line.push_str(&format!(" (priority: {})", priority.as_str()));
+    }
+
+    if let Some(external_id) = todo.external_id() {
+        line.push_str(&format!(" (ext: {external_id})"));
+    }
+
+    line
+}
+
+/// A field `list --format table` can show.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Column {
+    Id,
+    Status,
+    Due,
+    Scheduled,
+    Recurrence,
+    Ext,
+    Tags,
+    Name,
+}
+
+pub const DEFAULT_COLUMNS: [Column; 4] = [Column::Id, Column::Status, Column::Due, Column::Name];
+
+const COLUMN_NAMES: [(&str, Column); 8] = [
+    ("id", Column::Id),
+    ("status", Column::Status),
+    ("due", Column::Due),
+    ("scheduled", Column::Scheduled),
+    ("recurrence", Column::Recurrence),
+    ("ext", Column::Ext),
+    ("tags", Column::Tags),
+    ("name", Column::Name),
+];
+
+impl Column {
+    fn header(self) -> &'static str {
+        COLUMN_NAMES
+            .iter()
+            .find(|(_, column)| *column == self)
+            .map_or("", |(name, _)| name)
+    }
+
+    fn value(self, todo: &Todo, now: DateTime<Utc>) -> String {
+        let date = |value: Option<DateTime<Utc>>| {
+            value.map_or("-".to_string(), |value| {
+                in_home_zone(value).format("%Y-%m-%d %H:%M").to_string()
+            })
+        };
+        match self {
+            Column::Id => todo.id().to_string()[..8].to_string(),
+            Column::Status if todo.done() => "done".to_string(),
+            Column::Status if is_overdue(todo, now) => "overdue".to_string(),
+            Column::Status if is_due_soon(todo, now) => "soon".to_string(),
+            Column::Status => "open".to_string(),
+            Column::Due => date(todo.due_date()),
+            Column::Scheduled => date(todo.scheduled()),
+            Column::Recurrence => todo.recurrence_text().unwrap_or("-".to_string()),
+            Column::Ext => todo.external_id().unwrap_or("-").to_string(),
+            Column::Tags if todo.tags().is_empty() => "-".to_string(),
+            Column::Tags => todo.tags().join(","),
+            Column::Name => todo.name(),
+        }
+    }
+
+    /// The value for scripts (`get --field`): full id, `open`/`done`, RFC 3339
+    /// dates and an empty string when unset.
+    pub fn script_value(self, todo: &Todo) -> String {
+        let date = |value: Option<DateTime<Utc>>| {
+            value
+                .map(|value| value.to_rfc3339_opts(SecondsFormat::Secs, true))
+                .unwrap_or_default()
+        };
+        match self {
+            Column::Id => todo.id().to_string(),
+            Column::Status => if todo.done() { "done" } else { "open" }.to_string(),
+            Column::Due => date(todo.due_date()),
+            Column::Scheduled => date(todo.scheduled()),
+            Column::Recurrence => todo.recurrence_text().unwrap_or_default(),
+            Column::Ext => escape_porcelain_field(todo.external_id().unwrap_or_default()),
+            Column::Tags => todo.tags().join(","),
+            Column::Name => escape_porcelain_field(&todo.name()),
+        }
+    }
+}
+
+/// Parses a comma-separated `--columns` value, keeping the given order.
+pub fn parse_columns(raw: &str) -> Result<Vec<Column>> {
+    parse_column_names(raw, "column")
+}
+
+/// Parses a comma-separated `get --field` value, keeping the given order.
+pub fn parse_fields(raw: &str) -> Result<Vec<Column>> {
+    parse_column_names(raw, "field")
+}
+
+fn parse_column_names(raw: &str, noun: &str) -> Result<Vec<Column>> {
+    let mut columns = Vec::new();
+    for name in raw
+        .split(',')
+        .map(str::trim)
+        .filter(|name| !name.is_empty())
+    {
+        let Some((_, column)) = COLUMN_NAMES
+            .iter()
+            .find(|(known, _)| known.eq_ignore_ascii_case(name))
+        else {
+            let known = COLUMN_NAMES
+                .iter()
+                .map(|(known, _)| *known)
+                .collect::<Vec<_>>()
+                .join(", ");
+            bail!("unknown {noun} `{name}`; expected one of {known}");
+        };
+        columns.push(*column);
+    }
+    if columns.is_empty() {
+        bail!("expected at least one {noun}");
+    }
+    Ok(columns)
+}
+
+/// Renders `todos` as a table with a header row, each column padded to its
+/// widest cell (emoji count double) and no padding after the last column.
+pub fn render_table(todos: &[&Todo], columns: &[Column], now: DateTime<Utc>) -> String {
+    let mut rows = vec![columns
+        .iter()
+        .map(|column| column.header().to_uppercase())
+        .collect::<Vec<_>>()];
+    rows.extend(todos.iter().map(|todo| {
+        columns
+            .iter()
+            .map(|column| column.value(todo, now))
+            .collect::<Vec<_>>()
+    }));
+
+    let widths = (0..columns.len())
+        .map(|index| {
+            rows.iter()
+                .map(|row| display_width(&row[index]))
+                .max()
+                .unwrap_or(0)
+        })
+        .collect::<Vec<_>>();
+
+    let mut out = String::new();
+    for row in rows {
+        let mut line = String::new();
+        for (index, cell) in row.iter().enumerate() {
+            if index > 0 {
+                line.push_str("  ");
+            }
+            line.push_str(cell);
+            if index + 1 < row.len() {
+                line.push_str(&" ".repeat(widths[index] - display_width(cell)));
+            }
+        }
+        out.push_str(&line);
+        out.push('\n');
+    }
+    out
+}
+
+/// Tab-separated `id, status, due, name` columns. The column set and order are
+/// a scripting contract: new fields must be appended, never inserted.
+pub fn porcelain_line(todo: &Todo) -> String {
+    let due = todo
+        .due_date()
+        .map(|due_date| due_date.to_rfc3339_opts(SecondsFormat::Secs, true))
+        .unwrap_or_default();
+
+    [
+        todo.id().to_string(),
+        if todo.done() { "done" } else { "open" }.to_string(),
+        due,
+        escape_porcelain_field(&todo.name()),
+    ]
+    .join("\t")
+}
+
+/// Escapes backslash, tab, newline and carriage return so every record stays
+/// on one line with a fixed number of columns.
+pub fn escape_porcelain_field(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len());
+    for ch in value.chars() {
+        match ch {
+            '\\' => escaped.push_str("\\\\"),
+            '\t' => escaped.push_str("\\t"),
+            '\n' => escaped.push_str("\\n"),
+            '\r' => escaped.push_str("\\r"),
+            _ => escaped.push(ch),
+        }
+    }
+    escaped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::date_parser::pin_home_zone;
+    use crate::storage::parse_todo_content;
+
+    #[test]
+    fn porcelain_line_has_fixed_columns() {
+        let todo = Todo::from_str(
+            "- [_] Pay rent (due: 2026-03-01T12:00:00Z) (id: 123e4567-e89b-12d3-a456-426614174000)",
+        );
+
+        assert_eq!(
+            porcelain_line(&todo),
+            "123e4567-e89b-12d3-a456-426614174000\topen\t2026-03-01T12:00:00Z\tPay rent"
+        );
+    }
+
+    #[test]
+    fn porcelain_line_leaves_due_column_empty_when_undated() {
+        let todo = Todo::from_str("- [x] Done thing (id: 123e4567-e89b-12d3-a456-426614174000)");
+        let line = porcelain_line(&todo);
+
+        assert_eq!(line.split('\t').count(), 4);
+        assert_eq!(line.split('\t').nth(2), Some(""));
+        assert_eq!(line.split('\t').nth(1), Some("done"));
+    }
+
+    #[test]
+    fn escapes_tabs_newlines_and_backslashes() {
+        assert_eq!(escape_porcelain_field("a\tb\nc\\d"), "a\\tb\\nc\\\\d");
+    }
+
+    #[test]
+    fn emoji_markers_reflect_todo_state() {
+        let now = DateTime::parse_from_rfc3339("2026-03-01T12:00:00Z")
+            .expect("valid timestamp")
+            .with_timezone(&Utc);
+        let overdue = Todo::from_str(
+            "- [_] Late (due: 2026-02-20T12:00:00Z) (reccurence: daily) (id: 123e4567-e89b-12d3-a456-426614174000)",
+        );
+        let done = Todo::from_str("- [x] Shipped (id: 123e4567-e89b-12d3-a456-426614174001)");
+
+        assert_eq!(status_markers(&overdue, now), "🔴🔁");
+        assert_eq!(status_markers(&done, now), "✅");
+    }
+
+    #[test]
+    fn due_soon_follows_the_window() {
+        let _zone = pin_home_zone(chrono_tz::UTC);
+        let now = Utc.with_ymd_and_hms(2026, 2, 23, 18, 0, 0).unwrap();
+        let todo = |due: &str| {
+            Todo::from_str(&format!(
+                "- [_] Task (due: {due}) (id: 123e4567-e89b-12d3-a456-426614174000)"
+            ))
+        };
+
+        assert!(is_due_soon_within(&todo("2026-02-23T20:00:00Z"), now, 2));
+        assert!(is_due_soon_within(&todo("2026-02-25T23:00:00Z"), now, 2));
+        assert!(!is_due_soon_within(&todo("2026-02-26T09:00:00Z"), now, 2));
+        assert!(is_due_soon_within(&todo("2026-02-26T09:00:00Z"), now, 3));
+        assert!(!is_due_soon_within(&todo("2026-02-23T09:00:00Z"), now, 2));
+        assert!(!is_due_soon_within(&todo("2026-02-24T09:00:00Z"), now, 0));
+    }
+
+    #[test]
+    fn due_before_keeps_undated_todos_last() {
+        let parsed = parse_todo_content(
+            "- [_] Someday maybe (id: 123e4567-e89b-12d3-a456-426614174000)\n\
+             - [_] Next month (due: 2026-04-01T12:00:00Z) (id: 123e4567-e89b-12d3-a456-426614174001)\n\
+             - [_] This week (due: 2026-02-26T12:00:00Z) (id: 123e4567-e89b-12d3-a456-426614174002)\n",
+        );
+        let cutoff = Utc.with_ymd_and_hms(2026, 3, 1, 0, 0, 0).unwrap();
+        let names = sorted_todos(&parsed)
+            .into_iter()
+            .filter(|todo| is_due_before(todo, cutoff))
+            .map(Todo::name)
+            .collect::<Vec<_>>();
+
+        assert_eq!(names, ["This week", "Someday maybe"]);
+    }
+
+    #[test]
+    fn overdue_report_orders_by_lateness_and_excludes_due_now() {
+        let now = Utc.with_ymd_and_hms(2026, 2, 23, 18, 0, 0).unwrap();
+        let parsed = parse_todo_content(
+            "- [_] Due now (due: 2026-02-23T18:00:00Z) (id: 123e4567-e89b-12d3-a456-426614174000)\n\
+             - [_] Second late (due: 2026-02-23T17:59:59Z) (id: 123e4567-e89b-12d3-a456-426614174001)\n\
+             - [_] Gym (due: 2026-02-20T18:00:00Z) (reccurence: daily) (id: 123e4567-e89b-12d3-a456-426614174002)\n\
+             - [x] Done late (due: 2026-01-01T12:00:00Z) (id: 123e4567-e89b-12d3-a456-426614174003)\n",
+        );
+        let report = overdue_by_lateness(&sorted_todos(&parsed), now)
+            .into_iter()
+            .map(|(todo, days)| (todo.name(), days))
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            report,
+            [("Gym".to_string(), 3), ("Second late".to_string(), 1)]
+        );
+    }
+
+    #[test]
+    fn groups_overdue_todos_by_age() {
+        let now = Utc.with_ymd_and_hms(2026, 2, 23, 18, 0, 0).unwrap();
+        let parsed = parse_todo_content(
+            "- [_] Hour late (due: 2026-02-23T17:00:00Z) (id: 123e4567-e89b-12d3-a456-426614174000)\n\
+             - [_] Day late (due: 2026-02-22T18:00:00Z) (id: 123e4567-e89b-12d3-a456-426614174001)\n\
+             - [_] Three days (due: 2026-02-20T18:00:00Z) (id: 123e4567-e89b-12d3-a456-426614174002)\n\
+             - [_] Week and a bit (due: 2026-02-16T12:00:00Z) (id: 123e4567-e89b-12d3-a456-426614174003)\n\
+             - [_] Ancient (due: 2026-01-01T12:00:00Z) (id: 123e4567-e89b-12d3-a456-426614174004)\n\
+             - [x] Done late (due: 2026-01-01T12:00:00Z) (id: 123e4567-e89b-12d3-a456-426614174005)\n\
+             - [_] Upcoming (due: 2026-02-24T12:00:00Z) (id: 123e4567-e89b-12d3-a456-426614174006)\n",
+        );
+        let groups = group_overdue_by_age(&sorted_todos(&parsed), now)
+            .into_iter()
+            .map(|(bucket, todos)| {
+                (
+                    bucket.label(),
+                    todos.into_iter().map(Todo::name).collect::<Vec<_>>(),
+                )
+            })
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            groups,
+            [
+                ("over a month", vec!["Ancient".to_string()]),
+                ("over a week", vec!["Week and a bit".to_string()]),
+                ("2-7 days", vec!["Three days".to_string()]),
+                (
+                    "1 day",
+                    vec!["Day late".to_string(), "Hour late".to_string()]
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn someday_todos_are_never_overdue() {
+        let now = DateTime::parse_from_rfc3339("2026-03-01T12:00:00Z")
+            .expect("valid timestamp")
+            .with_timezone(&Utc);
+        let someday = Todo::from_str(
+            "- [_] Learn piano #Someday (due: 2026-02-20T12:00:00Z) (id: 123e4567-e89b-12d3-a456-426614174000)",
+        );
+
+        assert!(someday.is_someday());
+        assert!(!is_overdue(&someday, now));
+        assert_eq!(status_markers(&someday, now), "");
+    }
+
+    #[test]
+    fn list_scope_filters_someday_todos() {
+        let parsed = parse_todo_content(
+            "- [_] Pay rent (id: 123e4567-e89b-12d3-a456-426614174000)\n\
+             - [_] Learn piano #someday (id: 123e4567-e89b-12d3-a456-426614174001)\n\
+             - [_] Tag#someday is not a tag (id: 123e4567-e89b-12d3-a456-426614174002)\n",
+        );
+        let now = Utc::now();
+        let names = |scope| {
+            visible_todos(&parsed, false, scope, now)
+                .into_iter()
+                .map(Todo::name)
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(
+            names(ListScope::Active),
+            ["Pay rent", "Tag#someday is not a tag"]
+        );
+        assert_eq!(names(ListScope::Someday), ["Learn piano #someday"]);
+        assert_eq!(names(ListScope::All).len(), 3);
+    }
+
+    #[test]
+    fn table_shows_selected_columns_in_order() {
+        let now = DateTime::parse_from_rfc3339("2026-03-01T12:00:00Z")
+            .expect("valid timestamp")
+            .with_timezone(&Utc);
+        let late = Todo::from_str(
+            "- [_] Pay rent (due: 2026-02-28T09:00:00Z) (reccurence: monthly) (id: 123e4567-e89b-12d3-a456-426614174000)",
+        );
+        let undated = Todo::from_str("- [x] Call mom (id: 223e4567-e89b-12d3-a456-426614174001)");
+        let columns = parse_columns("name, recurrence,STATUS").expect("valid columns");
+
+        assert_eq!(columns, [Column::Name, Column::Recurrence, Column::Status]);
+        assert_eq!(
+            render_table(&[&late, &undated], &columns, now),
+            "NAME      RECURRENCE  STATUS\n\
+             Pay rent  monthly     overdue\n\
+             Call mom  -           done\n"
+        );
+    }
+
+    #[test]
+    fn table_pads_emoji_and_box_drawing_names_by_display_width() {
+        let now = DateTime::parse_from_rfc3339("2026-03-01T12:00:00Z")
+            .expect("valid timestamp")
+            .with_timezone(&Utc);
+        let party = Todo::from_str(
+            "- [_] Party 🎉 (reccurence: yearly) (id: 123e4567-e89b-12d3-a456-426614174000)",
+        );
+        let heart = Todo::from_str(
+            "- [_] Call ❤\u{fe0f} mom (reccurence: daily) (id: 123e4567-e89b-12d3-a456-426614174001)",
+        );
+        let family =
+            Todo::from_str("- [_] 👩\u{200d}👧 day (id: 123e4567-e89b-12d3-a456-426614174002)");
+        let boxed = Todo::from_str("- [_] ├─ step (id: 123e4567-e89b-12d3-a456-426614174003)");
+        let columns = parse_columns("name,recurrence").expect("valid columns");
+
+        assert_eq!(display_width("Party 🎉"), 8);
+        assert_eq!(display_width("Call ❤\u{fe0f} mom"), 10);
+        assert_eq!(display_width("├─ step"), 7);
+        assert_eq!(
+            render_table(&[&party, &heart, &family, &boxed], &columns, now),
+            "NAME        RECURRENCE\n\
+             Party 🎉    yearly\n\
+             Call ❤\u{fe0f} mom  daily\n\
+             👩\u{200d}👧 day    -\n\
+             ├─ step     -\n"
+        );
+    }
+
+    #[test]
+    fn script_values_are_raw_and_tab_separated() {
+        let todo = Todo::from_str(
+            "- [_] Pay rent (due: 2026-02-28T09:00:00Z) (id: 123e4567-e89b-12d3-a456-426614174000)",
+        );
+        let fields = parse_fields("id,due,recurrence,status").expect("valid fields");
+        let values = fields
+            .iter()
+            .map(|field| field.script_value(&todo))
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            values.join("\t"),
+            "123e4567-e89b-12d3-a456-426614174000\t2026-02-28T09:00:00Z\t\topen"
+        );
+        assert!(parse_fields("due,priority")
+            .expect_err("unknown field")
+            .to_string()
+            .contains("unknown field `priority`"));
+    }
+
+    #[test]
+    fn rejects_unknown_columns() {
+        let error = parse_columns("id,priority").expect_err("unknown column");
+        assert!(error.to_string().contains("unknown column `priority`"));
+        assert!(parse_columns(" , ").is_err());
+    }
+
+    #[test]
+    fn emoji_lines_align_names() {
+        let now = DateTime::parse_from_rfc3339("2026-03-01T12:00:00Z")
+            .expect("valid timestamp")
+            .with_timezone(&Utc);
+        let plain = Todo::from_str("- [_] Plain (id: 123e4567-e89b-12d3-a456-426614174000)");
+        let done = Todo::from_str("- [x] Done (id: 123e4567-e89b-12d3-a456-426614174001)");
+
+        let plain_line = emoji_line(&plain, now);
+        let done_line = emoji_line(&done, now);
+        let plain_prefix = plain_line.split("123e4567").next().expect("prefix");
+        let done_prefix = done_line.split("123e4567").next().expect("prefix");
+        assert_eq!(display_width(plain_prefix), display_width(done_prefix));
+    }
+
+    #[test]
+    fn pinned_todos_sort_first() {
+        let parsed = parse_todo_content(
+            "- [_] Soon (due: 2026-03-01T12:00:00Z) (id: 123e4567-e89b-12d3-a456-426614174001)\n\
+             - [_] Focus later #pinned (due: 2026-06-01T12:00:00Z) (id: 123e4567-e89b-12d3-a456-426614174002)\n\
+             - [_] Focus #Pinned (id: 123e4567-e89b-12d3-a456-426614174003)\n\
+             - [_] Undated (id: 123e4567-e89b-12d3-a456-426614174004)\n",
+        );
+        let names = sorted_todos(&parsed)
+            .into_iter()
+            .map(Todo::name)
+            .collect::<Vec<_>>();
+        assert_eq!(
+            names,
+            ["Focus later #pinned", "Focus #Pinned", "Soon", "Undated"]
+        );
+        let pinned = sorted_todos(&parsed)[1];
+        assert!(Todo::from_str(&pinned.to_line()).is_pinned());
+    }
+
+    #[test]
+    fn tag_summaries_count_case_variants_together() {
+        let parsed = parse_todo_content(
+            "- [_] Fix deploy #work (due: 2026-02-20T12:00:00Z) (id: 123e4567-e89b-12d3-a456-426614174001)\n\
+             - [_] Book flights #Travel #work (id: 123e4567-e89b-12d3-a456-426614174002)\n\
+             - [x] Send report #Work (id: 123e4567-e89b-12d3-a456-426614174003)\n\
+             - [_] Close #123 (id: 123e4567-e89b-12d3-a456-426614174004)\n",
+        );
+        let now = DateTime::parse_from_rfc3339("2026-02-23T18:00:00Z")
+            .expect("valid now")
+            .with_timezone(&Utc);
+        let todos = sorted_todos(&parsed);
+
+        let summaries = tag_summaries(&todos, now);
+        assert_eq!(
+            summaries,
+            [
+                TagSummary {
+                    tag: "work".to_string(),
+                    open: 2,
+                    overdue: 1,
+                    done: 1,
+                },
+                TagSummary {
+                    tag: "Travel".to_string(),
+                    open: 1,
+                    overdue: 0,
+                    done: 0,
+                },
+            ]
+        );
+        assert_eq!(
+            todos.iter().filter(|todo| todo.has_tag("travel")).count(),
+            1
+        );
+    }
+
+    #[test]
+    fn sorts_by_due_with_undated_last() {
+        let parsed = parse_todo_content(
+            "- [_] Later (due: 2026-03-02T12:00:00Z) (id: 123e4567-e89b-12d3-a456-426614174001)\n\
+             - [_] Undated (id: 123e4567-e89b-12d3-a456-426614174002)\n\
+             - [_] Sooner (due: 2026-03-01T12:00:00Z) (id: 123e4567-e89b-12d3-a456-426614174003)\n",
+        );
+
+        let names = sorted_todos(&parsed)
+            .into_iter()
+            .map(Todo::name)
+            .collect::<Vec<_>>();
+        assert_eq!(names, vec!["Sooner", "Later", "Undated"]);
+    }
+
+    #[test]
+    fn future_scheduled_todos_are_not_available() {
+        let now = DateTime::parse_from_rfc3339("2026-02-23T18:00:00Z")
+            .expect("valid now")
+            .with_timezone(&Utc);
+        let deferred = Todo::from_str(
+            "- [_] Later (scheduled: 2026-03-01T09:00:00Z) (id: 123e4567-e89b-12d3-a456-426614174000)",
+        );
+        let started = Todo::from_str(
+            "- [_] Started (scheduled: 2026-02-20T09:00:00Z) (id: 123e4567-e89b-12d3-a456-426614174001)",
+        );
+        let plain = Todo::from_str("- [_] Plain (id: 123e4567-e89b-12d3-a456-426614174002)");
+
+        assert!(!is_available(&deferred, now));
+        assert!(is_available(&started, now));
+        assert!(is_available(&plain, now));
+    }
+}
@@ -1,106 +1,1146 @@
+pub mod archive;
+pub mod batch;
 pub mod config;
 pub mod date_parser;
+pub mod dedupe;
 pub mod diff;
+pub mod doctor;
+pub mod edit;
+pub mod export;
+pub mod frontmatter;
+pub mod history;
+pub mod json;
+pub mod list;
+pub mod merge;
+pub mod notify;
+pub mod output;
 pub mod recurrence_parser;
+pub mod stats;
 pub mod storage;
 pub mod sync;
+pub mod trash;
 pub mod types;
+pub mod watch;
 
-use anyhow::{bail, Result};
+use std::io::Write;
+
+use anyhow::{bail, Context, Result};
+use chrono::Utc;
 use config::AppConfig;
-use storage::{format_todo_content, read_todo_file, validate_todo_content, write_todo_file_atomic};
+use storage::{
+    format_todo_content, read_todo_file, regenerate_todo_ids, validate_todo_content,
+    write_todo_file_atomic, write_todo_file_checked,
+};
 
 fn main() {
     if let Err(error) = run() {
+        // A closed pipe (e.g. `todo_md list | head`) is not worth reporting.
+        if error
+            .downcast_ref::<std::io::Error>()
+            .is_some_and(|error| error.kind() == std::io::ErrorKind::BrokenPipe)
+        {
+            return;
+        }
         eprintln!("error: {error}");
         std::process::exit(1);
     }
 }
 
 fn run() -> Result<()> {
-    let args = std::env::args().skip(1).collect::<Vec<_>>();
+    let mut args = std::env::args().skip(1).collect::<Vec<_>>();
+    // `--profile <name>` is global, so it is taken out before commands read
+    // their positional arguments.
+    if let Some(idx) = args.iter().position(|arg| arg == "--profile") {
+        let Some(name) = args.get(idx + 1).cloned() else {
+            bail!("--profile expects a profile name");
+        };
+        args.drain(idx..=idx + 1);
+        config::configure_profile(&name);
+    }
     let command = args.first().map(String::as_str).unwrap_or("help");
+    let mut out = output::Printer::stdout(has_flag(&args, "--no-color"));
+    if has_flag(&args, "--strict") {
+        storage::configure_strict(true);
+    }
 
     match command {
         "setup" => {
-            let config = AppConfig::load()?;
-            let remote_override = args.get(1).map(String::as_str);
-            sync::setup(&config, remote_override)?;
-            println!("setup complete at {}", config.config_dir.display());
-            println!("todo source: {}", config.todo_file.display());
+            let config = load_writable_config()?;
+            let local = has_flag(&args, "--local");
+            let remote_override = args
+                .iter()
+                .skip(1)
+                .map(String::as_str)
+                .find(|arg| *arg != "--local");
+            if local && remote_override.is_some() {
+                bail!("setup --local takes no remote; run `todo_md setup <remote-url>` later to add one");
+            }
+            let detected_branch = sync::setup(&config, remote_override, local)?;
+            writeln!(out, "setup complete at {}", config.config_dir.display())?;
+            if let Some(branch) = detected_branch {
+                writeln!(
+                    out,
+                    "branch: {branch} (the remote's default, saved to .env)"
+                )?;
+            }
+            writeln!(out, "todo source: {}", config.todo_file.display())?;
+            if local {
+                writeln!(
+                    out,
+                    "no remote: run `todo_md setup <remote-url>` before syncing"
+                )?;
+            }
         }
         "sync" => {
-            let config = AppConfig::load()?;
-            let result = sync::sync(&config)?;
-            println!(
-                "sync {} | added {} updated {} deleted {} completed {} | {}",
-                if result.committed {
-                    "committed"
+            let json = match flag_value(&args, "--format") {
+                None | Some("human") => false,
+                Some("json") => true,
+                Some(other) => bail!("--format expects `human` or `json`, got `{other}`"),
+            };
+            let config = load_writable_config()?;
+            let options = sync::SyncOptions {
+                skip_invalid: config.skip_invalid || has_flag(&args, "--skip-invalid"),
+                only: flag_value(&args, "--only").map(str::to_string),
+                exclude_done: has_flag(&args, "--exclude-done"),
+                commit_date: flag_value(&args, "--date")
+                    .map(|raw| {
+                        date_parser::parse_human_datetime(raw, Utc::now())
+                            .with_context(|| format!("--date expects a date, got `{raw}`"))
+                    })
+                    .transpose()?,
+                report_incoming: has_flag(&args, "--remote-only-changes"),
+                aggressive: has_flag(&args, "--aggressive"),
+            };
+            let result = sync::sync(&config, &options)?;
+            for skipped in &result.skipped {
+                eprintln!("warning: skipped invalid {skipped}");
+            }
+            if let Some(warning) = &result.env_warning {
+                eprintln!("warning: {warning}");
+            }
+            if let Some(warning) = &result.hook_warning {
+                eprintln!("warning: {warning}");
+            }
+            if json {
+                writeln!(out, "{}", result.to_json()?)?;
+                return Ok(());
+            }
+            if let Some(incoming) = &result.incoming {
+                if incoming.is_empty() {
+                    writeln!(out, "incoming from origin: no todo changes")?;
                 } else {
-                    "no local todo changes"
-                },
+                    writeln!(out, "incoming from origin:")?;
+                    for change in incoming {
+                        writeln!(out, "- {}", change.human())?;
+                    }
+                }
+            }
+            if result.pushed_pending > 0 {
+                writeln!(
+                    out,
+                    "pushing previously committed changes: {} commit(s) had not reached the remote",
+                    result.pushed_pending
+                )?;
+            }
+            writeln!(out, "{}", sync_summary(&result))?;
+            if !result.change_set.changes.is_empty() {
+                for change in &result.change_set.changes {
+                    if change.fields.is_empty() {
+                        writeln!(out, "- {:?}: {}", change.kind, change.id)?;
+                    } else {
+                        writeln!(
+                            out,
+                            "- {:?}: {}: {}",
+                            change.kind,
+                            change.id,
+                            diff::fields_text(&change.fields)
+                        )?;
+                    }
+                }
+            }
+        }
+        "undo" => {
+            let config = load_writable_config()?;
+            let result = sync::undo_last_sync(&config)?;
+            writeln!(
+                out,
+                "reverted `{}` | added {} updated {} deleted {} completed {} | run `todo_md sync` to push it",
+                result.subject,
                 result.change_set.added,
                 result.change_set.updated,
                 result.change_set.deleted,
-                result.change_set.completed,
-                result.line_summary
+                result.change_set.completed
+            )?;
+        }
+        "watch" => {
+            let config = load_writable_config()?;
+            let options = sync::SyncOptions {
+                skip_invalid: config.skip_invalid || has_flag(&args, "--skip-invalid"),
+                only: None,
+                exclude_done: false,
+                commit_date: None,
+                report_incoming: false,
+                aggressive: has_flag(&args, "--aggressive"),
+            };
+            writeln!(
+                out,
+                "watching {}; press Ctrl-C to stop",
+                config.todo_file.display()
+            )?;
+            watch::watch_todo_file(&config.todo_file, watch::DEBOUNCE, || {
+                let time = date_parser::in_home_zone(Utc::now()).format("%H:%M:%S");
+                match sync::sync(&config, &options) {
+                    Ok(result) => {
+                        if let Some(warning) = &result.hook_warning {
+                            eprintln!("warning: {warning}");
+                        }
+                        writeln!(out, "{time} {}", sync_summary(&result))?;
+                    }
+                    // Offline, mid-rebase and similar: report and wait for the
+                    // next save rather than stop watching.
+                    Err(error) => eprintln!("{time} sync failed: {error}"),
+                }
+                Ok(())
+            })?;
+            writeln!(out, "stopped watching")?;
+        }
+        "save" => {
+            let config = load_writable_config()?;
+            let parsed = read_todo_file(&config.todo_file)?;
+            let normalized = sync::normalize_content(
+                &parsed.content,
+                config.skip_invalid || has_flag(&args, "--skip-invalid"),
+            )?;
+            for skipped in &normalized.skipped {
+                eprintln!("warning: skipped invalid {skipped}");
+            }
+            if normalized.content == parsed.content {
+                writeln!(out, "todo.md is already normalized")?;
+                return Ok(());
+            }
+            write_todo_file_checked(&config.todo_file, &parsed, &normalized.content)?;
+            writeln!(
+                out,
+                "saved todo.md | {} id(s) assigned, {} line(s) normalized",
+                normalized.assigned_ids, normalized.reformatted_lines
+            )?;
+        }
+        "status" => {
+            let config = load_config()?;
+            let changes = history::status(&config)?;
+            match output_mode(&args)? {
+                ChangeOutput::Porcelain => {
+                    for change in &changes {
+                        writeln!(out, "{}", change.porcelain())?;
+                    }
+                }
+                ChangeOutput::Json => {
+                    let json = changes
+                        .iter()
+                        .map(history::NamedChange::to_json)
+                        .collect::<Vec<_>>();
+                    writeln!(out, "{}", serde_json::Value::from(json))?;
+                }
+                ChangeOutput::Human if changes.is_empty() => {
+                    writeln!(out, "no uncommitted todo changes")?;
+                }
+                ChangeOutput::Human => {
+                    for change in &changes {
+                        writeln!(out, "- {}", change.human())?;
+                    }
+                }
+            }
+        }
+        "diff" => {
+            let (Some(before), Some(after)) = (args.get(1), args.get(2)) else {
+                bail!(
+                    "usage: todo_md diff <before-file> <after-file> [--line-summary] [--porcelain|--json]"
+                );
+            };
+            let before = read_todo_file(std::path::Path::new(before))?;
+            let after = read_todo_file(std::path::Path::new(after))?;
+            let change_set = diff::semantic_changes(&before, &after);
+            let changes = history::named_changes(&before, &after);
+            let line_summary = has_flag(&args, "--line-summary")
+                .then(|| diff::line_diff_summary(&before.content, &after.content, false));
+            match output_mode(&args)? {
+                ChangeOutput::Porcelain => {
+                    for change in &changes {
+                        writeln!(out, "{}", change.porcelain())?;
+                    }
+                }
+                ChangeOutput::Json => {
+                    let mut json = serde_json::json!({
+                        "added": change_set.added,
+                        "updated": change_set.updated,
+                        "deleted": change_set.deleted,
+                        "completed": change_set.completed,
+                        "changes": changes
+                            .iter()
+                            .map(history::NamedChange::to_json)
+                            .collect::<Vec<_>>(),
+                    });
+                    if let Some(line_summary) = &line_summary {
+                        json["line_summary"] = line_summary.as_str().into();
+                    }
+                    writeln!(out, "{json}")?;
+                }
+                ChangeOutput::Human => {
+                    write!(
+                        out,
+                        "added {} updated {} deleted {} completed {}",
+                        change_set.added,
+                        change_set.updated,
+                        change_set.deleted,
+                        change_set.completed
+                    )?;
+                    match &line_summary {
+                        Some(line_summary) => writeln!(out, " | {line_summary}")?,
+                        None => writeln!(out)?,
+                    }
+                    for change in &changes {
+                        writeln!(out, "- {}", change.human())?;
+                    }
+                }
+            }
+        }
+        "verify" => {
+            let config = load_config()?;
+            let changes = history::status(&config)?;
+            if changes.is_empty() {
+                writeln!(out, "todo.md matches the last commit")?;
+                return Ok(());
+            }
+            for change in &changes {
+                writeln!(out, "- {}", change.human())?;
+            }
+            bail!(
+                "todo.md has {} uncommitted change(s); run `todo_md sync`",
+                changes.len()
             );
-            if !result.change_set.changes.is_empty() {
-                for change in &result.change_set.changes {
-                    println!("- {:?}: {}", change.kind, change.id);
+        }
+        "log" => {
+            let config = load_config()?;
+            let limit = match flag_value(&args, "-n") {
+                Some(count) => count
+                    .parse::<usize>()
+                    .ok()
+                    .filter(|count| *count > 0)
+                    .with_context(|| format!("-n expects a positive number, got `{count}`"))?,
+                None => 10,
+            };
+            let entries = history::log(&config, limit)?;
+            match output_mode(&args)? {
+                ChangeOutput::Porcelain => {
+                    for entry in &entries {
+                        for change in &entry.changes {
+                            writeln!(out, "{}\t{}", entry.commit, change.porcelain())?;
+                        }
+                    }
+                }
+                ChangeOutput::Json => {
+                    let json = entries
+                        .iter()
+                        .map(history::LogEntry::to_json)
+                        .collect::<Vec<_>>();
+                    writeln!(out, "{}", serde_json::Value::from(json))?;
+                }
+                ChangeOutput::Human => {
+                    for entry in &entries {
+                        let short = entry.commit.get(..7).unwrap_or(&entry.commit);
+                        writeln!(out, "{short} {}", entry.subject)?;
+                        for change in &entry.changes {
+                            writeln!(out, "  - {:?}: {}", change.kind, change.name)?;
+                        }
+                    }
+                }
+            }
+        }
+        "prune-remote-branch" => {
+            let config = if has_flag(&args, "--yes") {
+                load_writable_config()?
+            } else {
+                load_config()?
+            };
+            let stale = sync::stale_sync_branches(&config)?;
+            if stale.is_empty() {
+                writeln!(out, "no old todo sync branches on the remote")?;
+                return Ok(());
+            }
+            for branch in &stale {
+                if has_flag(&args, "--yes") {
+                    sync::delete_remote_branch(&config, branch)?;
+                    writeln!(out, "deleted {branch}")?;
+                } else {
+                    writeln!(out, "would delete {branch}")?;
+                }
+            }
+            if !has_flag(&args, "--yes") {
+                writeln!(out, "run again with --yes to delete these branches")?;
+            }
+        }
+        "stats" => {
+            let config = load_config()?;
+            if !has_flag(&args, "--heatmap") {
+                if args.len() > 1 {
+                    bail!("usage: todo_md stats [--heatmap [--since <date>]]");
                 }
+                let parsed = read_todo_file(&config.todo_file)?;
+                write!(out, "{}", stats::todo_stats(&parsed, Utc::now()).render())?;
+                return Ok(());
+            }
+            let today = date_parser::in_home_zone(Utc::now()).date_naive();
+            let since = match flag_value(&args, "--since") {
+                Some(raw) => date_parser::parse_human_datetime(raw, Utc::now())
+                    .map(|since| date_parser::in_home_zone(since).date_naive())
+                    .with_context(|| format!("--since expects a date, got `{raw}`"))?,
+                None => today - chrono::Duration::weeks(12),
+            };
+            let counts = history::completions_by_day(&config, since)?;
+            write!(
+                out,
+                "{}",
+                history::render_heatmap(&counts, since, today, config.week_start)
+            )?;
+        }
+        "completed" => {
+            let Some(raw) = flag_value(&args, "--since") else {
+                bail!("usage: todo_md completed --since <date>");
+            };
+            let config = load_config()?;
+            let since = date_parser::parse_human_datetime(raw, Utc::now())
+                .and_then(|since| {
+                    date_parser::in_home_zone(since)
+                        .with_time(chrono::NaiveTime::MIN)
+                        .single()
+                })
+                .with_context(|| format!("--since expects a date, got `{raw}`"))?;
+            let completions = history::completed_since(&config, since.to_utc())?;
+            if completions.is_empty() {
+                writeln!(out, "nothing completed since {}", since.date_naive())?;
+            }
+            for completion in &completions {
+                writeln!(
+                    out,
+                    "{}  {}",
+                    date_parser::in_home_zone(completion.completed_at).format("%Y-%m-%d %H:%M"),
+                    completion.name
+                )?;
+            }
+        }
+        "resolve" => {
+            if !has_flag(&args, "--interactive") {
+                bail!("usage: todo_md resolve --interactive");
+            }
+            let config = load_writable_config()?;
+            let result = merge::resolve_conflicts(&config, None, |conflict| {
+                prompt_conflict(&mut out, conflict)
+            })?;
+            writeln!(
+                out,
+                "resolved todo.md: {} merged automatically, {} chosen interactively",
+                result.auto_merged, result.prompted
+            )?;
+            if result.still_rebasing {
+                writeln!(
+                    out,
+                    "rebase stopped on another conflict; run `todo_md resolve --interactive` again"
+                )?;
+            } else if result.continued_rebase {
+                writeln!(out, "rebase continued; run `todo_md sync` to push")?;
+            } else {
+                writeln!(out, "run `todo_md sync` to commit the merged todo.md")?;
             }
         }
         "where" => {
-            let config = AppConfig::load()?;
-            println!("config: {}", config.config_dir.display());
-            println!("todo: {}", config.todo_file.display());
-            println!("env: {}", config.env_file.display());
-            println!("branch: {}", config.git_branch);
-            if let Some(remote) = config.git_remote {
-                println!("remote: {remote}");
+            let config = load_config()?;
+            if has_flag(&args, "--remote-url") {
+                writeln!(out, "{}", config::require_remote(&config)?)?;
+                return Ok(());
+            }
+            writeln!(out, "config: {}", config.config_dir.display())?;
+            writeln!(
+                out,
+                "profile: {}",
+                config.profile.as_deref().unwrap_or("default")
+            )?;
+            writeln!(out, "todo: {}", config.todo_file.display())?;
+            writeln!(out, "env: {}", config.env_file.display())?;
+            if let Some(profile_env_file) = &config.profile_env_file {
+                writeln!(out, "profile env: {}", profile_env_file.display())?;
+            }
+            writeln!(out, "branch: {}", config.git_branch)?;
+            if let Some(title) = &config.title {
+                writeln!(out, "title: {title}")?;
+            }
+            if let Some(tz) = config.tz {
+                writeln!(out, "timezone: {tz}")?;
+            } else if let Some(timezone) = config.timezone {
+                writeln!(out, "timezone: {timezone}")?;
+            }
+            if let Some(remote) = &config.git_remote {
+                writeln!(out, "remote: {remote}")?;
+            }
+            let origin = sync::origin_url(&config)?;
+            if let Some(origin) = &origin {
+                writeln!(out, "origin: {origin}")?;
+            }
+            match (&config.git_remote, &origin) {
+                (Some(remote), Some(origin)) if sync::remotes_differ(remote, origin) => {
+                    writeln!(
+                        out,
+                        "warning: TODOS_GIT_REMOTE ({remote}) differs from git origin ({origin}); sync fetches from origin but pushes to TODOS_GIT_REMOTE"
+                    )?;
+                }
+                (None, Some(origin)) => {
+                    writeln!(
+                        out,
+                        "warning: git origin is {origin} but TODOS_GIT_REMOTE is not set; sync will refuse to run"
+                    )?;
+                }
+                _ => {}
             }
             if config.github_token.is_some() {
-                println!("github token: set");
+                writeln!(out, "github token: set")?;
             }
         }
-        "doctor" => {
-            let config = AppConfig::load()?;
+        "list" => {
+            let config = load_config()?;
+            let parsed = read_todo_file(&config.todo_file)?;
+            let porcelain = has_flag(&args, "--porcelain");
+            let emoji = has_flag(&args, "--emoji") && out.color();
+            let now = Utc::now();
+            let visible = list::visible_todos(
+                &parsed,
+                has_flag(&args, "--available"),
+                list_scope(&args),
+                now,
+            );
+            let mut todos = visible.clone();
+            if has_flag(&args, "--overdue") {
+                todos.retain(|todo| list::is_overdue(todo, now));
+            }
+            match (has_flag(&args, "--open"), has_flag(&args, "--done")) {
+                (true, true) => bail!("--open and --done exclude each other"),
+                (true, false) => todos.retain(|todo| !todo.done()),
+                (false, true) => todos.retain(|todo| todo.done()),
+                (false, false) => {}
+            }
+            if let Some(raw) = flag_value(&args, "--due-before") {
+                let cutoff = date_parser::parse_human_datetime(raw, now)
+                    .with_context(|| format!("--due-before expects a date, got `{raw}`"))?;
+                todos.retain(|todo| list::is_due_before(todo, cutoff));
+            }
+            if let Some(tag) = flag_value(&args, "--tag") {
+                todos.retain(|todo| todo.has_tag(tag));
+            }
+            match flag_value(&args, "--group-by") {
+                Some("age") => {
+                    for (bucket, members) in list::group_overdue_by_age(&todos, now) {
+                        writeln!(out, "{}: {}", bucket.label(), members.len())?;
+                        for todo in members {
+                            writeln!(out, "  {}", list::human_line(todo, now))?;
+                        }
+                    }
+                    return Ok(());
+                }
+                Some(other) => bail!("--group-by expects `age`, got `{other}`"),
+                None => {}
+            }
+            let columns = flag_value(&args, "--columns")
+                .map(list::parse_columns)
+                .transpose()?;
+            // `--columns` alone implies the table format.
+            let table = match flag_value(&args, "--format") {
+                Some("table") => true,
+                Some(other) => bail!("--format expects `table`, got `{other}`"),
+                None => columns.is_some(),
+            };
+            if table {
+                let columns = columns.unwrap_or(list::DEFAULT_COLUMNS.to_vec());
+                write!(out, "{}", list::render_table(&todos, &columns, now))?;
+                return Ok(());
+            }
+            for todo in todos {
+                // Filters hide rows but keep numbers, so `done --line` still
+                // matches what was printed.
+                let index = visible
+                    .iter()
+                    .position(|shown| shown.id() == todo.id())
+                    .expect("filtered todos come from the visible list");
+                if porcelain {
+                    writeln!(out, "{}", list::porcelain_line(todo))?;
+                } else if emoji {
+                    writeln!(out, "{:>3}. {}", index + 1, list::emoji_line(todo, now))?;
+                } else {
+                    writeln!(out, "{:>3}. {}", index + 1, list::human_line(todo, now))?;
+                }
+            }
+        }
+        "overdue" => {
+            let config = load_config()?;
+            let parsed = read_todo_file(&config.todo_file)?;
+            let now = Utc::now();
+            let overdue = list::overdue_by_lateness(&list::sorted_todos(&parsed), now);
+            if overdue.is_empty() {
+                writeln!(out, "nothing overdue")?;
+                return Ok(());
+            }
+            for (todo, days) in &overdue {
+                let due = todo.due_date().expect("overdue todos have a due date");
+                writeln!(
+                    out,
+                    "{}  {}  ({days} day{} overdue)",
+                    date_parser::in_home_zone(due).format("%Y-%m-%d %I:%M %p"),
+                    todo.name(),
+                    if *days == 1 { "" } else { "s" }
+                )?;
+            }
+            // A failing exit lets shell prompts and CI react to overdue work.
+            bail!("{} todo(s) overdue", overdue.len());
+        }
+        "tags" => {
+            let config = load_config()?;
+            let parsed = read_todo_file(&config.todo_file)?;
+            let now = Utc::now();
+            let todos = list::sorted_todos(&parsed);
+            let mut summaries = list::tag_summaries(&todos, now);
+            let tag = flag_value(&args, "--tag");
+            if let Some(tag) = tag {
+                let tag = tag.strip_prefix('#').unwrap_or(tag);
+                summaries.retain(|summary| summary.tag.to_lowercase() == tag.to_lowercase());
+                if summaries.is_empty() {
+                    bail!("no todo is tagged #{tag}");
+                }
+            }
+            for summary in &summaries {
+                writeln!(
+                    out,
+                    "#{}: {} open, {} overdue, {} done",
+                    summary.tag, summary.open, summary.overdue, summary.done
+                )?;
+            }
+            if let Some(tag) = tag {
+                for todo in todos
+                    .iter()
+                    .filter(|todo| !todo.done() && todo.has_tag(tag))
+                {
+                    writeln!(out, "  {}", list::human_line(todo, now))?;
+                }
+            }
+        }
+        "get" => {
+            let (Some(prefix), Some(fields)) = (
+                args.get(1).filter(|arg| !arg.starts_with("--")),
+                flag_value(&args, "--field"),
+            ) else {
+                bail!("usage: todo_md get <id-prefix> --field <field>[,<field>...]");
+            };
+            let fields = list::parse_fields(fields)?;
+            let config = load_config()?;
+            let parsed = read_todo_file(&config.todo_file)?;
+            let id = storage::resolve_id_prefix(parsed.todos_by_id.keys().copied(), prefix)?;
+            let todo = &parsed.todos_by_id[&id];
+            let values = fields
+                .iter()
+                .map(|field| field.script_value(todo))
+                .collect::<Vec<_>>();
+            writeln!(out, "{}", values.join("\t"))?;
+        }
+        "add" => {
+            let Some(text) = args
+                .get(1)
+                .filter(|arg| !arg.trim().is_empty() && !arg.starts_with("--"))
+            else {
+                bail!("usage: todo_md add \"<name> [(due: ...)] [(reccurence: ...)]\" [--due <when>] [--section <heading>]");
+            };
+            let config = load_writable_config()?;
+            let parsed = read_todo_file(&config.todo_file)?;
+            let mut todo = edit::todo_from_text(text)?;
+            if let Some(raw) = flag_value(&args, "--due") {
+                todo.set_due_date(Some(edit::parse_date_field("due", raw)?));
+            }
+            let next = match flag_value(&args, "--section") {
+                Some(section) => edit::add_todo_to_section(&parsed.content, &todo, section),
+                None => edit::add_todo(&parsed.content, &todo),
+            };
+            write_todo_file_checked(&config.todo_file, &parsed, &next)?;
+            writeln!(out, "added {}", todo.id())?;
+            writeln!(out, "{}", list::human_line(&todo, Utc::now()))?;
+        }
+        "done" => {
+            let config = load_writable_config()?;
+            let parsed = read_todo_file(&config.todo_file)?;
+            let id = match (flag_value(&args, "--line"), args.get(1)) {
+                (Some(line), _) => {
+                    let position = line
+                        .parse::<usize>()
+                        .ok()
+                        .filter(|position| *position > 0)
+                        .with_context(|| {
+                            format!("--line expects a positive number, got `{line}`")
+                        })?;
+                    let todos = list::visible_todos(
+                        &parsed,
+                        has_flag(&args, "--available"),
+                        list_scope(&args),
+                        Utc::now(),
+                    );
+                    let Some(todo) = todos.get(position - 1) else {
+                        bail!(
+                            "--line {position} is past the end of the list ({} todo(s))",
+                            todos.len()
+                        );
+                    };
+                    todo.id()
+                }
+                (None, Some(prefix)) if !prefix.starts_with("--") => {
+                    storage::resolve_id_prefix(parsed.todos_by_id.keys().copied(), prefix)?
+                }
+                _ => bail!("usage: todo_md done <id-prefix> | done --line <n>"),
+            };
+            let occurrences = match (
+                flag_value(&args, "--occurrences"),
+                has_flag(&args, "--ahead"),
+            ) {
+                (Some(count), _) => count
+                    .parse::<u32>()
+                    .ok()
+                    .filter(|count| *count > 0)
+                    .with_context(|| {
+                        format!("--occurrences expects a positive number, got `{count}`")
+                    })?,
+                (None, true) => 2,
+                (None, false) => 1,
+            };
+            if has_flag(&args, "--log") {
+                if occurrences > 1 {
+                    bail!("--log completes one occurrence at a time");
+                }
+                let (next, logged, upcoming) = edit::complete_as_log(&parsed.content, id)?;
+                write_todo_file_checked(&config.todo_file, &parsed, &next)?;
+                writeln!(out, "{}", list::human_line(&logged, Utc::now()))?;
+                if let Some(upcoming) = upcoming {
+                    writeln!(out, "next: {}", list::human_line(&upcoming, Utc::now()))?;
+                }
+                return Ok(());
+            }
+            let (next, todo) = edit::complete_occurrences(&parsed.content, id, occurrences)?;
+            write_todo_file_checked(&config.todo_file, &parsed, &next)?;
+            writeln!(out, "{}", list::human_line(&todo, Utc::now()))?;
+            if occurrences > 1 {
+                writeln!(out, "advanced {occurrences} occurrences")?;
+            }
+        }
+        "reschedule" => {
+            if !has_flag(&args, "--overdue") {
+                bail!("usage: todo_md reschedule --overdue [--to <date|+<n>d>]");
+            }
+            let config = load_writable_config()?;
             let parsed = read_todo_file(&config.todo_file)?;
-            let issues = validate_todo_content(&parsed.content);
-            if issues.is_empty() {
-                println!("todo.md is healthy");
+            let now = Utc::now();
+            let to =
+                edit::parse_reschedule_target(flag_value(&args, "--to").unwrap_or("today"), now)?;
+            let (next, moved) = edit::reschedule_overdue(&parsed.content, to, now)?;
+            if !moved.is_empty() {
+                write_todo_file_checked(&config.todo_file, &parsed, &next)?;
+            }
+            for todo in &moved {
+                writeln!(out, "{}", list::human_line(todo, now))?;
+            }
+            let recurring = moved
+                .iter()
+                .filter(|todo| todo.recurence().is_some())
+                .count();
+            writeln!(
+                out,
+                "rescheduled {} overdue todo(s), {recurring} recurring",
+                moved.len()
+            )?;
+        }
+        "rm" => {
+            let config = load_writable_config()?;
+            let parsed = read_todo_file(&config.todo_file)?;
+            let Some(prefix) = args.get(1).filter(|arg| !arg.starts_with("--")) else {
+                bail!("usage: todo_md rm <id-prefix> [--purge]");
+            };
+            let id = storage::resolve_id_prefix(parsed.todos_by_id.keys().copied(), prefix)?;
+            if has_flag(&args, "--purge") {
+                let (next, todo) = edit::remove_todo(&parsed.content, id)?;
+                write_todo_file_checked(&config.todo_file, &parsed, &next)?;
+                writeln!(out, "purged {}", todo.name())?;
             } else {
-                println!("found {} issue(s):", issues.len());
-                for issue in issues {
-                    println!("- {issue}");
+                let trash = read_trash(&config)?;
+                let (next, next_trash, todo) =
+                    trash::trash_todo(&parsed.content, &trash, id, Utc::now())?;
+                storage::move_then_rewrite(
+                    &config.trash_file,
+                    &next_trash,
+                    &config.todo_file,
+                    &parsed,
+                    &next,
+                )?;
+                writeln!(out, "moved {} to trash", todo.name())?;
+            }
+        }
+        "archive" => {
+            let config = load_writable_config()?;
+            let parsed = read_todo_file(&config.todo_file)?;
+            let archive = if config.archive_file.exists() {
+                read_todo_file(&config.archive_file)?.content
+            } else {
+                String::new()
+            };
+            let (next, next_archive, archived) =
+                archive::archive_done(&parsed.content, &archive, Utc::now());
+            if archived.is_empty() {
+                writeln!(out, "no completed todos to archive")?;
+                return Ok(());
+            }
+            storage::move_then_rewrite(
+                &config.archive_file,
+                &next_archive,
+                &config.todo_file,
+                &parsed,
+                &next,
+            )?;
+            writeln!(
+                out,
+                "archived {} completed todo(s) to {}",
+                archived.len(),
+                config.archive_file.display()
+            )?;
+        }
+        "dedupe" => {
+            let merge = has_flag(&args, "--merge");
+            let config = if merge {
+                load_writable_config()?
+            } else {
+                load_config()?
+            };
+            let parsed = read_todo_file(&config.todo_file)?;
+            let clusters = dedupe::duplicate_clusters(&parsed.content, has_flag(&args, "--due"));
+            if clusters.is_empty() {
+                writeln!(out, "no duplicate todos")?;
+                return Ok(());
+            }
+            for cluster in &clusters {
+                let survivor = cluster.survivor();
+                writeln!(
+                    out,
+                    "{} ({} copies): keep {}",
+                    survivor.name(),
+                    cluster.todos.len(),
+                    survivor.id()
+                )?;
+                for extra in cluster.extras() {
+                    writeln!(
+                        out,
+                        "  {} {}",
+                        if merge { "removed" } else { "duplicate" },
+                        extra.id()
+                    )?;
+                }
+            }
+            if merge {
+                let (next, removed) = dedupe::merge_duplicates(&parsed.content, &clusters)?;
+                let trash = trash::append_to_trash(&read_trash(&config)?, &removed, Utc::now());
+                storage::move_then_rewrite(
+                    &config.trash_file,
+                    &trash,
+                    &config.todo_file,
+                    &parsed,
+                    &next,
+                )?;
+                writeln!(out, "moved {} duplicate(s) to trash", removed.len())?;
+            } else {
+                writeln!(
+                    out,
+                    "run again with --merge to keep the first of each group"
+                )?;
+            }
+        }
+        "trash" => {
+            let config = load_config()?;
+            let trash = read_trash(&config)?;
+            match flag_value(&args, "--restore") {
+                Some(prefix) => {
+                    storage::check_writable(&config.config_dir, &config.todo_file)?;
+                    let parsed = read_todo_file(&config.todo_file)?;
+                    let (next, next_trash, todo) =
+                        trash::restore_todo(&parsed.content, &trash, prefix)?;
+                    write_todo_file_checked(&config.todo_file, &parsed, &next)?;
+                    write_todo_file_atomic(&config.trash_file, &next_trash)?;
+                    writeln!(out, "restored {}", todo.name())?;
+                }
+                None => {
+                    for entry in trash::trash_entries(&trash) {
+                        writeln!(
+                            out,
+                            "{}  {}  deleted {}",
+                            entry.todo.id(),
+                            entry.todo.name(),
+                            entry.deleted_at.format("%Y-%m-%d %H:%M")
+                        )?;
+                    }
+                }
+            }
+        }
+        "batch" => {
+            let config = load_writable_config()?;
+            let parsed = read_todo_file(&config.todo_file)?;
+            let input =
+                std::io::read_to_string(std::io::stdin()).context("failed to read stdin")?;
+            let outcome = batch::run_batch(&parsed.content, &input);
+            for result in &outcome.results {
+                writeln!(out, "{result}")?;
+            }
+            match outcome.content {
+                Some(content) => {
+                    if !outcome.trashed.is_empty() {
+                        let trash = read_trash(&config)?;
+                        let next = trash::append_to_trash(&trash, &outcome.trashed, Utc::now());
+                        storage::move_then_rewrite(
+                            &config.trash_file,
+                            &next,
+                            &config.todo_file,
+                            &parsed,
+                            &content,
+                        )?;
+                    } else if content != parsed.content {
+                        write_todo_file_checked(&config.todo_file, &parsed, &content)?;
+                    }
+                }
+                None => bail!("batch failed; todo.md was not changed"),
+            }
+        }
+        "doctor" => {
+            let config = load_config()?;
+            let mut parsed = read_todo_file(&config.todo_file)?;
+            if has_flag(&args, "--fix") {
+                storage::check_writable(&config.config_dir, &config.todo_file)?;
+                let (repaired, lines) = storage::repair_id_segments(&parsed.content);
+                let (repaired, reassigned) = storage::reassign_duplicate_ids(&repaired);
+                if !lines.is_empty() || !reassigned.is_empty() {
+                    write_todo_file_checked(&config.todo_file, &parsed, &repaired)?;
+                    for line in &lines {
+                        writeln!(out, "repaired id segment on line {line}")?;
+                    }
+                    for fix in &reassigned {
+                        writeln!(
+                            out,
+                            "reassigned duplicate id {} on line {} to {}",
+                            fix.old, fix.line, fix.new
+                        )?;
+                    }
+                    parsed = read_todo_file(&config.todo_file)?;
                 }
-                bail!("todo.md has validation issues");
             }
+            let findings = doctor::diagnose(&parsed.content, &config.doctor_options());
+            writeln!(out, "{}", doctor::parse_coverage(&parsed.content))?;
+            if findings.is_empty() {
+                writeln!(out, "todo.md is healthy")?;
+            } else {
+                writeln!(out, "found {} issue(s):", findings.len())?;
+                for finding in &findings {
+                    writeln!(out, "- {finding}")?;
+                }
+                if doctor::has_errors(&findings) {
+                    bail!("todo.md has validation issues");
+                }
+            }
+        }
+        "parse" => {
+            let Some(line) = args.get(1) else {
+                bail!("usage: todo_md parse '<todo line>'");
+            };
+            write!(out, "{}", doctor::explain_line(line)?)?;
+        }
+        "notify" => {
+            let config = load_config()?;
+            let parsed = read_todo_file(&config.todo_file)?;
+            let state_path = notify::state_path(&config.config_dir, config.profile.as_deref());
+            let mut state = notify::read_state(&state_path)?;
+            let now = Utc::now();
+            let pending = notify::pending_notifications(&parsed, &state, now);
+            let dry_run = has_flag(&args, "--dry-run");
+            for todo in &pending {
+                let (summary, body) = notify::notification_text(todo, now);
+                if dry_run {
+                    writeln!(out, "would notify: {summary} | {body}")?;
+                } else if has_flag(&args, "--print") {
+                    writeln!(out, "{summary}\t{body}")?;
+                } else {
+                    notify::send_desktop(&summary, &body)?;
+                }
+            }
+            if dry_run {
+                if pending.is_empty() {
+                    writeln!(out, "nothing to notify")?;
+                }
+            } else {
+                notify::record_sent(&mut state, &parsed, &pending, now);
+                notify::write_state(&state_path, &state)?;
+            }
+        }
+        "explain" => {
+            let Some(prefix) = args.get(1) else {
+                bail!("usage: todo_md explain <id-prefix>");
+            };
+            let config = load_config()?;
+            let parsed = read_todo_file(&config.todo_file)?;
+            let id = storage::resolve_id_prefix(parsed.todos_by_id.keys().copied(), prefix)?;
+            let todo = &parsed.todos_by_id[&id];
+            writeln!(out, "{}", todo.name())?;
+            write!(out, "{}", doctor::explain_completion(todo, Utc::now()))?;
         }
         "fmt" => {
-            let config = AppConfig::load()?;
+            let config = load_writable_config()?;
             let parsed = read_todo_file(&config.todo_file)?;
-            let (formatted, issues) = format_todo_content(&parsed.content);
+            let (mut formatted, issues) = format_todo_content(&parsed.content);
+            match (has_flag(&args, "--sort"), has_flag(&args, "--sort-by-due")) {
+                (true, true) => bail!("--sort and --sort-by-due cannot be combined"),
+                (true, false) => {
+                    formatted = storage::sort_sections(&formatted, storage::SectionSort::DoneLast)
+                }
+                (false, true) => {
+                    formatted = storage::sort_sections(&formatted, storage::SectionSort::Due)
+                }
+                (false, false) => {}
+            }
             if !issues.is_empty() {
-                println!(
+                writeln!(
+                    out,
                     "formatting skipped some lines due to {} issue(s):",
                     issues.len()
-                );
+                )?;
                 for issue in issues {
-                    println!("- {issue}");
+                    writeln!(out, "- {issue}")?;
                 }
             }
 
             if formatted == parsed.content {
-                println!("todo.md already formatted");
+                writeln!(out, "todo.md already formatted")?;
+            } else {
+                write_todo_file_checked(&config.todo_file, &parsed, &formatted)?;
+                writeln!(out, "formatted {}", config.todo_file.display())?;
+            }
+        }
+        "upgrade" => {
+            let config = load_writable_config()?;
+            let parsed = read_todo_file(&config.todo_file)?;
+            let upgrade = storage::upgrade_content(&parsed.content)?;
+            for line in &upgrade.changed_lines {
+                writeln!(out, "line {line}: rewritten in the current format")?;
+            }
+            if upgrade.content == parsed.content {
+                writeln!(
+                    out,
+                    "todo.md is already at line format {}",
+                    frontmatter::LINE_FORMAT_VERSION
+                )?;
+            } else {
+                write_todo_file_checked(&config.todo_file, &parsed, &upgrade.content)?;
+                writeln!(
+                    out,
+                    "upgraded todo.md from line format {} to {} | {} line(s) rewritten",
+                    upgrade.from_version,
+                    frontmatter::LINE_FORMAT_VERSION,
+                    upgrade.changed_lines.len()
+                )?;
+            }
+        }
+        "export" => {
+            let config = load_config()?;
+            let parsed = read_todo_file(&config.todo_file)?;
+            let todos = list::sorted_todos(&parsed);
+            if let Some(command) = flag_value(&args, "--via") {
+                let output = export::export_via(command, &json::export_lines(&todos)?)?;
+                out.write_all(&output)?;
+                return Ok(());
+            }
+            let format = match (
+                flag_value(&args, "--format"),
+                has_flag(&args, "--json"),
+                has_flag(&args, "--ics"),
+            ) {
+                (Some(format), _, _) => format,
+                (None, true, false) => "json",
+                (None, false, true) => "ics",
+                _ => bail!(
+                    "usage: todo_md export --json [--pretty] | --ics | --format <json|org|ics> | --via <command>"
+                ),
+            };
+            match format {
+                "json" => writeln!(
+                    out,
+                    "{}",
+                    json::export_document(&todos, has_flag(&args, "--pretty"))?
+                )?,
+                "org" => write!(out, "{}", export::org_document(&todos))?,
+                "ics" => write!(out, "{}", export::ics_document(&todos, Utc::now()))?,
+                other => bail!("unknown export format `{other}`; expected json, org or ics"),
+            }
+        }
+        "import" => {
+            let Some(path) = args.get(1) else {
+                bail!("usage: todo_md import <file.json>");
+            };
+            let config = load_writable_config()?;
+            let input = std::fs::read_to_string(path)
+                .with_context(|| format!("failed to read import file {path}"))?;
+            let imported = json::import_document(&input)?;
+            let parsed = read_todo_file(&config.todo_file)?;
+            let (merged, changes) = json::merge_into_content(&parsed.content, &imported);
+            let issues = validate_todo_content(&merged);
+            if !issues.is_empty() {
+                bail!(
+                    "import would leave todo.md invalid; nothing was written\n{}",
+                    issues.join("\n")
+                );
+            }
+            if merged != parsed.content {
+                write_todo_file_checked(&config.todo_file, &parsed, &merged)?;
+            }
+            writeln!(
+                out,
+                "imported {} added, {} updated, {} completed",
+                changes.added, changes.updated, changes.completed
+            )?;
+        }
+        "changelog" => {
+            let config = load_config()?;
+            let Some(from) = flag_value(&args, "--from") else {
+                bail!("usage: todo_md changelog --from <ref> [--to <ref>]");
+            };
+            let to = flag_value(&args, "--to").unwrap_or("HEAD");
+            write!(out, "{}", history::changelog(&config, from, to)?)?;
+        }
+        "regenerate-ids" => {
+            if !has_flag(&args, "--yes") {
+                bail!(
+                    "regenerate-ids gives every todo a new id, so sync will see all of them as deleted and re-added; re-run with --yes to confirm"
+                );
+            }
+
+            let config = load_writable_config()?;
+            let parsed = read_todo_file(&config.todo_file)?;
+            let (regenerated, mapping) = regenerate_todo_ids(&parsed.content);
+            if mapping.is_empty() {
+                writeln!(out, "no todo ids to regenerate")?;
             } else {
-                write_todo_file_atomic(&config.todo_file, &formatted)?;
-                println!("formatted {}", config.todo_file.display());
+                write_todo_file_checked(&config.todo_file, &parsed, &regenerated)?;
+                for (old_id, new_id) in &mapping {
+                    writeln!(out, "{old_id} -> {new_id}")?;
+                }
+                writeln!(out, "regenerated {} id(s)", mapping.len())?;
             }
         }
+        "version" | "-V" | "--version" => {
+            write_version(&mut out)?;
+        }
         "help" | "-h" | "--help" => {
-            print_help();
+            print_help(&mut out)?;
         }
         _ => bail!("unknown command `{command}`; run `todo_md help`"),
     }
@@ -108,11 +1148,405 @@ fn run() -> Result<()> {
     Ok(())
 }
 
-fn print_help() {
-    println!("todo_md commands:");
-    println!("  setup [remote-url]  Initialize ~/.config/todos and git repo");
-    println!("  sync                Pull/rebase, diff todo.md, commit, and push");
-    println!("  where               Show resolved config and todo paths");
-    println!("  doctor              Validate todo.md for sync-safe issues");
-    println!("  fmt                 Canonicalize todo line formatting");
+fn load_config() -> Result<AppConfig> {
+    let config = AppConfig::load()?;
+    date_parser::configure(config.date_options());
+    storage::configure_strict(config.strict);
+    Ok(config)
+}
+
+/// [`load_config`] for commands that write todo.md or run git.
+fn load_writable_config() -> Result<AppConfig> {
+    let config = load_config()?;
+    storage::check_writable(&config.config_dir, &config.todo_file)?;
+    Ok(config)
+}
+
+fn list_scope(args: &[String]) -> list::ListScope {
+    if has_flag(args, "--all") {
+        list::ListScope::All
+    } else if has_flag(args, "--someday") {
+        list::ListScope::Someday
+    } else {
+        list::ListScope::Active
+    }
+}
+
+fn has_flag(args: &[String], flag: &str) -> bool {
+    args.iter().skip(1).any(|arg| arg == flag)
+}
+
+fn flag_value<'a>(args: &'a [String], flag: &str) -> Option<&'a str> {
+    args.iter()
+        .skip(1)
+        .position(|arg| arg == flag)
+        .and_then(|idx| args.get(idx + 2))
+        .map(String::as_str)
+}
+
+fn prompt_conflict(out: &mut impl Write, conflict: &merge::Conflict) -> Result<merge::Resolution> {
+    let show = |line: &Option<String>| line.clone().unwrap_or_else(|| "(deleted)".to_string());
+    writeln!(out, "conflict for {}:", conflict.id)?;
+    writeln!(out, "  base:   {}", show(&conflict.base))?;
+    writeln!(out, "  local:  {}", show(&conflict.local))?;
+    writeln!(out, "  remote: {}", show(&conflict.remote))?;
+
+    let stdin = std::io::stdin();
+    loop {
+        write!(out, "keep [l]ocal, [r]emote, or [m]erge by typing a line? ")?;
+        out.flush().context("failed to flush stdout")?;
+        let mut answer = String::new();
+        if stdin
+            .read_line(&mut answer)
+            .context("failed to read answer")?
+            == 0
+        {
+            bail!(
+                "input closed before conflict for {} was resolved",
+                conflict.id
+            );
+        }
+        match answer.trim() {
+            "l" | "local" => return Ok(merge::Resolution::Local),
+            "r" | "remote" => return Ok(merge::Resolution::Remote),
+            "m" | "merge" => {
+                write!(out, "merged line: ")?;
+                out.flush().context("failed to flush stdout")?;
+                let mut line = String::new();
+                stdin
+                    .read_line(&mut line)
+                    .context("failed to read merged line")?;
+                return Ok(merge::Resolution::Line(line));
+            }
+            _ => writeln!(out, "please answer l, r, or m")?,
+        }
+    }
+}
+
+enum ChangeOutput {
+    Human,
+    Porcelain,
+    Json,
+}
+
+fn output_mode(args: &[String]) -> Result<ChangeOutput> {
+    match (has_flag(args, "--porcelain"), has_flag(args, "--json")) {
+        (true, true) => bail!("--porcelain and --json cannot be combined"),
+        (true, false) => Ok(ChangeOutput::Porcelain),
+        (false, true) => Ok(ChangeOutput::Json),
+        (false, false) => Ok(ChangeOutput::Human),
+    }
+}
+
+/// One-line outcome of a sync, shared by `sync` and `watch`.
+fn sync_summary(result: &sync::SyncResult) -> String {
+    format!(
+        "sync {} | added {} updated {} deleted {} completed {} | {}",
+        if result.amended {
+            "amended the unpushed sync commit"
+        } else if result.committed {
+            "committed"
+        } else {
+            "no local todo changes"
+        },
+        result.change_set.added,
+        result.change_set.updated,
+        result.change_set.deleted,
+        result.change_set.completed,
+        result.line_summary
+    )
+}
+
+fn read_trash(config: &AppConfig) -> Result<String> {
+    if !config.trash_file.exists() {
+        return Ok(String::new());
+    }
+    Ok(read_todo_file(&config.trash_file)?.content)
+}
+
+/// Version, embedded git commit and the config dir being used. The config
+/// dir is resolved without loading `.env`, so this works on a broken setup.
+fn write_version(out: &mut impl Write) -> Result<()> {
+    let version = env!("CARGO_PKG_VERSION");
+    match option_env!("TODO_MD_GIT_COMMIT") {
+        Some(commit) => writeln!(out, "todo_md {version} ({commit})")?,
+        None => writeln!(out, "todo_md {version}")?,
+    }
+    match config::resolve_config_dir() {
+        Ok(dir) => writeln!(out, "config: {}", dir.display())?,
+        Err(error) => writeln!(out, "config: unavailable ({error})")?,
+    }
+    Ok(())
+}
+
+fn print_help(out: &mut impl Write) -> Result<()> {
+    writeln!(out, "todo_md commands:")?;
+    writeln!(out, "  setup [remote-url | --local]")?;
+    writeln!(
+        out,
+        "                      Initialize ~/.config/todos and git repo; --local skips the remote"
+    )?;
+    writeln!(
+        out,
+        "  sync [--skip-invalid] [--only <id>] [--exclude-done] [--date <when>] [--remote-only-changes] [--aggressive] [--format json]"
+    )?;
+    writeln!(
+        out,
+        "                      Pull/rebase, diff todo.md, commit, and push"
+    )?;
+    writeln!(
+        out,
+        "                      --date backdates the one commit this sync creates"
+    )?;
+    writeln!(
+        out,
+        "                      --remote-only-changes first lists what origin changed"
+    )?;
+    writeln!(
+        out,
+        "                      --aggressive amends the last sync commit if it was never pushed"
+    )?;
+    writeln!(
+        out,
+        "                      --format json prints the outcome as one JSON object"
+    )?;
+    writeln!(
+        out,
+        "  undo                Revert the last commit if sync made it and todo.md has no edits"
+    )?;
+    writeln!(out, "  watch [--skip-invalid] [--aggressive]")?;
+    writeln!(
+        out,
+        "                      Sync 2s after each save to todo.md until Ctrl-C; failed syncs retry on the next save"
+    )?;
+    writeln!(
+        out,
+        "  diff <before> <after> [--line-summary] [--porcelain|--json]"
+    )?;
+    writeln!(
+        out,
+        "                      Classify todo changes between two files, without git"
+    )?;
+    writeln!(out, "  save [--skip-invalid]")?;
+    writeln!(
+        out,
+        "                      Assign ids, normalize and validate todo.md without git"
+    )?;
+    writeln!(out, "  status [--porcelain|--json]")?;
+    writeln!(
+        out,
+        "                      Show uncommitted todo changes since the last sync"
+    )?;
+    writeln!(
+        out,
+        "  verify              Exit non-zero if todo.md has uncommitted todo changes"
+    )?;
+    writeln!(out, "  log [-n <count>] [--porcelain|--json]")?;
+    writeln!(
+        out,
+        "                      Show recent syncs and the todos each one changed"
+    )?;
+    writeln!(
+        out,
+        "                      (porcelain: <kind>\\t<id>\\t<name>, log prefixes the commit)"
+    )?;
+    writeln!(out, "  prune-remote-branch [--yes]")?;
+    writeln!(
+        out,
+        "                      List (or with --yes delete) old todo sync branches"
+    )?;
+    writeln!(out, "  stats [--heatmap [--since <date>]]")?;
+    writeln!(
+        out,
+        "                      Counts of open, done, overdue and recurring todos and file issues"
+    )?;
+    writeln!(
+        out,
+        "                      --heatmap shows a grid of completions per day from sync history"
+    )?;
+    writeln!(out, "  completed --since <date>")?;
+    writeln!(
+        out,
+        "                      Todos completed since a date, from history and unsynced edits"
+    )?;
+    writeln!(out, "  resolve --interactive")?;
+    writeln!(
+        out,
+        "                      Merge a conflicted todo.md by id, prompting for clashes"
+    )?;
+    writeln!(
+        out,
+        "  where               Show resolved config and todo paths"
+    )?;
+    writeln!(
+        out,
+        "  list [--porcelain|--emoji|--format table [--columns <list>]] [--available] [--someday|--all]"
+    )?;
+    writeln!(
+        out,
+        "                      Print numbered todos ordered by due date"
+    )?;
+    writeln!(
+        out,
+        "                      #someday todos are hidden unless --someday or --all"
+    )?;
+    writeln!(
+        out,
+        "                      #pinned todos come first regardless of due date"
+    )?;
+    writeln!(
+        out,
+        "                      columns: id,status,due,scheduled,recurrence,ext,tags,name"
+    )?;
+    writeln!(
+        out,
+        "                      --open/--done filter by state; --due-before <when> keeps undated todos last"
+    )?;
+    writeln!(
+        out,
+        "                      --tag <name> keeps todos carrying #name anywhere in their name"
+    )?;
+    writeln!(
+        out,
+        "  overdue             Overdue todos, latest first; exits nonzero if there are any"
+    )?;
+    writeln!(out, "  list --overdue [--group-by age]")?;
+    writeln!(
+        out,
+        "                      Only overdue todos; by age buckets from 1 day to over a month"
+    )?;
+    writeln!(out, "  tags [--tag <name>]")?;
+    writeln!(
+        out,
+        "                      Count open, overdue and done todos per #tag; --tag also lists its open todos"
+    )?;
+    writeln!(out, "  get <id-prefix> --field <list>")?;
+    writeln!(
+        out,
+        "                      Print fields of one todo, tab-separated, for scripts"
+    )?;
+    writeln!(
+        out,
+        "  add \"<name> [(due: ...)] [(reccurence: ...)]\" [--due <when>] [--section <heading>]"
+    )?;
+    writeln!(
+        out,
+        "                      Append a todo with a new id, or add it under a heading (created if missing)"
+    )?;
+    writeln!(
+        out,
+        "  done <id-prefix> | done --line <n> [--available] [--someday|--all] [--ahead|--occurrences <n>|--log]"
+    )?;
+    writeln!(
+        out,
+        "                      Complete a todo by id prefix or by its number in `list`"
+    )?;
+    writeln!(
+        out,
+        "                      (numbers follow the current list order; they are not ids)"
+    )?;
+    writeln!(
+        out,
+        "                      --ahead/--occurrences complete upcoming recurrences too"
+    )?;
+    writeln!(
+        out,
+        "                      --log keeps a done line and adds the next occurrence as a new todo"
+    )?;
+    writeln!(
+        out,
+        "  doctor [--fix]      Validate todo.md for sync-safe issues"
+    )?;
+    writeln!(
+        out,
+        "                      --fix repairs id spacing and gives copied ids a fresh one"
+    )?;
+    writeln!(
+        out,
+        "  parse '<line>'      Show the fields the parser reads from one todo line"
+    )?;
+    writeln!(
+        out,
+        "  explain <id-prefix> Describe what marking a todo done would do"
+    )?;
+    writeln!(out, "  notify [--dry-run] [--print]")?;
+    writeln!(
+        out,
+        "                      Remind once about overdue and due-soon todos via notify-send, for cron"
+    )?;
+    writeln!(
+        out,
+        "                      --print writes `summary<TAB>body` lines instead"
+    )?;
+    writeln!(out, "  fmt [--sort|--sort-by-due]")?;
+    writeln!(
+        out,
+        "                      Canonicalize todo lines; --sort orders each section open-first by due date and name"
+    )?;
+    writeln!(
+        out,
+        "  upgrade             Rewrite todo.md in the current line format and record its version"
+    )?;
+    writeln!(
+        out,
+        "  export --json [--pretty] | --ics | --format <json|org|ics> | --via <command>"
+    )?;
+    writeln!(
+        out,
+        "                      Print todos as versioned JSON, iCalendar VTODOs or Org-mode headings"
+    )?;
+    writeln!(
+        out,
+        "                      --via pipes one JSON todo per line to `sh -c <command>`"
+    )?;
+    writeln!(
+        out,
+        "  import <file.json>  Merge todos from an exported JSON document"
+    )?;
+    writeln!(out, "  reschedule --overdue [--to <date|+<n>d>]")?;
+    writeln!(
+        out,
+        "                      Move every open overdue todo to one date (default today)"
+    )?;
+    writeln!(out, "  rm <id-prefix> [--purge]")?;
+    writeln!(
+        out,
+        "                      Move a todo to trash.md; --purge deletes it outright"
+    )?;
+    writeln!(
+        out,
+        "  archive             Move completed todos to archive.md"
+    )?;
+    writeln!(out, "  dedupe [--due] [--merge]")?;
+    writeln!(
+        out,
+        "                      List open todos with the same name; --merge trashes all but the first"
+    )?;
+    writeln!(out, "  trash [--restore <id-prefix>]")?;
+    writeln!(
+        out,
+        "                      List trashed todos or move one back to todo.md"
+    )?;
+    writeln!(out, "  changelog --from <ref> [--to <ref>]")?;
+    writeln!(
+        out,
+        "                      Markdown changelog of todo changes between revisions"
+    )?;
+    writeln!(
+        out,
+        "  version             Print the version, build commit and config dir"
+    )?;
+    writeln!(out, "  regenerate-ids --yes")?;
+    writeln!(out, "                      Assign fresh ids to every todo")?;
+    writeln!(out, "any command accepts --strict:")?;
+    writeln!(
+        out,
+        "                      Fail if todo.md has lines a rewrite would change or lose (TODOS_STRICT)"
+    )?;
+    writeln!(out, "and --profile <name>:")?;
+    writeln!(
+        out,
+        "                      Use todo-<name>.md and .env.<name> overrides instead of todo.md (TODOS_PROFILE)"
+    )?;
+    Ok(())
 }
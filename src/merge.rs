@@ -0,0 +1,826 @@
+use std::collections::{HashMap, HashSet};
+use std::process::Command;
+
+use anyhow::{anyhow, bail, Context, Result};
+use uuid::Uuid;
+
+use crate::config::AppConfig;
+use crate::edit::line_todo_id;
+use crate::storage::{
+    continuation_blocks, read_todo_file, validate_todo_content, write_todo_file_atomic,
+};
+use crate::sync::{
+    apply_git_identity, git_show_or_empty, run_git, run_git_checked, todo_path_relative_to_repo,
+};
+use crate::types::Todo;
+
+/// A todo changed differently on both sides of a merge. A side is `None` when
+/// it deleted the todo.
+#[derive(Debug, Clone)]
+pub struct Conflict {
+    pub id: Uuid,
+    pub base: Option<String>,
+    pub local: Option<String>,
+    pub remote: Option<String>,
+}
+
+/// The side of a merge whose commit is newer. It stands in for `updated_at`
+/// when both sides changed the same field of a todo and either line predates
+/// `(updated: ...)`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Side {
+    Local,
+    Remote,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Resolution {
+    Local,
+    Remote,
+    /// A hand-merged todo line replacing both sides.
+    Line(String),
+}
+
+/// What `resolve` did to the conflicted todo file.
+#[derive(Debug, Clone)]
+pub struct ResolveResult {
+    pub auto_merged: usize,
+    pub prompted: usize,
+    /// A rebase was continued (rather than an autostash conflict resolved).
+    pub continued_rebase: bool,
+    /// The rebase stopped again on a later commit and needs another `resolve`.
+    pub still_rebasing: bool,
+}
+
+/// Resolves a rebase stopped on a conflicted todo file: todos changed on only
+/// one side, or in different fields on each side, are merged automatically
+/// and `choose` decides the rest. With `newer`, a field changed on both sides
+/// takes that side's value instead of asking (see [`newer_side`]). The merged
+/// file is written atomically and, when a rebase stopped, staged and the
+/// rebase continued.
+pub fn resolve_conflicts(
+    config: &AppConfig,
+    newer: Option<Side>,
+    choose: impl FnMut(&Conflict) -> Result<Resolution>,
+) -> Result<ResolveResult> {
+    let todo_rel = todo_path_relative_to_repo(config)?;
+    let unmerged = run_git_checked(
+        &config.config_dir,
+        ["ls-files", "--unmerged", "--", todo_rel.as_str()],
+    )?;
+    if unmerged.trim().is_empty() {
+        return resolve_marker_conflicts(config, &todo_rel, newer, choose);
+    }
+
+    // While rebasing, stage 2 is the upstream branch being rebased onto and
+    // stage 3 is the local commit being replayed.
+    let repo = &config.config_dir;
+    let base = git_show_or_empty(repo, &format!(":1:{todo_rel}"))?;
+    let remote = git_show_or_empty(repo, &format!(":2:{todo_rel}"))?;
+    let local = git_show_or_empty(repo, &format!(":3:{todo_rel}"))?;
+
+    let mut prompted = 0;
+    let mut choose = choose;
+    let (merged, auto_merged) = merge_todo_contents(&base, &local, &remote, newer, |conflict| {
+        prompted += 1;
+        choose(conflict)
+    })?;
+
+    let issues = validate_todo_content(&merged);
+    if !issues.is_empty() {
+        bail!(
+            "merged todo.md is invalid; nothing was written\n{}",
+            issues.join("\n")
+        );
+    }
+
+    write_todo_file_atomic(&config.todo_file, &merged)?;
+    run_git_checked(repo, ["add", "--", todo_rel.as_str()])?;
+
+    // A conflict from `pull --autostash` re-applying uncommitted edits leaves no
+    // rebase behind; the merged file simply stays as a local change.
+    let mut still_rebasing = false;
+    let continued_rebase = rebase_in_progress(config)?;
+    if continued_rebase {
+        let mut command = Command::new("git");
+        command
+            .args(["rebase", "--continue"])
+            .current_dir(repo)
+            .env("GIT_EDITOR", "true");
+        apply_git_identity(&mut command, config);
+        let output = command
+            .output()
+            .context("failed to execute git rebase --continue")?;
+        still_rebasing = !output.status.success();
+        if still_rebasing {
+            let unmerged = run_git(repo, ["ls-files", "--unmerged"])?;
+            if unmerged.stdout.is_empty() {
+                bail!(
+                    "git rebase --continue failed\nstderr:\n{}",
+                    String::from_utf8_lossy(&output.stderr).trim()
+                );
+            }
+        }
+    } else {
+        run_git_checked(repo, ["reset", "--quiet", "--", todo_rel.as_str()])?;
+    }
+
+    Ok(ResolveResult {
+        auto_merged,
+        prompted,
+        continued_rebase,
+        still_rebasing,
+    })
+}
+
+/// Fallback for a todo file that holds conflict markers git no longer tracks
+/// as unmerged (e.g. committed by mistake). The first side of each block is
+/// treated as local and the second as remote; lines outside blocks are the
+/// base. Nothing is staged.
+fn resolve_marker_conflicts(
+    config: &AppConfig,
+    todo_rel: &str,
+    newer: Option<Side>,
+    choose: impl FnMut(&Conflict) -> Result<Resolution>,
+) -> Result<ResolveResult> {
+    let current = read_todo_file(&config.todo_file)?;
+    let Some(sides) = split_conflict_markers(&current.content) else {
+        bail!("{todo_rel} has no merge conflict to resolve");
+    };
+
+    let mut prompted = 0;
+    let mut choose = choose;
+    let (merged, auto_merged) = merge_todo_contents(
+        &sides.base,
+        &sides.local,
+        &sides.remote,
+        newer,
+        |conflict| {
+            prompted += 1;
+            choose(conflict)
+        },
+    )?;
+    let issues = validate_todo_content(&merged);
+    if !issues.is_empty() {
+        bail!(
+            "merged todo.md is invalid; nothing was written\n{}",
+            issues.join("\n")
+        );
+    }
+    write_todo_file_atomic(&config.todo_file, &merged)?;
+
+    Ok(ResolveResult {
+        auto_merged,
+        prompted,
+        continued_rebase: false,
+        still_rebasing: false,
+    })
+}
+
+/// Both sides of a file containing conflict blocks, plus the shared lines.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MarkerSides {
+    pub base: String,
+    pub local: String,
+    pub remote: String,
+}
+
+/// Splits `<<<<<<< / ======= / >>>>>>>` blocks into two whole files. A diff3
+/// `|||||||` base section is dropped: its lines belong to neither side, and
+/// leaving it out of the base makes every todo inside a block a prompt rather
+/// than a guess. Returns `None` when there are no complete blocks.
+pub fn split_conflict_markers(content: &str) -> Option<MarkerSides> {
+    #[derive(PartialEq)]
+    enum Section {
+        Shared,
+        Local,
+        Base,
+        Remote,
+    }
+
+    let mut sides = MarkerSides {
+        base: String::new(),
+        local: String::new(),
+        remote: String::new(),
+    };
+    let mut section = Section::Shared;
+    let mut blocks = 0;
+    for line in content.lines() {
+        let trimmed = line.trim_start();
+        let next = match section {
+            Section::Shared if trimmed.starts_with("<<<<<<<") => Some(Section::Local),
+            Section::Local if trimmed.starts_with("|||||||") => Some(Section::Base),
+            Section::Local | Section::Base if trimmed.starts_with("=======") => {
+                Some(Section::Remote)
+            }
+            Section::Remote if trimmed.starts_with(">>>>>>>") => {
+                blocks += 1;
+                Some(Section::Shared)
+            }
+            _ => None,
+        };
+        if let Some(next) = next {
+            section = next;
+            continue;
+        }
+
+        let targets = match section {
+            Section::Shared => vec![&mut sides.base, &mut sides.local, &mut sides.remote],
+            Section::Local => vec![&mut sides.local],
+            Section::Remote => vec![&mut sides.remote],
+            Section::Base => Vec::new(),
+        };
+        for target in targets {
+            target.push_str(line);
+            target.push('\n');
+        }
+    }
+
+    (blocks > 0 && section == Section::Shared).then_some(sides)
+}
+
+/// Which side of a stopped rebase was committed last. The local side is the
+/// commit being replayed (`REBASE_HEAD`); without one, the conflict came from
+/// re-applying uncommitted edits, which are newer than anything pulled.
+pub fn newer_side(config: &AppConfig) -> Result<Side> {
+    let repo = &config.config_dir;
+    let commit_time = |rev: &str| -> Result<Option<i64>> {
+        let output = run_git(repo, ["log", "-1", "--format=%ct", rev, "--"])?;
+        Ok(String::from_utf8_lossy(&output.stdout).trim().parse().ok())
+    };
+    let Some(local) = commit_time("REBASE_HEAD")? else {
+        return Ok(Side::Local);
+    };
+    match commit_time("HEAD")? {
+        Some(remote) if remote > local => Ok(Side::Remote),
+        _ => Ok(Side::Local),
+    }
+}
+
+fn rebase_in_progress(config: &AppConfig) -> Result<bool> {
+    for dir in ["rebase-merge", "rebase-apply"] {
+        let path = run_git_checked(&config.config_dir, ["rev-parse", "--git-path", dir])?;
+        if config.config_dir.join(path.trim()).exists() {
+            return Ok(true);
+        }
+    }
+    Ok(false)
+}
+
+/// Three-way merge of todo files keyed by id. Returns the merged content and
+/// how many todos were merged without asking.
+///
+/// Each todo moves together with its notes. A todo changed on both sides is
+/// merged field by field (see [`merge_fields`]) and its notes line by line
+/// (see [`merge_notes`]); `choose` is asked only when that fails, e.g. when
+/// one side deleted the todo. The remote file supplies the layout. Other
+/// lines are merged by count, so a heading or blank line added on one side
+/// survives and one deleted on one side goes. Anything only present locally
+/// is placed after the local line it followed.
+pub fn merge_todo_contents(
+    base: &str,
+    local: &str,
+    remote: &str,
+    newer: Option<Side>,
+    mut choose: impl FnMut(&Conflict) -> Result<Resolution>,
+) -> Result<(String, usize)> {
+    let (base_entries, base_layout) = split_layout(base);
+    let (local_entries, local_layout) = split_layout(local);
+    let (remote_entries, remote_layout) = split_layout(remote);
+
+    let mut auto_merged = 0;
+    let mut resolved: HashMap<Uuid, Option<Entry>> = HashMap::new();
+    let mut ids = remote_entries
+        .keys()
+        .chain(local_entries.keys())
+        .copied()
+        .collect::<Vec<_>>();
+    ids.sort();
+    ids.dedup();
+
+    for id in ids {
+        let base = base_entries.get(&id).cloned();
+        let local = local_entries.get(&id).cloned();
+        let remote = remote_entries.get(&id).cloned();
+        let base_notes = base.as_ref().map_or(&[][..], |entry| &entry.notes);
+
+        let entry = if local == remote {
+            remote
+        } else if local == base {
+            auto_merged += 1;
+            remote
+        } else if remote == base {
+            auto_merged += 1;
+            local
+        } else if let (Some(local), Some(remote)) = (&local, &remote)
+            && let Some(line) = if local.line == remote.line {
+                Some(local.line.clone())
+            } else {
+                merge_fields(
+                    base.as_ref().map(|entry| entry.line.as_str()),
+                    &local.line,
+                    &remote.line,
+                    newer,
+                )
+            }
+        {
+            auto_merged += 1;
+            Some(Entry {
+                line,
+                notes: merge_notes(base_notes, &local.notes, &remote.notes),
+            })
+        } else {
+            let conflict = Conflict {
+                id,
+                base: base.as_ref().map(|entry| entry.line.clone()),
+                local: local.as_ref().map(|entry| entry.line.clone()),
+                remote: remote.as_ref().map(|entry| entry.line.clone()),
+            };
+            match choose(&conflict)? {
+                Resolution::Local => local,
+                Resolution::Remote => remote,
+                Resolution::Line(line) => Some(Entry {
+                    line: merged_line(&line, id)?,
+                    notes: merge_notes(
+                        base_notes,
+                        local.as_ref().map_or(&[][..], |entry| &entry.notes),
+                        remote.as_ref().map_or(&[][..], |entry| &entry.notes),
+                    ),
+                }),
+            }
+        };
+        resolved.insert(id, entry);
+    }
+
+    let layout = merge_layout(&base_layout, &local_layout, remote_layout);
+    let mut out = Vec::new();
+    for item in layout {
+        match item {
+            Item::Todo(id) => {
+                if let Some(Some(entry)) = resolved.get(&id) {
+                    out.push(entry.line.clone());
+                    out.extend(entry.notes.iter().cloned());
+                }
+            }
+            Item::Text(line) => out.push(line),
+        }
+    }
+
+    let mut merged = out.join("\n");
+    if !merged.is_empty() {
+        merged.push('\n');
+    }
+    Ok((merged, auto_merged))
+}
+
+/// A todo line with the notes indented under it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Entry {
+    line: String,
+    notes: Vec<String>,
+}
+
+/// One line of a file's layout; a todo stands for its whole [`Entry`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Item {
+    Todo(Uuid),
+    Text(String),
+}
+
+/// Splits `content` into its todos by id and the layout around them.
+fn split_layout(content: &str) -> (HashMap<Uuid, Entry>, Vec<Item>) {
+    let lines = content.lines().collect::<Vec<_>>();
+    let notes = continuation_blocks(content)
+        .into_iter()
+        .filter(|(todo_idx, _)| line_todo_id(lines[*todo_idx]).is_some())
+        .collect::<HashMap<_, _>>();
+    let note_lines = notes.values().flatten().copied().collect::<HashSet<_>>();
+
+    let mut entries = HashMap::new();
+    let mut layout = Vec::new();
+    for (idx, line) in lines.iter().enumerate() {
+        if note_lines.contains(&idx) {
+            continue;
+        }
+        match line_todo_id(line) {
+            Some(id) => {
+                let notes = notes.get(&idx).map_or_else(Vec::new, |block| {
+                    block.iter().map(|idx| lines[*idx].to_string()).collect()
+                });
+                entries.insert(
+                    id,
+                    Entry {
+                        line: line.to_string(),
+                        notes,
+                    },
+                );
+                layout.push(Item::Todo(id));
+            }
+            None => layout.push(Item::Text(line.to_string())),
+        }
+    }
+    (entries, layout)
+}
+
+/// Merges one todo's notes changed on both sides: the remote notes, then the
+/// local lines the remote lacks.
+fn merge_notes(base: &[String], local: &[String], remote: &[String]) -> Vec<String> {
+    if local == remote || local == base {
+        return remote.to_vec();
+    }
+    if remote == base {
+        return local.to_vec();
+    }
+    let mut merged = remote.to_vec();
+    merged.extend(local.iter().filter(|line| !remote.contains(line)).cloned());
+    merged
+}
+
+/// Starts from the remote layout and applies the local side's changes to it.
+/// A text line is kept as many times as a three-way merge of its counts says,
+/// dropping surplus copies from the end; todos and text lines only present
+/// locally go after the local item they followed.
+fn merge_layout(base: &[Item], local: &[Item], remote: Vec<Item>) -> Vec<Item> {
+    let counts = |items: &[Item]| {
+        let mut counts: HashMap<String, usize> = HashMap::new();
+        for item in items {
+            if let Item::Text(line) = item {
+                *counts.entry(line.clone()).or_default() += 1;
+            }
+        }
+        counts
+    };
+    let (base_counts, local_counts, remote_counts) = (counts(base), counts(local), counts(&remote));
+    let target = |line: &str| {
+        let count = |counts: &HashMap<String, usize>| counts.get(line).copied().unwrap_or(0);
+        let (base, local, remote) = (
+            count(&base_counts),
+            count(&local_counts),
+            count(&remote_counts),
+        );
+        if local == base {
+            remote
+        } else if remote == base {
+            local
+        } else {
+            local.max(remote)
+        }
+    };
+
+    let mut out = Vec::new();
+    let mut kept: HashMap<String, usize> = HashMap::new();
+    for item in remote.into_iter().rev() {
+        if let Item::Text(line) = &item {
+            let seen = kept.entry(line.clone()).or_default();
+            let surplus = remote_counts[line].saturating_sub(target(line));
+            *seen += 1;
+            if *seen <= surplus {
+                continue;
+            }
+        }
+        out.push(item);
+    }
+    out.reverse();
+
+    // The last copies of a text line the remote is short of are the local
+    // additions; earlier copies match `out` in order, or were deleted there.
+    let mut cursor = 0;
+    let mut seen: HashMap<String, usize> = HashMap::new();
+    for item in local {
+        let (position, added) = match item {
+            Item::Todo(_) => {
+                let position = out.iter().position(|other| other == item);
+                (position, position.is_none())
+            }
+            Item::Text(line) => {
+                let seen = seen.entry(line.clone()).or_default();
+                *seen += 1;
+                let missing =
+                    target(line).saturating_sub(remote_counts.get(line).copied().unwrap_or(0));
+                let position = out
+                    .iter()
+                    .enumerate()
+                    .filter(|(_, other)| *other == item)
+                    .nth(*seen - 1)
+                    .map(|(position, _)| position);
+                (position, *seen > local_counts[line] - missing)
+            }
+        };
+        if added {
+            out.insert(cursor, item.clone());
+            cursor += 1;
+        } else if let Some(position) = position {
+            cursor = position + 1;
+        }
+    }
+    out
+}
+
+/// Merges two edits of one todo field by field against `base` (`None` when
+/// both sides added it). A field changed on one side takes that change; a
+/// field changed differently on both takes the `newer` side's value, or fails
+/// the merge without one. Also `None` when any line does not parse.
+fn merge_fields(
+    base: Option<&str>,
+    local: &str,
+    remote: &str,
+    newer: Option<Side>,
+) -> Option<String> {
+    let local = Todo::try_from_str(local).ok()?;
+    let remote = Todo::try_from_str(remote).ok()?;
+    let base = base.map(Todo::try_from_str).transpose().ok()?;
+    let base = base.as_ref();
+    let newer = match (local.recorded_updated_at(), remote.recorded_updated_at()) {
+        (Some(local_at), Some(remote_at)) if local_at > remote_at => Some(Side::Local),
+        (Some(local_at), Some(remote_at)) if local_at < remote_at => Some(Side::Remote),
+        _ => newer,
+    };
+    let mut merged = Todo::from_parts(
+        local.id(),
+        merge_field(base, &local, &remote, newer, Todo::name)?,
+        merge_field(base, &local, &remote, newer, Todo::done)?,
+        merge_field(base, &local, &remote, newer, Todo::due_date)?,
+        merge_field(base, &local, &remote, newer, Todo::scheduled)?,
+        merge_field(base, &local, &remote, newer, |todo| {
+            todo.recurence().cloned()
+        })?,
+    );
+    merged.set_recurrence_end(merge_field(
+        base,
+        &local,
+        &remote,
+        newer,
+        Todo::recurrence_end,
+    )?);
+    merged.set_priority(merge_field(base, &local, &remote, newer, Todo::priority)?);
+    merged.set_external_id(merge_field(base, &local, &remote, newer, |todo| {
+        todo.external_id().map(str::to_string)
+    })?);
+    merged.set_timestamps(
+        [base, Some(&local), Some(&remote)]
+            .into_iter()
+            .flatten()
+            .filter_map(Todo::recorded_created_at)
+            .min(),
+        local
+            .recorded_updated_at()
+            .max(remote.recorded_updated_at()),
+    );
+    Some(merged.to_line())
+}
+
+/// One field of [`merge_fields`].
+fn merge_field<T: PartialEq>(
+    base: Option<&Todo>,
+    local: &Todo,
+    remote: &Todo,
+    newer: Option<Side>,
+    get: impl Fn(&Todo) -> T,
+) -> Option<T> {
+    let (base, local, remote) = (base.map(&get), get(local), get(remote));
+    if local == remote || base.as_ref() == Some(&remote) {
+        return Some(local);
+    }
+    if base.as_ref() == Some(&local) {
+        return Some(remote);
+    }
+    match newer? {
+        Side::Local => Some(local),
+        Side::Remote => Some(remote),
+    }
+}
+
+/// Validates a hand-merged line, adding the conflicting todo's id if omitted.
+fn merged_line(line: &str, id: Uuid) -> Result<String> {
+    let line = line.trim();
+    let line = if line.contains("(id:") {
+        line.to_string()
+    } else {
+        format!("{line} (id: {id})")
+    };
+    let todo = Todo::try_from_str(&line).map_err(|_| anyhow!("`{line}` is not a todo line"))?;
+    if todo.id() != id {
+        bail!("merged line must keep id {id}");
+    }
+    Ok(line)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::date_parser::pin_home_zone;
+
+    const A: &str = "123e4567-e89b-12d3-a456-426614174001";
+    const B: &str = "123e4567-e89b-12d3-a456-426614174002";
+    const C: &str = "123e4567-e89b-12d3-a456-426614174003";
+
+    #[test]
+    fn splits_diff3_conflict_blocks_and_drops_the_base() {
+        let content = format!(
+            "# Todos\n\
+             <<<<<<< HEAD\n\
+             - [x] Pay rent (id: {A})\n\
+             ||||||| merged common ancestors\n\
+             - [_] Pay rent (id: {A})\n\
+             =======\n\
+             - [_] Pay rent today (id: {A})\n\
+             >>>>>>> laptop\n\
+             - [_] Call mom (id: {B})\n"
+        );
+
+        let sides = split_conflict_markers(&content).expect("has a block");
+        assert_eq!(sides.base, format!("# Todos\n- [_] Call mom (id: {B})\n"));
+        assert_eq!(
+            sides.local,
+            format!("# Todos\n- [x] Pay rent (id: {A})\n- [_] Call mom (id: {B})\n")
+        );
+        assert_eq!(
+            sides.remote,
+            format!("# Todos\n- [_] Pay rent today (id: {A})\n- [_] Call mom (id: {B})\n")
+        );
+
+        let (merged, _) =
+            merge_todo_contents(&sides.base, &sides.local, &sides.remote, None, |_| {
+                Ok(Resolution::Local)
+            })
+            .expect("merges");
+        assert!(merged.contains(&format!("- [x] Pay rent (id: {A})")));
+        assert!(!merged.contains("|||||||"));
+    }
+
+    #[test]
+    fn unterminated_conflict_block_is_not_split() {
+        assert!(split_conflict_markers("<<<<<<< HEAD\n- [_] a\n=======\n").is_none());
+        assert!(split_conflict_markers("- [_] a\n").is_none());
+    }
+
+    fn no_prompt(conflict: &Conflict) -> Result<Resolution> {
+        panic!("unexpected conflict for {}", conflict.id)
+    }
+
+    #[test]
+    fn one_sided_changes_merge_without_prompting() {
+        let base = format!("# Todos\n- [_] A (id: {A})\n- [_] B (id: {B})\n");
+        let local = format!("# Todos\n- [x] A (id: {A})\n- [_] B (id: {B})\n- [_] C (id: {C})\n");
+        let remote = format!("# Todos\n- [_] A (id: {A})\n- [_] B renamed (id: {B})\n");
+
+        let (merged, auto_merged) =
+            merge_todo_contents(&base, &local, &remote, None, no_prompt).expect("merge");
+        assert_eq!(
+            merged,
+            format!("# Todos\n- [x] A (id: {A})\n- [_] B renamed (id: {B})\n- [_] C (id: {C})\n")
+        );
+        assert_eq!(auto_merged, 3);
+    }
+
+    #[test]
+    fn local_notes_and_headings_survive_a_remote_edit() {
+        let base = format!("# Todos\n- [_] A (id: {A})\n\n## Old\n- [_] B (id: {B})\n");
+        let local = format!(
+            "# Todos\n- [_] A (id: {A})\n    call before noon\n\n## Old\n- [_] B (id: {B})\n\n## Errands\n- [_] C (id: {C})\n    bring the receipt\n"
+        );
+        let remote = format!("# Todos\n- [x] A (id: {A})\n\n- [_] B (id: {B})\n");
+
+        let (merged, auto_merged) =
+            merge_todo_contents(&base, &local, &remote, None, no_prompt).expect("merge");
+        assert_eq!(
+            merged,
+            format!(
+                "# Todos\n- [x] A (id: {A})\n    call before noon\n\n- [_] B (id: {B})\n\n## Errands\n- [_] C (id: {C})\n    bring the receipt\n"
+            )
+        );
+        assert_eq!(auto_merged, 2);
+    }
+
+    #[test]
+    fn notes_added_on_both_sides_are_kept() {
+        let base = format!("- [_] A (id: {A})\n    shared\n");
+        let local = format!("- [_] A today (id: {A})\n    shared\n    local\n");
+        let remote = format!("- [_] A (id: {A})\n    shared\n    remote\n");
+
+        let (merged, _) =
+            merge_todo_contents(&base, &local, &remote, None, no_prompt).expect("merge");
+        assert_eq!(
+            merged,
+            format!("- [_] A today (id: {A})\n    shared\n    remote\n    local\n")
+        );
+    }
+
+    #[test]
+    fn deletion_on_one_side_wins_over_no_change() {
+        let base = format!("- [_] A (id: {A})\n- [_] B (id: {B})\n");
+        let local = format!("- [_] B (id: {B})\n");
+        let remote = base.clone();
+
+        let (merged, _) =
+            merge_todo_contents(&base, &local, &remote, None, no_prompt).expect("merge");
+        assert_eq!(merged, format!("- [_] B (id: {B})\n"));
+    }
+
+    #[test]
+    fn same_field_changes_are_resolved_by_choice() {
+        let base = format!("- [_] A (id: {A})\n- [_] B (id: {B})\n");
+        let local = format!("- [_] A local (id: {A})\n- [_] B local (id: {B})\n");
+        let remote = format!("- [_] A remote (id: {A})\n- [_] B remote (id: {B})\n");
+
+        let mut seen = Vec::new();
+        let (merged, auto_merged) = merge_todo_contents(&base, &local, &remote, None, |conflict| {
+            seen.push(conflict.id.to_string());
+            Ok(if conflict.id.to_string() == A {
+                Resolution::Line("- [x] A merged".to_string())
+            } else {
+                Resolution::Local
+            })
+        })
+        .expect("merge");
+
+        assert_eq!(seen, [A, B]);
+        assert_eq!(auto_merged, 0);
+        assert_eq!(
+            merged,
+            format!("- [x] A merged (id: {A})\n- [_] B local (id: {B})\n")
+        );
+    }
+
+    #[test]
+    fn edits_to_different_fields_merge_field_by_field() {
+        let _zone = pin_home_zone(chrono_tz::UTC);
+        let base = format!("- [_] Pay rent (id: {A})\n");
+        let local = format!("- [x] Pay rent (id: {A})\n");
+        let remote =
+            format!("- [_] Pay rent (due: 2026-03-01T12:00:00Z) (priority: high) (id: {A})\n");
+
+        let (merged, auto_merged) =
+            merge_todo_contents(&base, &local, &remote, None, no_prompt).expect("merge");
+        assert_eq!(
+            merged,
+            format!("- [x] Pay rent (due: 2026-03-01 12:00 PM) (priority: high) (id: {A})\n")
+        );
+        assert_eq!(auto_merged, 1);
+    }
+
+    #[test]
+    fn same_field_edits_take_the_newer_side() {
+        let _zone = pin_home_zone(chrono_tz::UTC);
+        let base = format!("- [_] Pay rent (due: 2026-03-01T12:00:00Z) (id: {A})\n");
+        let local = format!("- [_] Pay rent (due: 2026-03-02T12:00:00Z) (id: {A})\n");
+        let remote = format!("- [_] Pay rent now (due: 2026-03-05T12:00:00Z) (id: {A})\n");
+
+        let (merged, _) = merge_todo_contents(&base, &local, &remote, Some(Side::Local), no_prompt)
+            .expect("merge");
+        assert_eq!(
+            merged,
+            format!("- [_] Pay rent now (due: 2026-03-02 12:00 PM) (id: {A})\n")
+        );
+    }
+
+    #[test]
+    fn recorded_updated_at_beats_the_newer_commit() {
+        let base = format!("- [_] Pay rent (created: 2026-02-01T08:00:00Z) (id: {A})\n");
+        let local = format!(
+            "- [_] Pay rent today (created: 2026-02-01T08:00:00Z) (updated: 2026-02-20T08:00:00Z) (id: {A})\n"
+        );
+        let remote = format!(
+            "- [_] Pay rent soon (created: 2026-02-01T08:00:00Z) (updated: 2026-02-10T08:00:00Z) (id: {A})\n"
+        );
+
+        let (merged, _) =
+            merge_todo_contents(&base, &local, &remote, Some(Side::Remote), no_prompt)
+                .expect("merge");
+        assert_eq!(merged, local);
+    }
+
+    #[test]
+    fn add_add_with_the_same_id_merges_by_newer_side() {
+        let local = format!("- [_] Call mom (due: 2026-03-01T12:00:00Z) (id: {A})\n");
+        let remote = format!("- [_] Call mom (priority: low) (id: {A})\n");
+
+        assert!(merge_todo_contents("", &local, &remote, None, |_| bail!("prompted")).is_err());
+        let (merged, auto_merged) =
+            merge_todo_contents("", &local, &remote, Some(Side::Remote), no_prompt).expect("merge");
+        assert_eq!(
+            merged,
+            format!("- [_] Call mom (priority: low) (id: {A})\n")
+        );
+        assert_eq!(auto_merged, 1);
+    }
+
+    #[test]
+    fn edit_against_delete_still_needs_a_choice() {
+        let base = format!("- [_] A (id: {A})\n");
+        let local = format!("- [x] A (id: {A})\n");
+
+        let error = merge_todo_contents(&base, &local, "", Some(Side::Local), |_| {
+            bail!("unresolvable")
+        })
+        .expect_err("edit/delete prompts");
+        assert_eq!(error.to_string(), "unresolvable");
+    }
+
+    #[test]
+    fn merged_line_must_keep_the_conflicting_id() {
+        let id = Uuid::parse_str(A).expect("id");
+        assert!(merged_line(&format!("- [_] A (id: {B})"), id).is_err());
+        assert!(merged_line("not a todo", id).is_err());
+    }
+}
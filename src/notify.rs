@@ -0,0 +1,216 @@
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use anyhow::{bail, Context, Result};
+use chrono::{DateTime, SecondsFormat, Utc};
+use uuid::Uuid;
+
+use crate::date_parser::{humanize_relative, in_home_zone};
+use crate::list::{is_due_soon, is_overdue, sorted_todos};
+use crate::storage::{write_todo_file_atomic, ParsedTodoFile};
+use crate::types::Todo;
+
+/// A reminder that was sent: when, and for which due date. A todo is
+/// reminded again only once its due date moves, e.g. after a recurring todo
+/// rolls forward.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Sent {
+    pub notified_at: DateTime<Utc>,
+    pub due: DateTime<Utc>,
+}
+
+/// Reminders already sent, by todo id.
+pub type NotifiedState = BTreeMap<Uuid, Sent>;
+
+/// Lives in `.todo_cache`, which the default `.gitignore` keeps out of sync.
+/// Each profile keeps its own, since recording drops ids it does not know.
+pub fn state_path(config_dir: &Path, profile: Option<&str>) -> PathBuf {
+    let name = match profile {
+        Some(profile) => format!("notified-{profile}"),
+        None => "notified".to_string(),
+    };
+    config_dir.join(".todo_cache").join(name)
+}
+
+/// Open todos that are overdue or due soon (see [`is_due_soon`]) and have no
+/// reminder on record for their current due date, in `list` order.
+pub fn pending_notifications<'a>(
+    parsed: &'a ParsedTodoFile,
+    state: &NotifiedState,
+    now: DateTime<Utc>,
+) -> Vec<&'a Todo> {
+    sorted_todos(parsed)
+        .into_iter()
+        .filter(|todo| is_overdue(todo, now) || is_due_soon(todo, now))
+        .filter(|todo| {
+            state
+                .get(&todo.id())
+                .is_none_or(|sent| Some(sent.due) != todo.due_date())
+        })
+        .collect()
+}
+
+/// Records `sent` in `state` and forgets todos that are gone or no longer
+/// dated, so the file does not grow without bound.
+pub fn record_sent(
+    state: &mut NotifiedState,
+    parsed: &ParsedTodoFile,
+    sent: &[&Todo],
+    now: DateTime<Utc>,
+) {
+    state.retain(|id, entry| {
+        parsed
+            .todos_by_id
+            .get(id)
+            .is_some_and(|todo| !todo.done() && todo.due_date() == Some(entry.due))
+    });
+    for todo in sent {
+        if let Some(due) = todo.due_date() {
+            state.insert(
+                todo.id(),
+                Sent {
+                    notified_at: now,
+                    due,
+                },
+            );
+        }
+    }
+}
+
+/// Summary and body for one reminder.
+pub fn notification_text(todo: &Todo, now: DateTime<Utc>) -> (String, String) {
+    let summary = if is_overdue(todo, now) {
+        format!("Overdue: {}", todo.name())
+    } else {
+        format!("Due soon: {}", todo.name())
+    };
+    let body = todo.due_date().map_or(String::new(), |due| {
+        format!(
+            "due {} ({})",
+            in_home_zone(due).format("%Y-%m-%d %I:%M %p"),
+            humanize_relative(due, now)
+        )
+    });
+    (summary, body)
+}
+
+/// Shows one desktop notification through `notify-send`.
+pub fn send_desktop(summary: &str, body: &str) -> Result<()> {
+    let status = Command::new("notify-send")
+        .args(["--app-name", "todo_md", summary, body])
+        .status()
+        .context("failed to run notify-send; use `todo_md notify --print` to pipe reminders to another notifier")?;
+    if !status.success() {
+        bail!("notify-send exited with {status}");
+    }
+    Ok(())
+}
+
+/// `<id>\t<notified at>\t<due>` per line; unreadable lines are dropped.
+pub fn parse_state(content: &str) -> NotifiedState {
+    let date = |raw: &str| {
+        DateTime::parse_from_rfc3339(raw)
+            .ok()
+            .map(|value| value.with_timezone(&Utc))
+    };
+    content
+        .lines()
+        .filter_map(|line| {
+            let mut fields = line.split('\t');
+            let id = Uuid::parse_str(fields.next()?).ok()?;
+            let notified_at = date(fields.next()?)?;
+            let due = date(fields.next()?)?;
+            Some((id, Sent { notified_at, due }))
+        })
+        .collect()
+}
+
+pub fn render_state(state: &NotifiedState) -> String {
+    state
+        .iter()
+        .map(|(id, sent)| {
+            format!(
+                "{id}\t{}\t{}\n",
+                sent.notified_at.to_rfc3339_opts(SecondsFormat::Secs, true),
+                sent.due.to_rfc3339_opts(SecondsFormat::Secs, true)
+            )
+        })
+        .collect()
+}
+
+pub fn read_state(path: &Path) -> Result<NotifiedState> {
+    match fs::read_to_string(path) {
+        Ok(content) => Ok(parse_state(&content)),
+        Err(error) if error.kind() == std::io::ErrorKind::NotFound => Ok(NotifiedState::new()),
+        Err(error) => Err(error).with_context(|| format!("failed to read {}", path.display())),
+    }
+}
+
+pub fn write_state(path: &Path, state: &NotifiedState) -> Result<()> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)
+            .with_context(|| format!("failed to create {}", parent.display()))?;
+    }
+    write_todo_file_atomic(path, &render_state(state))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::date_parser::pin_home_zone;
+    use crate::storage::parse_todo_content;
+    use chrono::TimeZone;
+
+    const CONTENT: &str = "- [_] Pay rent (due: 2026-02-20T09:00:00Z) (id: 123e4567-e89b-12d3-a456-426614174000)\n\
+        - [_] Call mom (due: 2026-02-24T09:00:00Z) (id: 123e4567-e89b-12d3-a456-426614174001)\n\
+        - [_] Renew passport (due: 2026-04-01T09:00:00Z) (id: 123e4567-e89b-12d3-a456-426614174002)\n\
+        - [x] Filed taxes (due: 2026-02-22T09:00:00Z) (id: 123e4567-e89b-12d3-a456-426614174003)\n\
+        - [_] Read more (id: 123e4567-e89b-12d3-a456-426614174004)\n";
+
+    fn now() -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2026, 2, 23, 18, 0, 0).unwrap()
+    }
+
+    fn names(todos: &[&Todo]) -> Vec<String> {
+        todos.iter().map(|todo| todo.name()).collect()
+    }
+
+    #[test]
+    fn selects_open_overdue_and_due_soon_todos() {
+        let _zone = pin_home_zone(chrono_tz::UTC);
+        let parsed = parse_todo_content(CONTENT);
+        let pending = pending_notifications(&parsed, &NotifiedState::new(), now());
+        assert_eq!(names(&pending), ["Pay rent", "Call mom"]);
+
+        let (summary, body) = notification_text(pending[0], now());
+        assert_eq!(summary, "Overdue: Pay rent");
+        assert_eq!(body, "due 2026-02-20 09:00 AM (3 days ago)");
+        assert_eq!(notification_text(pending[1], now()).0, "Due soon: Call mom");
+    }
+
+    #[test]
+    fn notifies_once_per_due_date() {
+        let parsed = parse_todo_content(CONTENT);
+        let mut state = NotifiedState::new();
+        let pending = pending_notifications(&parsed, &state, now());
+        record_sent(&mut state, &parsed, &pending, now());
+        assert!(pending_notifications(&parsed, &state, now()).is_empty());
+
+        // Moving the due date makes the todo eligible again.
+        let moved = parse_todo_content(&CONTENT.replace("2026-02-24T09", "2026-02-25T09"));
+        assert_eq!(
+            names(&pending_notifications(&moved, &state, now())),
+            ["Call mom"]
+        );
+
+        let reread = parse_state(&render_state(&state));
+        assert_eq!(reread, state);
+
+        // Entries for completed todos are dropped on the next record.
+        let done = parse_todo_content(&CONTENT.replace("- [_] Pay rent", "- [x] Pay rent"));
+        record_sent(&mut state, &done, &[], now());
+        assert_eq!(state.len(), 1);
+    }
+}
@@ -0,0 +1,58 @@
+use std::io::{self, IsTerminal, Stdout, Write};
+
+/// Destination for command output. Commands write through one `Printer` so the
+/// decision to decorate output is made once, and tests can capture what a
+/// command printed by using a `Vec<u8>` writer.
+pub struct Printer<W: Write> {
+    writer: W,
+    color: bool,
+}
+
+impl Printer<Stdout> {
+    /// Stdout, decorated only for interactive terminals that haven't opted out
+    /// via `--no-color` or a non-empty `NO_COLOR`.
+    pub fn stdout(no_color_flag: bool) -> Self {
+        let color = !no_color_flag
+            && std::env::var_os("NO_COLOR").is_none_or(|value| value.is_empty())
+            && io::stdout().is_terminal();
+        Self::new(io::stdout(), color)
+    }
+}
+
+impl<W: Write> Printer<W> {
+    pub fn new(writer: W, color: bool) -> Self {
+        Self { writer, color }
+    }
+
+    /// Whether colors, emoji and other decoration may be written.
+    pub fn color(&self) -> bool {
+        self.color
+    }
+
+    pub fn into_inner(self) -> W {
+        self.writer
+    }
+}
+
+impl<W: Write> Write for Printer<W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.writer.write(buf)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.writer.flush()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn captures_written_output() {
+        let mut printer = Printer::new(Vec::new(), false);
+        writeln!(printer, "sync committed").expect("write");
+        assert!(!printer.color());
+        assert_eq!(printer.into_inner(), b"sync committed\n");
+    }
+}
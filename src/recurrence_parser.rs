@@ -4,9 +4,17 @@ use chrono::{Datelike, Duration, Months, NaiveDate, TimeZone};
 use regex::Regex;
 use strsim::normalized_levenshtein;
 
-use crate::types::{DaysOfWeek, Reccurence};
+use crate::date_parser::{
+    configured_fuzzy, configured_rollover, configured_timezone, configured_tz,
+};
+use crate::types::{
+    DaysOfWeek, IntervalUnit, Reccurence, RecurrenceEnd, WeekdayOrdinal, WorkdayPosition,
+    MONTH_NAMES, WEEKDAYS,
+};
 
-pub fn parse_reccurence(raw: &str, now_local: DateTime<Local>) -> Option<Reccurence> {
+/// `now_local` should be in the user's zone: plain "weekly" repeats on its
+/// weekday.
+pub fn parse_reccurence<Tz: TimeZone>(raw: &str, now_local: DateTime<Tz>) -> Option<Reccurence> {
     let normalized = raw.trim().to_ascii_lowercase();
     if normalized == "daily" {
         return Some(Reccurence::Daily);
@@ -14,9 +22,15 @@ pub fn parse_reccurence(raw: &str, now_local: DateTime<Local>) -> Option<Reccure
     if normalized == "monthly" {
         return Some(Reccurence::Monthly(None));
     }
+    if normalized == "quarterly" {
+        return Some(Reccurence::Quarterly);
+    }
     if normalized == "yearly" {
         return Some(Reccurence::Yearly);
     }
+    if normalized == "weekdays" {
+        return Some(Reccurence::Weekly(WEEKDAYS.to_vec()));
+    }
     if normalized == "weekly" {
         return Some(Reccurence::Weekly(vec![from_chrono_weekday(
             now_local.weekday(),
@@ -28,33 +42,165 @@ pub fn parse_reccurence(raw: &str, now_local: DateTime<Local>) -> Option<Reccure
         return parse_weekly_days(days_part).map(Reccurence::Weekly);
     }
 
+    if let Some(rule) = normalized.strip_prefix("yearly on ") {
+        return parse_yearly_weekday(rule);
+    }
+
+    if let Some(rule) = normalized.strip_prefix("every ") {
+        return parse_interval(rule);
+    }
+
     let monthly_prefix = "monthly on ";
     if let Some(day_part) = normalized.strip_prefix(monthly_prefix) {
+        if let Some(position) = parse_workday_position(day_part) {
+            return Some(Reccurence::MonthlyWorkday(position));
+        }
+        if matches!(day_part.trim(), "the last day" | "last day") {
+            return Some(Reccurence::MonthlyLastDay);
+        }
+        if let Some(recurrence) = parse_monthly_weekday(day_part) {
+            return Some(recurrence);
+        }
         return parse_monthly_day(day_part).map(|day| Reccurence::Monthly(Some(day)));
     }
 
-    None
+    parse_compact_weekly(&normalized).map(Reccurence::Weekly)
+}
+
+/// Splits an end condition off a rule: `weekly on monday until 2026-06-01`
+/// or `daily x10`. `None` when the condition is malformed, e.g. `x0`.
+pub fn split_recurrence_end(raw: &str) -> Option<(&str, Option<RecurrenceEnd>)> {
+    let raw = raw.trim();
+    let end_re = Regex::new(r"(?i)^(?P<rule>.+?)\s+(?:until\s+(?P<until>\S+)|x(?P<count>\d+))$")
+        .expect("recurrence end regex");
+    let Some(captures) = end_re.captures(raw) else {
+        return Some((raw, None));
+    };
+    let end = match (captures.name("until"), captures.name("count")) {
+        (Some(until), _) => {
+            RecurrenceEnd::Until(NaiveDate::parse_from_str(until.as_str(), "%Y-%m-%d").ok()?)
+        }
+        (_, Some(count)) => {
+            RecurrenceEnd::Count(count.as_str().parse().ok().filter(|count| *count > 0)?)
+        }
+        _ => return None,
+    };
+    Some((captures.name("rule")?.as_str(), Some(end)))
 }
 
 pub fn next_due_date_utc(
     due_date: DateTime<Utc>,
     recurrence: &Reccurence,
 ) -> Option<DateTime<Utc>> {
-    let due_local = due_date.with_timezone(&Local);
-    let naive_due = due_local.naive_local();
+    if let Some(tz) = configured_tz() {
+        return next_due_in_zone(due_date, recurrence, &tz);
+    }
+    match configured_timezone() {
+        Some(tz) => next_due_in_zone(due_date, recurrence, &tz),
+        None => next_due_in_zone(due_date, recurrence, &Local),
+    }
+}
+
+/// Steps the due date on the wall clock of `tz`, so a daily 9am stays 9am there.
+fn next_due_in_zone<Tz: TimeZone>(
+    due_date: DateTime<Utc>,
+    recurrence: &Reccurence,
+    tz: &Tz,
+) -> Option<DateTime<Utc>> {
+    let naive_due = due_date.with_timezone(tz).naive_local();
     let next_naive = next_due_naive(naive_due, recurrence)?;
-    let next_local = localize(next_naive)?;
+    let next_local = match tz.from_local_datetime(&next_naive) {
+        LocalResult::Single(dt) => dt,
+        LocalResult::Ambiguous(a, b) => a.min(b),
+        LocalResult::None => return None,
+    };
     Some(next_local.with_timezone(&Utc))
 }
 
+/// How completing a recurring todo picks its next due date (`TODOS_ROLLOVER`).
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Rollover {
+    /// Exactly one step after the old due date, even if that is still past.
+    #[default]
+    Strict,
+    /// Steps until the due date is in the future, so an overdue daily todo
+    /// completed today is next due tomorrow.
+    FromNow,
+}
+
+impl Rollover {
+    pub fn parse(raw: &str) -> Option<Rollover> {
+        match raw.trim().to_ascii_lowercase().as_str() {
+            "strict" => Some(Rollover::Strict),
+            "from-now" | "from_now" => Some(Rollover::FromNow),
+            _ => None,
+        }
+    }
+}
+
+/// Upper bound on steps taken by [`Rollover::FromNow`]; about 27 years of a
+/// daily todo.
+const MAX_ROLLOVER_STEPS: usize = 10_000;
+
+/// Next due date when a recurring todo is completed at `now`, following the
+/// configured [`Rollover`] mode.
+pub fn rollover_due_date(
+    due_date: DateTime<Utc>,
+    recurrence: &Reccurence,
+    now: DateTime<Utc>,
+) -> Option<DateTime<Utc>> {
+    rollover_due_date_with(due_date, recurrence, now, configured_rollover())
+}
+
+fn rollover_due_date_with(
+    due_date: DateTime<Utc>,
+    recurrence: &Reccurence,
+    now: DateTime<Utc>,
+    mode: Rollover,
+) -> Option<DateTime<Utc>> {
+    let mut next = next_due_date_utc(due_date, recurrence)?;
+    if mode == Rollover::FromNow {
+        for _ in 0..MAX_ROLLOVER_STEPS {
+            if next > now {
+                break;
+            }
+            next = next_due_date_utc(next, recurrence)?;
+        }
+    }
+    Some(next)
+}
+
+/// Whether `current_due` is where completing at `previous_due` lands. Under
+/// [`Rollover::FromNow`] any later occurrence of the series counts.
 pub fn is_rollover_due_date(
     previous_due: DateTime<Utc>,
     current_due: DateTime<Utc>,
     recurrence: &Reccurence,
 ) -> bool {
-    next_due_date_utc(previous_due, recurrence)
-        .map(|next| next == current_due)
-        .unwrap_or(false)
+    is_rollover_due_date_with(previous_due, current_due, recurrence, configured_rollover())
+}
+
+fn is_rollover_due_date_with(
+    previous_due: DateTime<Utc>,
+    current_due: DateTime<Utc>,
+    recurrence: &Reccurence,
+    mode: Rollover,
+) -> bool {
+    let steps = match mode {
+        Rollover::Strict => 1,
+        Rollover::FromNow => MAX_ROLLOVER_STEPS,
+    };
+    let mut due = previous_due;
+    for _ in 0..steps {
+        let Some(next) = next_due_date_utc(due, recurrence) else {
+            return false;
+        };
+        if next >= current_due {
+            return next == current_due;
+        }
+        due = next;
+    }
+    false
 }
 
 fn next_due_naive(due: NaiveDateTime, recurrence: &Reccurence) -> Option<NaiveDateTime> {
@@ -69,10 +215,150 @@ fn next_due_naive(due: NaiveDateTime, recurrence: &Reccurence) -> Option<NaiveDa
             let next_date = add_months_clamped(due.date(), 1)?;
             Some(next_date.and_time(due.time()))
         }
+        Reccurence::Quarterly => {
+            let next_date = add_months_clamped(due.date(), 3)?;
+            Some(next_date.and_time(due.time()))
+        }
+        Reccurence::MonthlyLastDay => {
+            let month_first = due.date().with_day(1)?.checked_add_months(Months::new(1))?;
+            let last_day = last_day_of_month(month_first.year(), month_first.month())?;
+            Some(month_first.with_day(last_day)?.and_time(due.time()))
+        }
+        Reccurence::MonthlyWeekday { ordinal, weekday } => {
+            // The first occurrence after the due date, as for YearlyWeekday.
+            let this_month = nth_weekday_of_month(due.year(), due.month(), *ordinal, *weekday)?;
+            let next_date = if this_month > due.date() {
+                this_month
+            } else {
+                let month_first = due.date().with_day(1)?.checked_add_months(Months::new(1))?;
+                nth_weekday_of_month(month_first.year(), month_first.month(), *ordinal, *weekday)?
+            };
+            Some(next_date.and_time(due.time()))
+        }
+        Reccurence::MonthlyWorkday(position) => {
+            let month_first = due.date().with_day(1)?.checked_add_months(Months::new(1))?;
+            let next_date = workday_of_month(month_first.year(), month_first.month(), *position)?;
+            Some(next_date.and_time(due.time()))
+        }
         Reccurence::Yearly => {
             let next_date = add_years_clamped(due.date(), 1)?;
             Some(next_date.and_time(due.time()))
         }
+        Reccurence::YearlyWeekday {
+            month,
+            ordinal,
+            weekday,
+        } => {
+            // The first occurrence after the due date, so a due date that was
+            // set off-rule snaps onto it.
+            let this_year = nth_weekday_of_month(due.year(), *month, *ordinal, *weekday)?;
+            let next_date = if this_year > due.date() {
+                this_year
+            } else {
+                nth_weekday_of_month(due.year() + 1, *month, *ordinal, *weekday)?
+            };
+            Some(next_date.and_time(due.time()))
+        }
+        Reccurence::Interval { every, unit } => match unit {
+            IntervalUnit::Day => Some(due + Duration::days((*every).into())),
+            IntervalUnit::Week => Some(due + Duration::weeks((*every).into())),
+            IntervalUnit::Month => {
+                let next_date = add_months_clamped(due.date(), *every)?;
+                Some(next_date.and_time(due.time()))
+            }
+        },
+    }
+}
+
+/// "3 days", "2 weeks", "1 month"; the count must be at least 1. Every 3
+/// months is `Quarterly`, so it has one spelling.
+fn parse_interval(raw: &str) -> Option<Reccurence> {
+    let (count, unit) = raw.split_once(' ')?;
+    let every = count.parse::<u32>().ok().filter(|every| *every > 0)?;
+    let unit = match unit.trim() {
+        "day" | "days" => IntervalUnit::Day,
+        "week" | "weeks" => IntervalUnit::Week,
+        "month" | "months" => IntervalUnit::Month,
+        _ => return None,
+    };
+    if (every, unit) == (3, IntervalUnit::Month) {
+        return Some(Reccurence::Quarterly);
+    }
+    Some(Reccurence::Interval { every, unit })
+}
+
+/// "the last monday of may", "2nd sunday in march", ...
+fn parse_yearly_weekday(raw: &str) -> Option<Reccurence> {
+    let cleaned = raw.trim().trim_start_matches("the ");
+    let (ordinal, rest) = cleaned.split_once(' ')?;
+    let ordinal = parse_weekday_ordinal(ordinal)?;
+    let (weekday, month) = rest
+        .split_once(" of ")
+        .or_else(|| rest.split_once(" in "))?;
+    let weekday = parse_day_of_week(weekday.trim())?;
+    let month = month.trim();
+    let month = MONTH_NAMES
+        .iter()
+        .position(|name| *name == month || (month.len() >= 3 && name.starts_with(month)))?
+        as u32
+        + 1;
+    Some(Reccurence::YearlyWeekday {
+        month,
+        ordinal,
+        weekday,
+    })
+}
+
+/// "the first monday", "3rd friday", "last thursday", ...
+fn parse_monthly_weekday(raw: &str) -> Option<Reccurence> {
+    let cleaned = raw.trim().trim_start_matches("the ");
+    let (ordinal, weekday) = cleaned.split_once(' ')?;
+    Some(Reccurence::MonthlyWeekday {
+        ordinal: parse_weekday_ordinal(ordinal)?,
+        weekday: parse_day_of_week(weekday.trim())?,
+    })
+}
+
+/// There is no "fifth": not every month has one.
+fn parse_weekday_ordinal(raw: &str) -> Option<WeekdayOrdinal> {
+    match raw {
+        "first" | "1st" => Some(WeekdayOrdinal::First),
+        "second" | "2nd" => Some(WeekdayOrdinal::Second),
+        "third" | "3rd" => Some(WeekdayOrdinal::Third),
+        "fourth" | "4th" => Some(WeekdayOrdinal::Fourth),
+        "last" => Some(WeekdayOrdinal::Last),
+        _ => None,
+    }
+}
+
+fn nth_weekday_of_month(
+    year: i32,
+    month: u32,
+    ordinal: WeekdayOrdinal,
+    weekday: DaysOfWeek,
+) -> Option<NaiveDate> {
+    let target = weekday_number(weekday);
+    match ordinal {
+        WeekdayOrdinal::Last => {
+            let mut date = NaiveDate::from_ymd_opt(year, month, last_day_of_month(year, month)?)?;
+            while date.weekday().number_from_monday() != target {
+                date -= Duration::days(1);
+            }
+            Some(date)
+        }
+        _ => {
+            let nth = match ordinal {
+                WeekdayOrdinal::First => 0,
+                WeekdayOrdinal::Second => 1,
+                WeekdayOrdinal::Third => 2,
+                _ => 3,
+            };
+            let mut date = NaiveDate::from_ymd_opt(year, month, 1)?;
+            while date.weekday().number_from_monday() != target {
+                date += Duration::days(1);
+            }
+            Some(date + Duration::weeks(nth))
+        }
     }
 }
 
@@ -88,6 +374,35 @@ fn parse_monthly_day(raw: &str) -> Option<u32> {
     }
 }
 
+/// "first workday", "the last business day", ...
+fn parse_workday_position(raw: &str) -> Option<WorkdayPosition> {
+    let cleaned = raw.trim().trim_start_matches("the ");
+    let (position, rest) = cleaned.split_once(' ')?;
+    if !matches!(rest.trim(), "workday" | "weekday" | "business day") {
+        return None;
+    }
+    match position {
+        "first" | "1st" => Some(WorkdayPosition::First),
+        "last" => Some(WorkdayPosition::Last),
+        _ => None,
+    }
+}
+
+/// The first or last Monday-Friday of the month.
+fn workday_of_month(year: i32, month: u32, position: WorkdayPosition) -> Option<NaiveDate> {
+    let (mut date, step) = match position {
+        WorkdayPosition::First => (NaiveDate::from_ymd_opt(year, month, 1)?, 1),
+        WorkdayPosition::Last => (
+            NaiveDate::from_ymd_opt(year, month, last_day_of_month(year, month)?)?,
+            -1,
+        ),
+    };
+    while matches!(date.weekday(), Weekday::Sat | Weekday::Sun) {
+        date += Duration::days(step);
+    }
+    Some(date)
+}
+
 fn next_weekly_due(due: NaiveDateTime, days: &[DaysOfWeek]) -> NaiveDateTime {
     let mut day_indexes = days.iter().map(|d| weekday_number(*d)).collect::<Vec<_>>();
     if day_indexes.is_empty() {
@@ -134,20 +449,12 @@ fn add_years_clamped(date: NaiveDate, years: i32) -> Option<NaiveDate> {
     NaiveDate::from_ymd_opt(target_year, date.month(), day)
 }
 
-fn last_day_of_month(year: i32, month: u32) -> Option<u32> {
+pub(crate) fn last_day_of_month(year: i32, month: u32) -> Option<u32> {
     let first = NaiveDate::from_ymd_opt(year, month, 1)?;
     let next = first.with_day(1)?.checked_add_months(Months::new(1))?;
     Some((next - Duration::days(1)).day())
 }
 
-fn localize(naive: NaiveDateTime) -> Option<DateTime<Local>> {
-    match Local.from_local_datetime(&naive) {
-        LocalResult::Single(dt) => Some(dt),
-        LocalResult::Ambiguous(a, b) => Some(a.min(b)),
-        LocalResult::None => None,
-    }
-}
-
 fn parse_weekly_days(raw: &str) -> Option<Vec<DaysOfWeek>> {
     let mut days = Vec::new();
     let normalized = raw.replace(" and ", ",");
@@ -178,7 +485,57 @@ fn parse_day_group(token: &str) -> Option<Vec<DaysOfWeek>> {
         return Some(expand_day_range(start_day, end_day));
     }
 
-    Some(vec![parse_day_of_week(token)?])
+    parse_day_of_week(token)
+        .map(|day| vec![day])
+        .or_else(|| parse_compact_days(token))
+}
+
+/// Bare compact weekly notation such as `MWF` or `M,W,F`.
+fn parse_compact_weekly(raw: &str) -> Option<Vec<DaysOfWeek>> {
+    let mut days = Vec::new();
+    for token in raw.split(',').map(str::trim) {
+        for day in parse_compact_days(token)? {
+            if !days.contains(&day) {
+                days.push(day);
+            }
+        }
+    }
+    if days.is_empty() {
+        None
+    } else {
+        Some(days)
+    }
+}
+
+/// Reads run-together day codes, longest match first. Two-letter codes are
+/// `tu`, `th`, `sa`, `su`; single letters are `m`, `t` (Tuesday), `w`, `r`
+/// (Thursday) and `f`. A lone `s` is ambiguous and rejects the token.
+fn parse_compact_days(token: &str) -> Option<Vec<DaysOfWeek>> {
+    let codes = [
+        ("tu", DaysOfWeek::Tuesday),
+        ("th", DaysOfWeek::Thursday),
+        ("sa", DaysOfWeek::Saturday),
+        ("su", DaysOfWeek::Sunday),
+        ("m", DaysOfWeek::Monday),
+        ("t", DaysOfWeek::Tuesday),
+        ("w", DaysOfWeek::Wednesday),
+        ("r", DaysOfWeek::Thursday),
+        ("f", DaysOfWeek::Friday),
+    ];
+
+    let mut rest = token.trim();
+    if rest.is_empty() {
+        return None;
+    }
+    let mut days = Vec::new();
+    while !rest.is_empty() {
+        let (code, day) = codes.iter().find(|(code, _)| rest.starts_with(code))?;
+        if !days.contains(day) {
+            days.push(*day);
+        }
+        rest = &rest[code.len()..];
+    }
+    Some(days)
 }
 
 fn expand_day_range(start: DaysOfWeek, end: DaysOfWeek) -> Vec<DaysOfWeek> {
@@ -235,6 +592,11 @@ fn day_from_index(index: usize) -> DaysOfWeek {
 }
 
 fn parse_day_of_week(raw: &str) -> Option<DaysOfWeek> {
+    parse_day_of_week_with(raw, configured_fuzzy())
+}
+
+/// Exact names and abbreviations; with `fuzzy`, also near misses.
+fn parse_day_of_week_with(raw: &str, fuzzy: bool) -> Option<DaysOfWeek> {
     let token = raw.trim().to_ascii_lowercase();
     let aliases = [
         ("monday", DaysOfWeek::Monday),
@@ -259,6 +621,9 @@ fn parse_day_of_week(raw: &str) -> Option<DaysOfWeek> {
     if let Some((_, day)) = aliases.iter().find(|(name, _)| *name == token) {
         return Some(*day);
     }
+    if !fuzzy {
+        return None;
+    }
 
     let mut best: Option<DaysOfWeek> = None;
     let mut best_score = 0.0;
@@ -292,6 +657,8 @@ fn from_chrono_weekday(day: Weekday) -> DaysOfWeek {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::date_parser::pin_home_zone;
+    use crate::types::RecurrenceStyle;
     use chrono::{FixedOffset, TimeZone};
 
     fn fixed_local() -> DateTime<Local> {
@@ -303,6 +670,53 @@ mod tests {
             .with_timezone(&Local)
     }
 
+    fn utc(raw: &str) -> DateTime<Utc> {
+        DateTime::parse_from_rfc3339(raw)
+            .expect("valid date")
+            .with_timezone(&Utc)
+    }
+
+    #[test]
+    fn strict_rollover_steps_once_from_an_overdue_date() {
+        let due = utc("2026-02-20T09:00:00Z");
+        let now = utc("2026-02-23T18:00:00Z");
+        assert_eq!(
+            rollover_due_date_with(due, &Reccurence::Daily, now, Rollover::Strict),
+            Some(utc("2026-02-21T09:00:00Z"))
+        );
+    }
+
+    #[test]
+    fn from_now_rollover_skips_to_the_next_future_date() {
+        let due = utc("2026-02-20T09:00:00Z");
+        let now = utc("2026-02-23T18:00:00Z");
+        let next = rollover_due_date_with(due, &Reccurence::Daily, now, Rollover::FromNow);
+        assert_eq!(next, Some(utc("2026-02-24T09:00:00Z")));
+
+        // A todo that is not overdue still moves exactly one step.
+        let upcoming = utc("2026-02-25T09:00:00Z");
+        assert_eq!(
+            rollover_due_date_with(upcoming, &Reccurence::Daily, now, Rollover::FromNow),
+            Some(utc("2026-02-26T09:00:00Z"))
+        );
+
+        // The diff still reads the multi-step jump as a completion.
+        assert!(is_rollover_due_date_with(
+            due,
+            utc("2026-02-24T09:00:00Z"),
+            &Reccurence::Daily,
+            Rollover::FromNow
+        ));
+        assert!(!is_rollover_due_date_with(
+            due,
+            utc("2026-02-24T09:00:00Z"),
+            &Reccurence::Daily,
+            Rollover::Strict
+        ));
+        assert_eq!(Rollover::parse("From-Now"), Some(Rollover::FromNow));
+        assert_eq!(Rollover::parse("skip"), None);
+    }
+
     #[test]
     fn parses_weekly_range() {
         let parsed = parse_reccurence("weekly on mon-fri", fixed_local()).expect("valid parse");
@@ -345,6 +759,111 @@ mod tests {
         assert_eq!(parsed, Reccurence::Weekly(vec![DaysOfWeek::Monday]));
     }
 
+    #[test]
+    fn defaults_plain_weekly_to_today_in_the_given_zone() {
+        // Sunday evening in UTC is already Monday in Auckland (+13:00).
+        let sunday_utc = Utc
+            .with_ymd_and_hms(2026, 2, 22, 20, 0, 0)
+            .single()
+            .expect("valid dt");
+        let auckland = FixedOffset::east_opt(13 * 3600).expect("valid offset");
+
+        let parsed =
+            parse_reccurence("weekly", sunday_utc.with_timezone(&auckland)).expect("valid parse");
+        assert_eq!(parsed, Reccurence::Weekly(vec![DaysOfWeek::Monday]));
+        let parsed = parse_reccurence("weekly", sunday_utc).expect("valid parse");
+        assert_eq!(parsed, Reccurence::Weekly(vec![DaysOfWeek::Sunday]));
+    }
+
+    #[test]
+    fn steps_daily_due_on_the_given_zone_wall_clock() {
+        let due = Utc
+            .with_ymd_and_hms(2026, 2, 23, 14, 0, 0)
+            .single()
+            .expect("valid dt");
+        let new_york = FixedOffset::west_opt(5 * 3600).expect("valid offset");
+        let next = next_due_in_zone(due, &Reccurence::Daily, &new_york).expect("next due");
+        assert_eq!(next.to_rfc3339(), "2026-02-24T14:00:00+00:00");
+
+        // A named zone keeps 9am across the switch to daylight time.
+        let due = Utc
+            .with_ymd_and_hms(2026, 3, 7, 14, 0, 0)
+            .single()
+            .expect("valid dt");
+        let next = next_due_in_zone(due, &Reccurence::Daily, &chrono_tz::America::New_York)
+            .expect("next due");
+        assert_eq!(next.to_rfc3339(), "2026-03-08T13:00:00+00:00");
+    }
+
+    #[test]
+    fn parses_yearly_nth_weekday_of_month() {
+        let memorial_day = Reccurence::YearlyWeekday {
+            month: 5,
+            ordinal: WeekdayOrdinal::Last,
+            weekday: DaysOfWeek::Monday,
+        };
+        assert_eq!(
+            parse_reccurence("yearly on the last monday of may", fixed_local()),
+            Some(memorial_day.clone())
+        );
+        assert_eq!(memorial_day.as_str(), "yearly on the last monday of may");
+        assert_eq!(
+            parse_reccurence(&memorial_day.as_str(), fixed_local()),
+            Some(memorial_day)
+        );
+        assert_eq!(
+            parse_reccurence("yearly on 2nd sun in mar", fixed_local()),
+            Some(Reccurence::YearlyWeekday {
+                month: 3,
+                ordinal: WeekdayOrdinal::Second,
+                weekday: DaysOfWeek::Sunday,
+            })
+        );
+        assert_eq!(
+            parse_reccurence("yearly on the fifth monday of may", fixed_local()),
+            None
+        );
+    }
+
+    #[test]
+    fn yearly_weekday_date_shifts_between_years() {
+        let rule = Reccurence::YearlyWeekday {
+            month: 5,
+            ordinal: WeekdayOrdinal::Last,
+            weekday: DaysOfWeek::Monday,
+        };
+        let due = Utc
+            .with_ymd_and_hms(2026, 5, 25, 9, 0, 0)
+            .single()
+            .expect("valid dt");
+        let next = next_due_date_utc(due, &rule).expect("next");
+        assert_eq!(
+            next.date_naive(),
+            NaiveDate::from_ymd_opt(2027, 5, 31).expect("date")
+        );
+        let after = next_due_date_utc(next, &rule).expect("after");
+        assert_eq!(
+            after.date_naive(),
+            NaiveDate::from_ymd_opt(2028, 5, 29).expect("date")
+        );
+
+        // Thanksgiving: 4th Thursday of November, from an off-rule due date.
+        let thanksgiving = Reccurence::YearlyWeekday {
+            month: 11,
+            ordinal: WeekdayOrdinal::Fourth,
+            weekday: DaysOfWeek::Thursday,
+        };
+        let due = Utc
+            .with_ymd_and_hms(2026, 2, 1, 9, 0, 0)
+            .single()
+            .expect("valid dt");
+        let next = next_due_date_utc(due, &thanksgiving).expect("next");
+        assert_eq!(
+            next.date_naive(),
+            NaiveDate::from_ymd_opt(2026, 11, 26).expect("date")
+        );
+    }
+
     #[test]
     fn parses_monthly_with_ordinal_day() {
         let parsed = parse_reccurence("monthly on the 18th", fixed_local()).expect("valid parse");
@@ -378,6 +897,46 @@ mod tests {
         assert_eq!(next.to_rfc3339(), "2026-02-28T10:30:00+00:00");
     }
 
+    #[test]
+    fn every_n_days_crosses_month_boundaries() {
+        let every_ten = parse_reccurence("every 10 days", fixed_local()).expect("parses");
+        assert_eq!(
+            every_ten,
+            Reccurence::Interval {
+                every: 10,
+                unit: IntervalUnit::Day,
+            }
+        );
+        assert_eq!(every_ten.as_str(), "every 10 days");
+
+        let due = utc("2026-01-25T10:30:00Z");
+        let next = next_due_date_utc(due, &every_ten).expect("next due");
+        assert_eq!(next.to_rfc3339(), "2026-02-04T10:30:00+00:00");
+        let next = next_due_date_utc(utc("2026-02-25T10:30:00Z"), &every_ten).expect("next due");
+        assert_eq!(next.to_rfc3339(), "2026-03-07T10:30:00+00:00");
+
+        let fortnightly = parse_reccurence("every 2 weeks", fixed_local()).expect("parses");
+        let next = next_due_date_utc(due, &fortnightly).expect("next due");
+        assert_eq!(next.to_rfc3339(), "2026-02-08T10:30:00+00:00");
+
+        assert_eq!(parse_reccurence("every 0 days", fixed_local()), None);
+        assert_eq!(parse_reccurence("every 3 fortnights", fixed_local()), None);
+    }
+
+    #[test]
+    fn quarterly_clamps_across_the_year_boundary() {
+        let quarterly = parse_reccurence("quarterly", fixed_local()).expect("parses");
+        assert_eq!(quarterly, Reccurence::Quarterly);
+        assert_eq!(
+            parse_reccurence("every 3 months", fixed_local()),
+            Some(Reccurence::Quarterly)
+        );
+        assert_eq!(quarterly.as_str(), "quarterly");
+
+        let next = next_due_date_utc(utc("2025-11-30T09:00:00Z"), &quarterly).expect("next due");
+        assert_eq!(next.to_rfc3339(), "2026-02-28T09:00:00+00:00");
+    }
+
     #[test]
     fn advances_monthly_specific_day_with_clamp() {
         let due = DateTime::parse_from_rfc3339("2026-01-18T10:30:00Z")
@@ -387,4 +946,295 @@ mod tests {
         let next = next_due_date_utc(due, &Reccurence::Monthly(Some(31))).expect("next due");
         assert_eq!(next.to_rfc3339(), "2026-02-28T10:30:00+00:00");
     }
+
+    #[test]
+    fn parses_first_and_last_workday() {
+        let first = parse_reccurence("monthly on the first workday", fixed_local());
+        assert_eq!(
+            first,
+            Some(Reccurence::MonthlyWorkday(WorkdayPosition::First))
+        );
+        let last = parse_reccurence("monthly on last business day", fixed_local());
+        assert_eq!(
+            last,
+            Some(Reccurence::MonthlyWorkday(WorkdayPosition::Last))
+        );
+        assert_eq!(
+            parse_reccurence("monthly on the second workday", fixed_local()),
+            None
+        );
+    }
+
+    #[test]
+    fn first_workday_skips_a_weekend_month_start() {
+        // 2026-03-01 and 2026-08-01 fall on a Sunday and a Saturday.
+        let due = DateTime::parse_from_rfc3339("2026-02-02T09:00:00Z")
+            .expect("valid due")
+            .with_timezone(&Utc);
+        let recurrence = Reccurence::MonthlyWorkday(WorkdayPosition::First);
+
+        let next = next_due_date_utc(due, &recurrence).expect("next due");
+        assert_eq!(next.to_rfc3339(), "2026-03-02T09:00:00+00:00");
+
+        let july = DateTime::parse_from_rfc3339("2026-07-01T09:00:00Z")
+            .expect("valid due")
+            .with_timezone(&Utc);
+        let next = next_due_date_utc(july, &recurrence).expect("next due");
+        assert_eq!(next.to_rfc3339(), "2026-08-03T09:00:00+00:00");
+    }
+
+    #[test]
+    fn last_workday_skips_a_weekend_month_end() {
+        // 2026-02-28 falls on a Saturday.
+        let due = DateTime::parse_from_rfc3339("2026-01-30T17:00:00Z")
+            .expect("valid due")
+            .with_timezone(&Utc);
+
+        let next = next_due_date_utc(due, &Reccurence::MonthlyWorkday(WorkdayPosition::Last))
+            .expect("next due");
+        assert_eq!(next.to_rfc3339(), "2026-02-27T17:00:00+00:00");
+    }
+
+    #[test]
+    fn last_day_of_month_snaps_back_after_february() {
+        let _zone = pin_home_zone(chrono_tz::UTC);
+        let recurrence =
+            parse_reccurence("monthly on the last day", fixed_local()).expect("parses");
+        assert_eq!(recurrence, Reccurence::MonthlyLastDay);
+        assert_eq!(recurrence.as_str(), "monthly on the last day");
+
+        let february = next_due_date_utc(utc("2026-01-31T17:00:00Z"), &recurrence).expect("next");
+        assert_eq!(february.to_rfc3339(), "2026-02-28T17:00:00+00:00");
+        let march = next_due_date_utc(february, &recurrence).expect("next");
+        assert_eq!(march.to_rfc3339(), "2026-03-31T17:00:00+00:00");
+        let april = next_due_date_utc(march, &recurrence).expect("next");
+        assert_eq!(april.to_rfc3339(), "2026-04-30T17:00:00+00:00");
+    }
+
+    #[test]
+    fn last_weekday_of_month_follows_each_months_week_count() {
+        let recurrence =
+            parse_reccurence("monthly on the last thursday", fixed_local()).expect("parses");
+        assert_eq!(
+            recurrence,
+            Reccurence::MonthlyWeekday {
+                ordinal: WeekdayOrdinal::Last,
+                weekday: DaysOfWeek::Thursday,
+            }
+        );
+
+        // April 2026 has five Thursdays, February and March four.
+        let mut due = utc("2026-01-29T15:00:00Z");
+        let mut dates = Vec::new();
+        for _ in 0..3 {
+            due = next_due_date_utc(due, &recurrence).expect("next");
+            dates.push(due.date_naive().to_string());
+        }
+        assert_eq!(dates, ["2026-02-26", "2026-03-26", "2026-04-30"]);
+
+        let first_monday =
+            parse_reccurence("monthly on 1st monday", fixed_local()).expect("parses");
+        assert_eq!(first_monday.as_str(), "monthly on the first monday");
+        let next = next_due_date_utc(utc("2026-02-02T15:00:00Z"), &first_monday).expect("next");
+        assert_eq!(next.to_rfc3339(), "2026-03-02T15:00:00+00:00");
+        // A due date off the rule snaps onto it within the same month.
+        let next = next_due_date_utc(utc("2026-03-01T15:00:00Z"), &first_monday).expect("next");
+        assert_eq!(next.to_rfc3339(), "2026-03-02T15:00:00+00:00");
+
+        assert_eq!(
+            parse_reccurence("monthly on the fifth friday", fixed_local()),
+            None
+        );
+    }
+
+    #[test]
+    fn parses_compact_weekly_notation() {
+        let expected = Reccurence::Weekly(vec![
+            DaysOfWeek::Monday,
+            DaysOfWeek::Wednesday,
+            DaysOfWeek::Friday,
+        ]);
+        assert_eq!(
+            parse_reccurence("MWF", fixed_local()),
+            Some(expected.clone())
+        );
+        assert_eq!(
+            parse_reccurence("M,W,F", fixed_local()),
+            Some(expected.clone())
+        );
+        assert_eq!(
+            parse_reccurence("weekly on mwf", fixed_local()),
+            Some(expected)
+        );
+    }
+
+    #[test]
+    fn compact_notation_disambiguates_t_and_s() {
+        assert_eq!(
+            parse_reccurence("TTh", fixed_local()),
+            Some(Reccurence::Weekly(vec![
+                DaysOfWeek::Tuesday,
+                DaysOfWeek::Thursday
+            ]))
+        );
+        assert_eq!(
+            parse_reccurence("TuR", fixed_local()),
+            Some(Reccurence::Weekly(vec![
+                DaysOfWeek::Tuesday,
+                DaysOfWeek::Thursday
+            ]))
+        );
+        assert_eq!(
+            parse_reccurence("SaSu", fixed_local()),
+            Some(Reccurence::Weekly(vec![
+                DaysOfWeek::Saturday,
+                DaysOfWeek::Sunday
+            ]))
+        );
+        assert_eq!(parse_reccurence("MS", fixed_local()), None);
+        assert_eq!(parse_reccurence("S", fixed_local()), None);
+    }
+
+    #[test]
+    fn weekday_typos_need_fuzzy_matching() {
+        assert_eq!(
+            parse_day_of_week_with("wensday", true),
+            Some(DaysOfWeek::Wednesday)
+        );
+        assert_eq!(parse_day_of_week_with("wensday", false), None);
+        assert_eq!(
+            parse_day_of_week_with("wed", false),
+            Some(DaysOfWeek::Wednesday)
+        );
+    }
+
+    #[test]
+    fn full_day_names_are_not_read_as_compact_codes() {
+        assert_eq!(
+            parse_reccurence("weekly on sat", fixed_local()),
+            Some(Reccurence::Weekly(vec![DaysOfWeek::Saturday]))
+        );
+        assert_eq!(parse_reccurence("monday", fixed_local()), None);
+    }
+
+    /// Same schedule, regardless of day order, repeats, or a seven-day
+    /// weekly spelling of daily.
+    fn canonical(recurrence: &Reccurence) -> Reccurence {
+        match recurrence {
+            Reccurence::Weekly(days) => {
+                let mut indexes = days.iter().map(|day| day_index(*day)).collect::<Vec<_>>();
+                indexes.sort_unstable();
+                indexes.dedup();
+                if indexes.len() == 7 {
+                    Reccurence::Daily
+                } else {
+                    Reccurence::Weekly(indexes.into_iter().map(day_from_index).collect())
+                }
+            }
+            other => other.clone(),
+        }
+    }
+
+    /// Every recurrence the serializer can be handed: each non-empty day set
+    /// (forwards, backwards, and with a repeated day), each day of the month,
+    /// and each yearly weekday rule.
+    fn all_recurrences() -> Vec<Reccurence> {
+        let mut all = vec![
+            Reccurence::Daily,
+            Reccurence::Monthly(None),
+            Reccurence::MonthlyWorkday(WorkdayPosition::First),
+            Reccurence::MonthlyWorkday(WorkdayPosition::Last),
+            Reccurence::MonthlyLastDay,
+            Reccurence::Quarterly,
+            Reccurence::Yearly,
+        ];
+        for mask in 1..128_usize {
+            let days = (0..7)
+                .filter(|index| mask & (1 << index) != 0)
+                .map(day_from_index)
+                .collect::<Vec<_>>();
+            let mut backwards = days.clone();
+            backwards.reverse();
+            let mut repeated = days.clone();
+            repeated.push(days[0]);
+            all.extend([days, backwards, repeated].map(Reccurence::Weekly));
+        }
+        all.extend((1..=31).map(|day| Reccurence::Monthly(Some(day))));
+        for unit in [IntervalUnit::Day, IntervalUnit::Week, IntervalUnit::Month] {
+            all.extend([1, 2, 10].map(|every| Reccurence::Interval { every, unit }));
+        }
+        let ordinals = [
+            WeekdayOrdinal::First,
+            WeekdayOrdinal::Second,
+            WeekdayOrdinal::Third,
+            WeekdayOrdinal::Fourth,
+            WeekdayOrdinal::Last,
+        ];
+        for ordinal in ordinals {
+            for weekday in (0..7).map(day_from_index) {
+                all.push(Reccurence::MonthlyWeekday { ordinal, weekday });
+            }
+        }
+        for month in 1..=12 {
+            for ordinal in ordinals {
+                for weekday in (0..7).map(day_from_index) {
+                    all.push(Reccurence::YearlyWeekday {
+                        month,
+                        ordinal,
+                        weekday,
+                    });
+                }
+            }
+        }
+        all
+    }
+
+    #[test]
+    fn every_recurrence_round_trips_through_as_str() {
+        for style in [RecurrenceStyle::Compact, RecurrenceStyle::Verbose] {
+            for recurrence in all_recurrences() {
+                let written = recurrence.as_str_with(style);
+                let reparsed = parse_reccurence(&written, fixed_local());
+                assert_eq!(
+                    reparsed.as_ref().map(canonical),
+                    Some(canonical(&recurrence)),
+                    "{recurrence:?} was written as `{written}` ({style:?})"
+                );
+                assert_eq!(
+                    reparsed.map(|reparsed| reparsed.as_str_with(style)),
+                    Some(written),
+                    "`{}` is not stable",
+                    recurrence.as_str_with(style)
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn day_ranges_match_their_spelled_out_days() {
+        let names = [
+            "monday",
+            "tuesday",
+            "wednesday",
+            "thursday",
+            "friday",
+            "saturday",
+            "sunday",
+        ];
+        for start in 0..7 {
+            for end in 0..7 {
+                let range = format!("weekly on {}-{}", names[start], names[end]);
+                let spelled = (0..)
+                    .map(|step| (start + step) % 7)
+                    .take((end + 7 - start) % 7 + 1)
+                    .map(|index| names[index])
+                    .collect::<Vec<_>>()
+                    .join(", ");
+                let from_range = parse_reccurence(&range, fixed_local()).expect("range parses");
+                let from_days = parse_reccurence(&format!("weekly on {spelled}"), fixed_local())
+                    .expect("days parse");
+                assert_eq!(canonical(&from_range), canonical(&from_days), "{range}");
+            }
+        }
+    }
 }
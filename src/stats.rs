@@ -0,0 +1,128 @@
+use std::collections::BTreeMap;
+
+use chrono::{DateTime, Utc};
+
+use crate::list::is_overdue;
+use crate::storage::{validate_todo_issues, ParsedTodoFile};
+use crate::types::Reccurence;
+
+/// Workload and health of the todo file, for `stats`.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct TodoStats {
+    pub total: usize,
+    pub open: usize,
+    pub done: usize,
+    pub dated: usize,
+    pub overdue: usize,
+    /// Recurring todos per rule kind, e.g. `weekly`.
+    pub recurring: BTreeMap<&'static str, usize>,
+    /// Lines `validate` would report.
+    pub issues: usize,
+}
+
+impl TodoStats {
+    pub fn render(&self) -> String {
+        let mut out = format!(
+            "todos: {} ({} open, {} done)\ndue dates: {} ({} overdue)\n",
+            self.total, self.open, self.done, self.dated, self.overdue
+        );
+        if self.recurring.is_empty() {
+            out.push_str("recurring: none\n");
+        } else {
+            let kinds = self
+                .recurring
+                .iter()
+                .map(|(kind, count)| format!("{kind} {count}"))
+                .collect::<Vec<_>>()
+                .join(", ");
+            out.push_str(&format!("recurring: {kinds}\n"));
+        }
+        out.push_str(&format!("issues: {}\n", self.issues));
+        out
+    }
+}
+
+/// Counts over every todo in `parsed`, someday items included; overdue as
+/// of `now` (see [`is_overdue`]).
+pub fn todo_stats(parsed: &ParsedTodoFile, now: DateTime<Utc>) -> TodoStats {
+    let mut stats = TodoStats {
+        issues: validate_todo_issues(&parsed.content).len(),
+        ..TodoStats::default()
+    };
+    for todo in parsed.todos_by_id.values() {
+        stats.total += 1;
+        if todo.done() {
+            stats.done += 1;
+        } else {
+            stats.open += 1;
+        }
+        if todo.due_date().is_some() {
+            stats.dated += 1;
+        }
+        if is_overdue(todo, now) {
+            stats.overdue += 1;
+        }
+        if let Some(rule) = todo.recurence() {
+            *stats.recurring.entry(recurrence_kind(rule)).or_default() += 1;
+        }
+    }
+    stats
+}
+
+fn recurrence_kind(rule: &Reccurence) -> &'static str {
+    match rule {
+        Reccurence::Daily => "daily",
+        Reccurence::Weekly(_) => "weekly",
+        Reccurence::Monthly(_)
+        | Reccurence::MonthlyWorkday(_)
+        | Reccurence::MonthlyLastDay
+        | Reccurence::MonthlyWeekday { .. } => "monthly",
+        Reccurence::Quarterly => "quarterly",
+        Reccurence::Yearly | Reccurence::YearlyWeekday { .. } => "yearly",
+        Reccurence::Interval { .. } => "interval",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::parse_todo_content;
+
+    #[test]
+    fn counts_match_the_file() {
+        let now = DateTime::parse_from_rfc3339("2026-02-23T18:00:00Z")
+            .expect("valid timestamp")
+            .with_timezone(&Utc);
+        let parsed = parse_todo_content(
+            "- [_] Pay rent (due: 2026-02-20T17:00:00Z) (reccurence: monthly) (id: 123e4567-e89b-12d3-a456-426614174000)\n\
+             - [_] Water plants (due: 2026-02-25T17:00:00Z) (reccurence: weekly) (id: 123e4567-e89b-12d3-a456-426614174001)\n\
+             - [_] Stretch (due: 2026-02-21T17:00:00Z) (reccurence: daily) (id: 123e4567-e89b-12d3-a456-426614174002)\n\
+             - [x] Book flights (due: 2026-02-01T17:00:00Z) (id: 123e4567-e89b-12d3-a456-426614174003)\n\
+             - [_] Read a book (id: 123e4567-e89b-12d3-a456-426614174004)\n\
+             - [_] Quarterly taxes (due: 2026-04-15T17:00:00Z) (reccurence: monthly on the 15th) (id: 123e4567-e89b-12d3-a456-426614174005)\n\
+             - [_] no id here\n",
+        );
+
+        let stats = todo_stats(&parsed, now);
+
+        assert_eq!(
+            stats,
+            TodoStats {
+                total: 7,
+                open: 6,
+                done: 1,
+                dated: 5,
+                overdue: 2,
+                recurring: BTreeMap::from([("daily", 1), ("monthly", 2), ("weekly", 1)]),
+                issues: 1,
+            }
+        );
+        assert_eq!(
+            stats.render(),
+            "todos: 7 (6 open, 1 done)\n\
+             due dates: 5 (2 overdue)\n\
+             recurring: daily 1, monthly 2, weekly 1\n\
+             issues: 1\n"
+        );
+    }
+}
@@ -1,21 +1,66 @@
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
+use std::fmt;
 use std::fs;
 use std::io::Write;
-use std::path::Path;
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+use std::time::SystemTime;
 
-use anyhow::{Context, Result};
+use anyhow::{anyhow, bail, Context, Result};
+use chrono::{DateTime, NaiveDateTime, Utc};
 use regex::Regex;
 use uuid::Uuid;
 
-use crate::types::Todo;
+use crate::frontmatter::{
+    frontmatter_line_count, parse_frontmatter, set_frontmatter_value, LINE_FORMAT_VERSION,
+};
+use crate::types::{unwrap_id, Todo};
 
 #[derive(Debug, Clone)]
 pub struct ParsedTodoFile {
     pub content: String,
     pub todos_by_id: HashMap<Uuid, Todo>,
+    /// Ids in the order their todos appear in the file; a duplicated id is
+    /// listed once, where it first appears.
+    pub order: Vec<Uuid>,
+    /// The file's size and modification time when it was read; `None` for
+    /// content that did not come from disk.
+    pub stamp: Option<FileStamp>,
 }
 
-pub fn ensure_layout(config_dir: &Path, todo_file: &Path, env_file: &Path) -> Result<()> {
+/// Enough of a file's metadata to notice that it was rewritten.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FileStamp {
+    modified: Option<SystemTime>,
+    len: u64,
+}
+
+impl ParsedTodoFile {
+    /// Todos in file order (see [`ParsedTodoFile::order`]).
+    pub fn todos_in_order(&self) -> Vec<&Todo> {
+        self.order
+            .iter()
+            .filter_map(|id| self.todos_by_id.get(id))
+            .collect()
+    }
+}
+
+impl FileStamp {
+    pub fn of(path: &Path) -> Option<FileStamp> {
+        let metadata = fs::metadata(path).ok()?;
+        Some(FileStamp {
+            modified: metadata.modified().ok(),
+            len: metadata.len(),
+        })
+    }
+}
+
+pub fn ensure_layout(
+    config_dir: &Path,
+    todo_file: &Path,
+    env_file: &Path,
+    gitignore_entries: &[String],
+) -> Result<()> {
     fs::create_dir_all(config_dir)
         .with_context(|| format!("failed to create {}", config_dir.display()))?;
 
@@ -30,42 +75,348 @@ pub fn ensure_layout(config_dir: &Path, todo_file: &Path, env_file: &Path) -> Re
     }
 
     let gitignore = config_dir.join(".gitignore");
-    ensure_gitignore_has_env(&gitignore)?;
+    ensure_gitignore_entries(&gitignore, gitignore_entries)?;
     Ok(())
 }
 
+/// Preflight for commands that write: fails early with the exact path when
+/// the config dir (or the nearest existing parent `setup` would create it in)
+/// or the todo file can't be written.
+pub fn check_writable(config_dir: &Path, todo_file: &Path) -> Result<()> {
+    let hint = "todo_md needs write access there; fix its permissions or point TODOS_CONFIG_DIR at a writable directory";
+
+    let mut dir = config_dir;
+    while !dir.exists() {
+        match dir.parent() {
+            Some(parent) => dir = parent,
+            None => return Ok(()),
+        }
+    }
+    if !dir.is_dir() {
+        bail!("{} is not a directory; {hint}", dir.display());
+    }
+    // Permission bits don't tell the whole story (ACLs, read-only mounts,
+    // root), so probe with a real file.
+    let probe = dir.join(format!(".todo_md_write_check_{}", std::process::id()));
+    match fs::File::create(&probe) {
+        Ok(_) => {
+            let _ = fs::remove_file(&probe);
+        }
+        Err(error) => bail!("cannot write to {}: {error}; {hint}", dir.display()),
+    }
+
+    if let Ok(metadata) = fs::metadata(todo_file)
+        && metadata.permissions().readonly()
+    {
+        bail!("{} is read-only; {hint}", todo_file.display());
+    }
+    Ok(())
+}
+
+/// Git conflict marker lines, including the `|||||||` base marker written
+/// with `merge.conflictStyle = diff3` (or `zdiff3`).
+pub fn is_conflict_marker(line: &str) -> bool {
+    ["<<<<<<<", "|||||||", "=======", ">>>>>>>"]
+        .iter()
+        .any(|marker| line.trim_start().starts_with(marker))
+}
+
+static STRICT: OnceLock<bool> = OnceLock::new();
+
+/// Turns on strict mode (`TODOS_STRICT` or `--strict`) for this process. Only
+/// the first call takes effect.
+pub fn configure_strict(strict: bool) {
+    let _ = STRICT.set(strict);
+}
+
+fn strict_mode() -> bool {
+    STRICT.get().copied().unwrap_or(false)
+}
+
+/// In strict mode, fails when any line would not survive a read and rewrite
+/// unchanged (see [`silent_loss_issues`]).
 pub fn read_todo_file(path: &Path) -> Result<ParsedTodoFile> {
+    let stamp = FileStamp::of(path);
+    let bytes = fs::read(path).with_context(|| format!("failed to read {}", path.display()))?;
     let content =
-        fs::read_to_string(path).with_context(|| format!("failed to read {}", path.display()))?;
-    let todos_by_id = parse_todos_from_content(&content);
+        decode_todo_bytes(bytes).with_context(|| format!("failed to read {}", path.display()))?;
+    if strict_mode() {
+        ensure_lossless(&content).map_err(|error| anyhow!("{}: {error}", path.display()))?;
+    }
+    let (todos_by_id, order) = parse_todos_from_content(&content);
 
     Ok(ParsedTodoFile {
         content,
         todos_by_id,
+        order,
+        stamp,
     })
 }
 
+/// Decodes todo file bytes as UTF-8, dropping a leading byte order mark that
+/// some editors add (it would hide the first line's `- [` prefix).
+fn decode_todo_bytes(bytes: Vec<u8>) -> Result<String> {
+    let mut content = String::from_utf8(bytes).map_err(|error| {
+        let offset = error.utf8_error().valid_up_to();
+        let line = error.as_bytes()[..offset]
+            .iter()
+            .filter(|byte| **byte == b'\n')
+            .count()
+            + 1;
+        anyhow!(
+            "file is not valid UTF-8: invalid byte at offset {offset} (line {line}); re-save it as UTF-8"
+        )
+    })?;
+    if content.starts_with(UTF8_BOM) {
+        content.drain(..UTF8_BOM.len_utf8());
+    }
+    Ok(content)
+}
+
+const UTF8_BOM: char = '\u{feff}';
+
 pub fn parse_todo_content(content: &str) -> ParsedTodoFile {
+    let content = content.strip_prefix(UTF8_BOM).unwrap_or(content);
+    let (todos_by_id, order) = parse_todos_from_content(content);
     ParsedTodoFile {
         content: content.to_string(),
-        todos_by_id: parse_todos_from_content(content),
+        todos_by_id,
+        order,
+        stamp: None,
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum IssueKind {
+    ConflictMarker,
+    MissingId,
+    Unparseable,
+    DuplicateId,
+    IdMismatch,
+    InvalidDue,
+    InvalidRecurrence,
+    DuplicateMetadata,
+    InvalidId,
+    InvalidScheduled,
+    InvalidPriority,
+    InvalidTimestamp,
+    /// A trailing `.` the parser strips; reported only in strict mode.
+    DroppedPeriod,
+    /// Metadata segments out of canonical order; reported only in strict mode.
+    ReorderedSegments,
+}
+
+impl IssueKind {
+    /// Line-local problems that leave the rest of the file meaningful. Sync can
+    /// step over these with `--skip-invalid`; everything else always blocks.
+    pub fn is_skippable(self) -> bool {
+        matches!(self, IssueKind::MissingId | IssueKind::Unparseable)
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct TodoIssue {
+    pub line: usize,
+    pub kind: IssueKind,
+    pub message: String,
+}
+
+impl fmt::Display for TodoIssue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "line {}: {}", self.line, self.message)
+    }
+}
+
+/// Every todo line that reading and writing back would change beyond
+/// formatting: unparseable lines, metadata the parser cannot read or folds
+/// into the name, a stripped trailing `.`, and segments that get reordered.
+pub fn silent_loss_issues(content: &str) -> Vec<TodoIssue> {
+    let segment_re =
+        Regex::new(r"\((due|scheduled|reccurence|recurrence|priority|ext|created|updated|id):")
+            .expect("valid segment regex");
+    let mut issues = Vec::new();
+    for (idx, line) in content
+        .lines()
+        .enumerate()
+        .skip(frontmatter_line_count(content))
+    {
+        if !line.trim_start().starts_with("- [") {
+            continue;
+        }
+        let line_no = idx + 1;
+        let Ok(todo) = Todo::try_from_str(line) else {
+            issues.push(TodoIssue {
+                line: line_no,
+                kind: IssueKind::Unparseable,
+                message: "todo line could not be parsed".to_string(),
+            });
+            continue;
+        };
+        if let Some((kind, message)) = dropped_metadata(line, &todo) {
+            issues.push(TodoIssue {
+                line: line_no,
+                kind,
+                message,
+            });
+            continue;
+        }
+        if line.trim_end().ends_with('.') {
+            issues.push(TodoIssue {
+                line: line_no,
+                kind: IssueKind::DroppedPeriod,
+                message: "the trailing `.` is dropped when the line is rewritten".to_string(),
+            });
+        }
+        let ranks = segment_re
+            .captures_iter(line)
+            .filter_map(|captures| {
+                let key = match &captures[1] {
+                    "recurrence" => "reccurence",
+                    key => key,
+                };
+                SEGMENT_ORDER.iter().position(|known| *known == key)
+            })
+            .collect::<Vec<_>>();
+        if ranks.windows(2).any(|pair| pair[0] > pair[1]) {
+            issues.push(TodoIssue {
+                line: line_no,
+                kind: IssueKind::ReorderedSegments,
+                message: format!(
+                    "metadata is rewritten in the order {}",
+                    SEGMENT_ORDER.join(", ")
+                ),
+            });
+        }
+    }
+    issues
+}
+
+/// Order `Todo::to_line` writes metadata segments in.
+const SEGMENT_ORDER: [&str; 8] = [
+    "due",
+    "scheduled",
+    "reccurence",
+    "priority",
+    "ext",
+    "created",
+    "updated",
+    "id",
+];
+
+/// Fails with every [`silent_loss_issues`] line, for strict mode.
+pub fn ensure_lossless(content: &str) -> Result<()> {
+    let issues = silent_loss_issues(content);
+    if issues.is_empty() {
+        return Ok(());
     }
+    let lines = issues
+        .iter()
+        .map(ToString::to_string)
+        .collect::<Vec<_>>()
+        .join("\n");
+    bail!(
+        "strict mode: these lines would not survive a rewrite unchanged; fix them, or accept the rewrite with `todo_md fmt` outside strict mode\n{lines}"
+    );
 }
 
 pub fn validate_todo_content(content: &str) -> Vec<String> {
+    validate_todo_issues(content)
+        .iter()
+        .map(ToString::to_string)
+        .collect()
+}
+
+/// Indented, non-blank, non-todo lines directly under a todo: notes that
+/// belong to it. Returns each todo's line index with its note line indexes.
+pub(crate) fn continuation_blocks(content: &str) -> Vec<(usize, Vec<usize>)> {
+    let mut blocks: Vec<(usize, Vec<usize>)> = Vec::new();
+    let mut open = false;
+    for (idx, line) in content
+        .lines()
+        .enumerate()
+        .skip(frontmatter_line_count(content))
+    {
+        let trimmed = line.trim_start();
+        if trimmed.starts_with("- [") {
+            blocks.push((idx, Vec::new()));
+            open = true;
+        } else if open && !trimmed.is_empty() && trimmed.len() < line.len() {
+            if let Some((_, block)) = blocks.last_mut() {
+                block.push(idx);
+            }
+        } else {
+            open = false;
+        }
+    }
+    blocks.retain(|(_, block)| !block.is_empty());
+    blocks
+}
+
+/// The indentation `fmt` gives each note line: the shallowest line of a
+/// block sits at [`NOTE_INDENT`] spaces and deeper lines keep their nesting.
+fn note_indents(content: &str) -> HashMap<usize, String> {
+    let lines = content.lines().collect::<Vec<_>>();
+    let mut indents = HashMap::new();
+    for (_, block) in continuation_blocks(content) {
+        let widths = block
+            .iter()
+            .map(|idx| leading_whitespace(&expand_leading_tabs(lines[*idx])).len())
+            .collect::<Vec<_>>();
+        let shallowest = widths.iter().copied().min().unwrap_or_default();
+        for (idx, width) in block.into_iter().zip(widths) {
+            indents.insert(idx, " ".repeat(NOTE_INDENT + width - shallowest));
+        }
+    }
+    indents
+}
+
+const NOTE_INDENT: usize = 4;
+
+pub(crate) fn leading_whitespace(line: &str) -> &str {
+    &line[..line.len() - line.trim_start().len()]
+}
+
+/// Replaces tabs in the indentation with spaces up to the next 4-column stop.
+fn expand_leading_tabs(line: &str) -> String {
+    let indent = leading_whitespace(line);
+    let mut expanded = String::new();
+    for ch in indent.chars() {
+        if ch == '\t' {
+            let width = TAB_WIDTH - expanded.chars().count() % TAB_WIDTH;
+            expanded.push_str(&" ".repeat(width));
+        } else {
+            expanded.push(ch);
+        }
+    }
+    expanded.push_str(&line[indent.len()..]);
+    expanded
+}
+
+const TAB_WIDTH: usize = 4;
+
+pub fn validate_todo_issues(content: &str) -> Vec<TodoIssue> {
     let mut issues = Vec::new();
     let mut seen_ids: HashMap<Uuid, usize> = HashMap::new();
-    let id_re = Regex::new(r"\(id:\s*([0-9a-fA-F-]{36})\)").expect("valid id regex");
+    let id_re = Regex::new(r"\(id:\s*([^)]*)\)").expect("valid id regex");
+    let mut push = |line: usize, kind: IssueKind, message: String| {
+        issues.push(TodoIssue {
+            line,
+            kind,
+            message,
+        });
+    };
 
-    for (idx, line) in content.lines().enumerate() {
+    let frontmatter_lines = frontmatter_line_count(content);
+    for (idx, line) in content.lines().enumerate().skip(frontmatter_lines) {
         let line_no = idx + 1;
         let trimmed = line.trim_start();
 
-        if trimmed.starts_with("<<<<<<<")
-            || trimmed.starts_with("=======")
-            || trimmed.starts_with(">>>>>>>")
-        {
-            issues.push(format!("line {line_no}: unresolved git conflict marker"));
+        if is_conflict_marker(trimmed) {
+            push(
+                line_no,
+                IssueKind::ConflictMarker,
+                "unresolved git conflict marker".to_string(),
+            );
             continue;
         }
 
@@ -74,47 +425,193 @@ pub fn validate_todo_content(content: &str) -> Vec<String> {
         }
 
         if !line.contains("(id:") {
-            issues.push(format!("line {line_no}: todo line is missing required id"));
+            push(
+                line_no,
+                IssueKind::MissingId,
+                "todo line is missing required id".to_string(),
+            );
             continue;
         }
 
         let todo = match Todo::try_from_str(line) {
             Ok(todo) => todo,
             Err(_) => {
-                issues.push(format!("line {line_no}: todo line could not be parsed"));
+                push(
+                    line_no,
+                    IssueKind::Unparseable,
+                    "todo line could not be parsed".to_string(),
+                );
                 continue;
             }
         };
 
-        if let Some(captures) = id_re.captures(line) {
-            if let Some(raw_id) = captures.get(1).map(|m| m.as_str()) {
-                if let Ok(id) = Uuid::parse_str(raw_id) {
-                    if let Some(previous_line) = seen_ids.insert(id, line_no) {
-                        issues.push(format!(
-                            "line {line_no}: duplicate id {id} (first seen on line {previous_line})"
-                        ));
-                    }
-                    if id != todo.id() {
-                        issues.push(format!(
-                            "line {line_no}: parsed id mismatch, this line may be malformed"
-                        ));
-                    }
-                }
+        if let Some((kind, message)) = dropped_metadata(line, &todo) {
+            push(line_no, kind, message);
+        }
+
+        if let Some(captures) = id_re.captures(line)
+            && let Some(raw_id) = captures.get(1).map(|m| m.as_str())
+            && let Ok(id) = Uuid::parse_str(unwrap_id(raw_id))
+        {
+            if let Some(previous_line) = seen_ids.insert(id, line_no) {
+                push(
+                    line_no,
+                    IssueKind::DuplicateId,
+                    format!("duplicate id {id} (first seen on line {previous_line})"),
+                );
+            }
+            if id != todo.id() {
+                push(
+                    line_no,
+                    IssueKind::IdMismatch,
+                    "parsed id mismatch: `(id: <uuid>)` needs exactly one space after `id:` and must follow the name"
+                        .to_string(),
+                );
             }
         }
     }
 
+    if strict_mode() {
+        issues.extend(silent_loss_issues(content).into_iter().filter(|issue| {
+            matches!(
+                issue.kind,
+                IssueKind::DroppedPeriod | IssueKind::ReorderedSegments
+            )
+        }));
+        issues.sort_by_key(|issue| issue.line);
+    }
     issues
 }
 
+/// Reports metadata that re-serializing the todo would lose or mangle: a
+/// segment repeated on one line (the parser folds all but the last into the
+/// name), an id that is not a UUID, or a due, scheduled, recurrence or
+/// priority segment that was not understood.
+pub(crate) fn dropped_metadata(line: &str, todo: &Todo) -> Option<(IssueKind, String)> {
+    let segment_re =
+        Regex::new(r"\((due|scheduled|reccurence|recurrence|priority|ext|created|updated|id):")
+            .expect("valid segment regex");
+    let mut counts: HashMap<&str, usize> = HashMap::new();
+    for captures in segment_re.captures_iter(line) {
+        let key = match captures.get(1).map(|m| m.as_str()) {
+            Some("recurrence") => "reccurence",
+            Some(key) => key,
+            None => continue,
+        };
+        *counts.entry(key).or_default() += 1;
+    }
+    for key in SEGMENT_ORDER {
+        if counts.get(key).copied().unwrap_or(0) > 1 {
+            return Some((
+                IssueKind::DuplicateMetadata,
+                format!("`({key}: ...)` appears more than once; remove the stale copy"),
+            ));
+        }
+    }
+
+    let id_re = Regex::new(r"\(id:\s*([^)]*)\)").expect("valid id regex");
+    if let Some(captures) = id_re.captures(line)
+        && Uuid::parse_str(unwrap_id(&captures[1])).is_err()
+    {
+        return Some((
+            IssueKind::InvalidId,
+            format!(
+                "id `{}` is not a valid UUID; it would be replaced on every read",
+                captures[1].trim()
+            ),
+        ));
+    }
+
+    let due_re = Regex::new(r"\(due:\s*([^)]*)\)").expect("valid due regex");
+    if todo.due_date().is_none()
+        && let Some(captures) = due_re.captures(line)
+    {
+        return Some((
+            IssueKind::InvalidDue,
+            format!("due date `{}` could not be parsed", captures[1].trim()),
+        ));
+    }
+
+    let scheduled_re = Regex::new(r"\(scheduled:\s*([^)]*)\)").expect("valid scheduled regex");
+    if todo.scheduled().is_none()
+        && let Some(captures) = scheduled_re.captures(line)
+    {
+        return Some((
+            IssueKind::InvalidScheduled,
+            format!(
+                "scheduled date `{}` could not be parsed",
+                captures[1].trim()
+            ),
+        ));
+    }
+
+    let recurrence_re =
+        Regex::new(r"\((?:reccurence|recurrence):\s*([^)]*)\)").expect("valid recurrence regex");
+    if todo.recurence().is_none()
+        && let Some(captures) = recurrence_re.captures(line)
+    {
+        return Some((
+            IssueKind::InvalidRecurrence,
+            format!("recurrence `{}` could not be parsed", captures[1].trim()),
+        ));
+    }
+
+    let priority_re = Regex::new(r"\(priority:\s*([^)]*)\)").expect("valid priority regex");
+    if todo.priority().is_none()
+        && let Some(captures) = priority_re.captures(line)
+    {
+        return Some((
+            IssueKind::InvalidPriority,
+            format!(
+                "priority `{}` is not high, medium or low (or p1-p3)",
+                captures[1].trim()
+            ),
+        ));
+    }
+
+    let stamp_re = Regex::new(r"\((created|updated):\s*([^)]*)\)").expect("valid timestamp regex");
+    for captures in stamp_re.captures_iter(line) {
+        let recorded = match &captures[1] {
+            "created" => todo.recorded_created_at(),
+            _ => todo.recorded_updated_at(),
+        };
+        if recorded.is_none() {
+            return Some((
+                IssueKind::InvalidTimestamp,
+                format!(
+                    "{} time `{}` is not an RFC 3339 timestamp",
+                    &captures[1],
+                    captures[2].trim()
+                ),
+            ));
+        }
+    }
+
+    None
+}
+
 pub fn format_todo_content(content: &str) -> (String, Vec<String>) {
     let mut issues = Vec::new();
     let mut out = Vec::new();
 
+    let continuations = note_indents(content);
+    let frontmatter_lines = frontmatter_line_count(content);
     for (idx, line) in content.lines().enumerate() {
         let line_no = idx + 1;
         let trimmed = line.trim_start();
-        if !trimmed.starts_with("- [") {
+        if let Some(indent) = continuations.get(&idx) {
+            out.push(format!("{indent}{}", line.trim()));
+            continue;
+        }
+        if idx < frontmatter_lines || !trimmed.starts_with("- [") {
+            out.push(line.trim_end().to_string());
+            continue;
+        }
+
+        if let Ok(todo) = Todo::try_from_str(line)
+            && let Some((_, message)) = dropped_metadata(line, &todo)
+        {
+            issues.push(format!("line {line_no}: {message}"));
             out.push(line.trim_end().to_string());
             continue;
         }
@@ -150,20 +647,111 @@ pub fn format_todo_content(content: &str) -> (String, Vec<String>) {
     (formatted, issues)
 }
 
+/// How `fmt` reorders todos within a section. Both sorts are stable, so
+/// ties keep their file order.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SectionSort {
+    /// `--sort-by-due`: by due date, undated last.
+    Due,
+    /// `--sort`: open before done, then by due date with undated last, then
+    /// by name.
+    DoneLast,
+}
+
+/// Reorders todos within each `#` to `######` section. Todos keep their notes
+/// and never cross a header; headers, blank lines and other text stay put
+/// while the todo slots between them are refilled in sorted order.
+pub fn sort_sections(content: &str, order: SectionSort) -> String {
+    let lines = content.lines().collect::<Vec<_>>();
+    let notes = continuation_blocks(content)
+        .into_iter()
+        .collect::<HashMap<_, _>>();
+    let note_lines = notes.values().flatten().copied().collect::<HashSet<_>>();
+    let frontmatter_lines = frontmatter_line_count(content);
+
+    // Line indexes in the current section; `None` marks a todo slot.
+    let mut section: Vec<Option<usize>> = Vec::new();
+    let mut todos = Vec::new();
+    let mut out = Vec::with_capacity(lines.len());
+    let mut flush = |section: &mut Vec<Option<usize>>, todos: &mut Vec<usize>| {
+        todos.sort_by_cached_key(|idx| {
+            let todo = Todo::try_from_str(lines[*idx]).ok();
+            let due = todo.as_ref().and_then(Todo::due_date);
+            match order {
+                SectionSort::Due => (false, due.is_none(), due, String::new()),
+                SectionSort::DoneLast => (
+                    todo.as_ref().is_some_and(Todo::done),
+                    due.is_none(),
+                    due,
+                    todo.map(|todo| todo.name()).unwrap_or_default(),
+                ),
+            }
+        });
+        let mut sorted = todos.drain(..);
+        for piece in section.drain(..) {
+            match piece {
+                Some(idx) => out.push(lines[idx]),
+                None => {
+                    let Some(idx) = sorted.next() else { continue };
+                    out.push(lines[idx]);
+                    for note in notes.get(&idx).into_iter().flatten() {
+                        out.push(lines[*note]);
+                    }
+                }
+            }
+        }
+    };
+
+    for (idx, line) in lines.iter().enumerate() {
+        if note_lines.contains(&idx) {
+            continue;
+        }
+        if idx >= frontmatter_lines && line.trim_start().starts_with("- [") {
+            section.push(None);
+            todos.push(idx);
+            continue;
+        }
+        if idx >= frontmatter_lines && is_section_header(line) {
+            flush(&mut section, &mut todos);
+        }
+        section.push(Some(idx));
+    }
+    flush(&mut section, &mut todos);
+
+    let mut sorted = out.join("\n");
+    if content.ends_with('\n') {
+        sorted.push('\n');
+    }
+    sorted
+}
+
+/// An ATX heading: one to six `#` followed by a space or the end of the line.
+fn is_section_header(line: &str) -> bool {
+    let rest = line.trim_start_matches('#');
+    (1..=6).contains(&(line.len() - rest.len())) && (rest.is_empty() || rest.starts_with(' '))
+}
+
 pub fn hydrate_todo_ids(content: &str) -> (String, usize, Vec<String>) {
     let mut changed = 0_usize;
     let mut issues = Vec::new();
     let mut out = Vec::new();
 
+    let frontmatter_lines = frontmatter_line_count(content);
     for (idx, line) in content.lines().enumerate() {
         let line_no = idx + 1;
         let trimmed = line.trim_start();
-        if !trimmed.starts_with("- [") || line.contains("(id:") {
+        if idx < frontmatter_lines || !trimmed.starts_with("- [") || line.contains("(id:") {
             out.push(line.to_string());
             continue;
         }
 
         match Todo::try_from_str(line) {
+            Ok(todo) if dropped_metadata(line, &todo).is_some() => {
+                out.push(line.to_string());
+                issues.push(format!(
+                    "line {line_no}: could not auto-generate id without losing metadata"
+                ));
+            }
             Ok(todo) => {
                 out.push(todo.to_line());
                 changed += 1;
@@ -183,11 +771,272 @@ pub fn hydrate_todo_ids(content: &str) -> (String, usize, Vec<String>) {
     (hydrated, changed, issues)
 }
 
+/// Gives every todo line with an id a fresh v4 UUID, leaving the rest of each
+/// line untouched. Returns the rewritten content and the old -> new mapping in
+/// file order. This breaks semantic diffing against earlier commits.
+pub fn regenerate_todo_ids(content: &str) -> (String, Vec<(Uuid, Uuid)>) {
+    let id_re = Regex::new(r"\(id:\s*([0-9a-fA-F-]{36})\)").expect("valid id regex");
+    let mut mapping = Vec::new();
+    let mut out = Vec::new();
+
+    let frontmatter_lines = frontmatter_line_count(content);
+    for (idx, line) in content.lines().enumerate() {
+        if idx < frontmatter_lines || !line.trim_start().starts_with("- [") {
+            out.push(line.to_string());
+            continue;
+        }
+
+        let Some(old_id) = id_re
+            .captures(line)
+            .and_then(|captures| Uuid::parse_str(&captures[1]).ok())
+        else {
+            out.push(line.to_string());
+            continue;
+        };
+
+        let new_id = Uuid::new_v4();
+        out.push(
+            id_re
+                .replace(line, format!("(id: {new_id})").as_str())
+                .into_owned(),
+        );
+        mapping.push((old_id, new_id));
+    }
+
+    let mut regenerated = out.join("\n");
+    if content.ends_with('\n') {
+        regenerated.push('\n');
+    }
+
+    (regenerated, mapping)
+}
+
+/// A todo file rewritten to the current line format by `todo_md upgrade`.
+#[derive(Debug, Clone)]
+pub struct Upgrade {
+    pub content: String,
+    /// The `format` the file declared, 1 when it had none.
+    pub from_version: u32,
+    /// Lines (in the original file) whose text changed.
+    pub changed_lines: Vec<usize>,
+}
+
+/// Rewrites every todo line in canonical form and stamps the frontmatter with
+/// [`LINE_FORMAT_VERSION`]. Fails, changing nothing, when a line could not be
+/// rewritten without losing data.
+pub fn upgrade_content(content: &str) -> Result<Upgrade> {
+    let from_version = parse_frontmatter(content)?
+        .and_then(|frontmatter| frontmatter.format)
+        .unwrap_or(1);
+    let (formatted, issues) = format_todo_content(content);
+    if !issues.is_empty() {
+        bail!(
+            "todo.md has lines that cannot be upgraded; fix them first\n{}",
+            issues.join("\n")
+        );
+    }
+    let changed_lines = content
+        .lines()
+        .zip(formatted.lines())
+        .enumerate()
+        .filter(|(_, (before, after))| before != after)
+        .map(|(idx, _)| idx + 1)
+        .collect();
+    Ok(Upgrade {
+        content: set_frontmatter_value(&formatted, "format", &LINE_FORMAT_VERSION.to_string()),
+        from_version,
+        changed_lines,
+    })
+}
+
+/// Rewrites a trailing `(id: ...)` segment with stray spacing or a wrapper
+/// into `(id: <uuid>)`: `(id:<uuid>)`, which the parser cannot read and would
+/// replace on every read, and `(id: `<uuid>`)` or `(id: <uuid> )`, which it
+/// tolerates. Mismatches with anything after the id segment are left for a
+/// human. Returns the content and the repaired lines.
+pub fn repair_id_segments(content: &str) -> (String, Vec<usize>) {
+    let segment_re = Regex::new(r#"\(id:\s*[`"']?\s*([0-9a-fA-F-]{36})\s*[`"']?\s*\)(\.?)\s*$"#)
+        .expect("valid id regex");
+    let mut repaired = Vec::new();
+    let mut lines = Vec::new();
+
+    let frontmatter_lines = frontmatter_line_count(content);
+    for (idx, line) in content.lines().enumerate() {
+        if idx < frontmatter_lines || !line.trim_start().starts_with("- [") {
+            lines.push(line.to_string());
+            continue;
+        }
+        let Some(captures) = segment_re.captures(line) else {
+            lines.push(line.to_string());
+            continue;
+        };
+        let Ok(id) = Uuid::parse_str(&captures[1]) else {
+            lines.push(line.to_string());
+            continue;
+        };
+        let segment = captures.get(0).expect("whole match");
+        let fixed = format!(
+            "{}(id: {}){}",
+            &line[..segment.start()],
+            &captures[1],
+            &captures[2]
+        );
+        let parses_to_id = Todo::try_from_str(&fixed).is_ok_and(|todo| todo.id() == id);
+        let canonical = line[segment.start()..].starts_with(&format!("(id: {})", &captures[1]));
+        if !canonical && parses_to_id {
+            repaired.push(idx + 1);
+            lines.push(fixed);
+        } else {
+            lines.push(line.to_string());
+        }
+    }
+
+    let mut next = lines.join("\n");
+    if content.ends_with('\n') {
+        next.push('\n');
+    }
+    (next, repaired)
+}
+
+/// A duplicated id given to a later line by [`reassign_duplicate_ids`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Reassigned {
+    pub line: usize,
+    pub old: Uuid,
+    pub new: Uuid,
+}
+
+/// Gives every todo line that repeats an earlier line's id a fresh one, e.g.
+/// after a copy-paste. The first line keeps its id, so history still follows
+/// it. Returns the content and each reassignment.
+pub fn reassign_duplicate_ids(content: &str) -> (String, Vec<Reassigned>) {
+    let id_re = Regex::new(r"\(id:\s*([^)]*)\)").expect("valid id regex");
+    let mut seen = HashSet::new();
+    let mut reassigned = Vec::new();
+    let mut lines = Vec::new();
+
+    let frontmatter_lines = frontmatter_line_count(content);
+    for (idx, line) in content.lines().enumerate() {
+        let todo = (idx >= frontmatter_lines && line.trim_start().starts_with("- ["))
+            .then(|| Todo::try_from_str(line).ok())
+            .flatten();
+        let Some(todo) = todo else {
+            lines.push(line.to_string());
+            continue;
+        };
+        if seen.insert(todo.id()) {
+            lines.push(line.to_string());
+            continue;
+        }
+        let value = id_re
+            .captures_iter(line)
+            .filter_map(|captures| captures.get(1))
+            .filter(|value| Uuid::parse_str(unwrap_id(value.as_str())).ok() == Some(todo.id()))
+            .last();
+        let Some(value) = value else {
+            lines.push(line.to_string());
+            continue;
+        };
+        let new = Uuid::new_v4();
+        seen.insert(new);
+        lines.push(format!(
+            "{}{new}{}",
+            &line[..value.start()],
+            &line[value.end()..]
+        ));
+        reassigned.push(Reassigned {
+            line: idx + 1,
+            old: todo.id(),
+            new,
+        });
+    }
+
+    let mut next = lines.join("\n");
+    if content.ends_with('\n') {
+        next.push('\n');
+    }
+    (next, reassigned)
+}
+
+/// Finds the single id starting with `prefix` (case-insensitive). Errors list
+/// the candidates when the prefix is ambiguous.
+pub fn resolve_id_prefix(ids: impl IntoIterator<Item = Uuid>, prefix: &str) -> Result<Uuid> {
+    let prefix = prefix.trim().to_ascii_lowercase();
+    if prefix.is_empty() {
+        bail!("id prefix must not be empty");
+    }
+
+    let mut matches = ids
+        .into_iter()
+        .filter(|id| id.to_string().starts_with(&prefix))
+        .collect::<Vec<_>>();
+    matches.sort();
+    matches.dedup();
+
+    match matches.as_slice() {
+        [id] => Ok(*id),
+        [] => bail!("no todo id matches `{prefix}`"),
+        candidates => bail!(
+            "id prefix `{prefix}` is ambiguous; candidates:\n{}",
+            candidates
+                .iter()
+                .map(ToString::to_string)
+                .collect::<Vec<_>>()
+                .join("\n")
+        ),
+    }
+}
+
+/// Fails when the file at `path` is no longer the one `read` came from, e.g.
+/// an editor saved it while a command was working on its content.
+pub fn ensure_unchanged(path: &Path, read: &ParsedTodoFile) -> Result<()> {
+    if let Some(stamp) = read.stamp
+        && FileStamp::of(path) != Some(stamp)
+    {
+        bail!(
+            "{} changed on disk since it was read; nothing was written, re-run the command",
+            path.display()
+        );
+    }
+    Ok(())
+}
+
+/// [`write_todo_file_atomic`] for content derived from `read`, refusing to
+/// overwrite edits made to the file in the meantime.
+pub fn write_todo_file_checked(path: &Path, read: &ParsedTodoFile, content: &str) -> Result<()> {
+    ensure_unchanged(path, read)?;
+    write_todo_file_atomic(path, content)
+}
+
+/// Writes `side_content` to `side_file` (trash, archive), then `next` to
+/// `todo_file` as [`write_todo_file_checked`] would. The side file goes
+/// first so a failure in between leaves a copy, never a loss.
+pub fn move_then_rewrite(
+    side_file: &Path,
+    side_content: &str,
+    todo_file: &Path,
+    read: &ParsedTodoFile,
+    next: &str,
+) -> Result<()> {
+    ensure_unchanged(todo_file, read)?;
+    write_todo_file_atomic(side_file, side_content)?;
+    write_todo_file_checked(todo_file, read, next)
+}
+
 pub fn write_todo_file_atomic(path: &Path, content: &str) -> Result<()> {
     let parent = path
         .parent()
         .with_context(|| format!("{} has no parent directory", path.display()))?;
-    let temp_path = parent.join("todo.md.tmp");
+    // Named after the target so writes to todo, trash and archive files in
+    // one directory, or from two processes, never share a temp file.
+    let file_name = path
+        .file_name()
+        .with_context(|| format!("{} has no file name", path.display()))?;
+    let temp_path = parent.join(format!(
+        ".{}.{}.tmp",
+        file_name.to_string_lossy(),
+        std::process::id()
+    ));
 
     {
         let mut file = fs::File::create(&temp_path)
@@ -204,34 +1053,128 @@ pub fn write_todo_file_atomic(path: &Path, content: &str) -> Result<()> {
     Ok(())
 }
 
-fn parse_todos_from_content(content: &str) -> HashMap<Uuid, Todo> {
+/// Copies `content` of `todo_file` into `backup_dir` as
+/// `<stem>.<timestamp>.md`, then prunes that file's copies to the newest
+/// `keep`. Skipped when `keep` is 0 or the newest copy already matches.
+/// Returns the copy written, if any.
+pub fn backup_todo_file(
+    backup_dir: &Path,
+    todo_file: &Path,
+    content: &str,
+    keep: usize,
+    now: DateTime<Utc>,
+) -> Result<Option<PathBuf>> {
+    if keep == 0 {
+        return Ok(None);
+    }
+    let stem = todo_file
+        .file_stem()
+        .and_then(|stem| stem.to_str())
+        .with_context(|| format!("{} has no file name", todo_file.display()))?;
+    fs::create_dir_all(backup_dir)
+        .with_context(|| format!("failed to create {}", backup_dir.display()))?;
+
+    let existing = backups_of(backup_dir, stem)?;
+    let mut written = None;
+    if existing
+        .last()
+        .is_none_or(|newest| fs::read_to_string(newest).ok().as_deref() != Some(content))
+    {
+        let path = backup_dir.join(format!("{stem}.{}.md", now.format(BACKUP_TIMESTAMP)));
+        write_todo_file_atomic(&path, content)?;
+        written = Some(path);
+    }
+
+    let backups = backups_of(backup_dir, stem)?;
+    for old in &backups[..backups.len().saturating_sub(keep)] {
+        fs::remove_file(old).with_context(|| format!("failed to remove {}", old.display()))?;
+    }
+    Ok(written)
+}
+
+const BACKUP_TIMESTAMP: &str = "%Y%m%dT%H%M%SZ";
+
+/// `<stem>.<timestamp>.md` copies in `backup_dir`, oldest first. Copies of
+/// other todo files are left out even when their stem starts the same way.
+fn backups_of(backup_dir: &Path, stem: &str) -> Result<Vec<PathBuf>> {
+    let entries = fs::read_dir(backup_dir)
+        .with_context(|| format!("failed to read {}", backup_dir.display()))?;
+    let mut backups = Vec::new();
+    for entry in entries {
+        let path = entry
+            .with_context(|| format!("failed to read {}", backup_dir.display()))?
+            .path();
+        let is_backup = path
+            .file_name()
+            .and_then(|name| name.to_str())
+            .and_then(|name| name.strip_prefix(stem)?.strip_prefix('.'))
+            .and_then(|rest| rest.strip_suffix(".md"))
+            .is_some_and(|stamp| NaiveDateTime::parse_from_str(stamp, BACKUP_TIMESTAMP).is_ok());
+        if is_backup {
+            backups.push(path);
+        }
+    }
+    backups.sort();
+    Ok(backups)
+}
+
+/// Todos by id, and their ids in file order.
+fn parse_todos_from_content(content: &str) -> (HashMap<Uuid, Todo>, Vec<Uuid>) {
+    let lines = content.lines().collect::<Vec<_>>();
+    let notes = continuation_blocks(content)
+        .into_iter()
+        .collect::<HashMap<_, _>>();
     let mut todos = HashMap::new();
-    for line in content.lines() {
+    let mut order = Vec::new();
+    for (idx, line) in lines
+        .iter()
+        .enumerate()
+        .skip(frontmatter_line_count(content))
+    {
         if !line.trim_start().starts_with("- [") {
             continue;
         }
 
-        if let Ok(todo) = Todo::try_from_str(line) {
+        if let Ok(mut todo) = Todo::try_from_str(line) {
+            if let Some(block) = notes.get(&idx) {
+                todo.set_notes(
+                    block
+                        .iter()
+                        .map(|note| lines[*note].trim().to_string())
+                        .collect(),
+                );
+            }
+            if !todos.contains_key(&todo.id()) {
+                order.push(todo.id());
+            }
             todos.insert(todo.id(), todo);
         }
     }
 
-    todos
+    (todos, order)
 }
 
-fn ensure_gitignore_has_env(gitignore_path: &Path) -> Result<()> {
-    let mut content = if gitignore_path.exists() {
+fn ensure_gitignore_entries(gitignore_path: &Path, entries: &[String]) -> Result<()> {
+    let original = if gitignore_path.exists() {
         fs::read_to_string(gitignore_path)
             .with_context(|| format!("failed to read {}", gitignore_path.display()))?
     } else {
         String::new()
     };
 
-    if !content.lines().any(|line| line.trim() == ".env") {
+    let mut content = original.clone();
+    for entry in entries {
+        if content.lines().any(|line| line.trim() == entry.trim()) {
+            continue;
+        }
         if !content.is_empty() && !content.ends_with('\n') {
             content.push('\n');
         }
-        content.push_str(".env\n");
+        content.push_str(entry.trim());
+        content.push('\n');
+    }
+
+    if content != original {
         write_todo_file_atomic(gitignore_path, &content)?;
     }
 
@@ -241,6 +1184,80 @@ fn ensure_gitignore_has_env(gitignore_path: &Path) -> Result<()> {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::date_parser::pin_home_zone;
+
+    #[test]
+    fn check_writable_names_a_config_dir_that_is_a_file() {
+        let temp_dir =
+            std::env::temp_dir().join(format!("todo_md_writable_{}", std::process::id()));
+        let _ = fs::create_dir_all(&temp_dir);
+        let not_a_dir = temp_dir.join("todos");
+        fs::write(&not_a_dir, "").expect("create file");
+
+        let error = check_writable(&not_a_dir.join("nested"), &not_a_dir.join("nested/todo.md"))
+            .expect_err("a file can't hold the config dir");
+        assert!(error.to_string().contains("is not a directory"));
+        assert!(error.to_string().contains(&not_a_dir.display().to_string()));
+        check_writable(&temp_dir.join("fresh"), &temp_dir.join("fresh/todo.md"))
+            .expect("missing dir under a writable parent is fine");
+
+        let _ = fs::remove_dir_all(temp_dir);
+    }
+
+    #[test]
+    fn strips_bom_so_first_todo_still_parses() {
+        let temp_dir = std::env::temp_dir().join(format!("todo_md_bom_{}", std::process::id()));
+        let _ = fs::create_dir_all(&temp_dir);
+        let path = temp_dir.join("todo.md");
+        fs::write(
+            &path,
+            "\u{feff}- [_] First (id: 123e4567-e89b-12d3-a456-426614174000)\n",
+        )
+        .expect("write");
+
+        let parsed = read_todo_file(&path).expect("read");
+        assert!(parsed.content.starts_with("- [_] First"));
+        assert_eq!(parsed.todos_by_id.len(), 1);
+        assert!(validate_todo_content(&parsed.content).is_empty());
+    }
+
+    #[test]
+    fn refuses_to_overwrite_a_file_changed_since_it_was_read() {
+        let temp_dir =
+            std::env::temp_dir().join(format!("todo_md_lost_update_{}", std::process::id()));
+        let _ = fs::create_dir_all(&temp_dir);
+        let path = temp_dir.join("todo.md");
+        fs::write(
+            &path,
+            "- [_] First (id: 123e4567-e89b-12d3-a456-426614174000)\n",
+        )
+        .expect("write");
+
+        let parsed = read_todo_file(&path).expect("read");
+        // An editor saves while the command is still working on `parsed`.
+        let edited = "- [_] First (id: 123e4567-e89b-12d3-a456-426614174000)\n\
+                      - [_] Typed in the editor (id: 123e4567-e89b-12d3-a456-426614174001)\n";
+        fs::write(&path, edited).expect("edit");
+
+        let error =
+            write_todo_file_checked(&path, &parsed, "- [x] First\n").expect_err("stale read");
+        assert!(error
+            .to_string()
+            .contains("changed on disk since it was read"));
+        assert_eq!(fs::read_to_string(&path).expect("read"), edited);
+
+        let reread = read_todo_file(&path).expect("read");
+        write_todo_file_checked(&path, &reread, "- [x] First\n").expect("fresh read");
+        assert_eq!(fs::read_to_string(&path).expect("read"), "- [x] First\n");
+
+        let _ = fs::remove_dir_all(temp_dir);
+    }
+
+    #[test]
+    fn reports_offset_of_invalid_utf8() {
+        let error = decode_todo_bytes(b"# Todos\n- [_] caf\xe9\n".to_vec()).expect_err("invalid");
+        assert!(error.to_string().contains("offset 17 (line 2)"));
+    }
 
     #[test]
     fn validates_missing_id_and_conflicts() {
@@ -251,6 +1268,189 @@ mod tests {
         assert!(issues.iter().any(|m| m.contains("missing required id")));
     }
 
+    #[test]
+    fn detects_diff3_base_marker() {
+        let input = "||||||| merged common ancestors\n";
+        let issues = validate_todo_issues(input);
+        assert_eq!(issues.len(), 1);
+        assert_eq!(issues[0].kind, IssueKind::ConflictMarker);
+    }
+
+    #[test]
+    fn classifies_issue_kinds_for_skipping() {
+        let input = "=======\n- [_] Task without id\n";
+        let issues = validate_todo_issues(input);
+        assert_eq!(issues.len(), 2);
+        assert_eq!(issues[0].kind, IssueKind::ConflictMarker);
+        assert!(!issues[0].kind.is_skippable());
+        assert_eq!(issues[1].kind, IssueKind::MissingId);
+        assert!(issues[1].kind.is_skippable());
+        assert_eq!(
+            issues[1].to_string(),
+            "line 2: todo line is missing required id"
+        );
+    }
+
+    #[test]
+    fn resolves_unique_id_prefixes() {
+        let ids = [
+            Uuid::parse_str("123e4567-e89b-12d3-a456-426614174000").expect("id"),
+            Uuid::parse_str("123f4567-e89b-12d3-a456-426614174001").expect("id"),
+        ];
+
+        assert_eq!(resolve_id_prefix(ids, "123E").expect("unique"), ids[0]);
+        let ambiguous = resolve_id_prefix(ids, "123").expect_err("ambiguous");
+        assert!(ambiguous.to_string().contains("ambiguous"));
+        assert!(resolve_id_prefix(ids, "999").is_err());
+    }
+
+    #[test]
+    fn reports_unreadable_timestamps() {
+        let input = "- [_] X (created: yesterday) (id: 123e4567-e89b-12d3-a456-426614174000)\n";
+
+        let issues = validate_todo_issues(input);
+        assert_eq!(issues.len(), 1);
+        assert_eq!(issues[0].kind, IssueKind::InvalidTimestamp);
+        assert!(issues[0].message.contains("`yesterday`"));
+    }
+
+    #[test]
+    fn reports_unparseable_due_as_error_and_keeps_the_line() {
+        let input = "- [_] X (due: blargh) (id: 123e4567-e89b-12d3-a456-426614174000)\n";
+
+        let issues = validate_todo_issues(input);
+        assert_eq!(issues.len(), 1);
+        assert_eq!(issues[0].kind, IssueKind::InvalidDue);
+        assert!(issues[0].message.contains("`blargh`"));
+
+        let (formatted, format_issues) = format_todo_content(input);
+        assert_eq!(formatted, input);
+        assert_eq!(format_issues.len(), 1);
+
+        let (hydrated, changed, hydrate_issues) = hydrate_todo_ids("- [ ] Y (due: blargh)\n");
+        assert_eq!(changed, 0);
+        assert_eq!(hydrated, "- [ ] Y (due: blargh)\n");
+        assert_eq!(hydrate_issues.len(), 1);
+    }
+
+    #[test]
+    fn reports_malformed_id_and_keeps_the_line() {
+        let input = "- [_] X (id: 123e4567-e89b-12d3-a456-4266141740zz)\n";
+
+        let issues = validate_todo_issues(input);
+        assert_eq!(issues.len(), 1);
+        assert_eq!(issues[0].kind, IssueKind::InvalidId);
+        assert!(issues[0].message.contains("not a valid UUID"));
+
+        let (formatted, format_issues) = format_todo_content(input);
+        assert_eq!(formatted, input);
+        assert_eq!(format_issues.len(), 1);
+    }
+
+    #[test]
+    fn upgrades_an_unversioned_file_to_the_current_format() {
+        let _zone = pin_home_zone(chrono_tz::UTC);
+        let old = "# Home\n\
+                   - [ ] Pay rent (due: 2026-03-01T12:00:00Z) (recurrence: monthly on the 1st) (id: 123e4567-e89b-12d3-a456-426614174000)\n\
+                   - [X] Call mom (id: 123e4567-e89b-12d3-a456-426614174001)\n\
+                   - [_] Book flights (id: 123e4567-e89b-12d3-a456-426614174002)\n";
+
+        let upgrade = upgrade_content(old).expect("upgrade");
+        assert_eq!(upgrade.from_version, 1);
+        assert_eq!(upgrade.changed_lines, [2, 3]);
+        assert_eq!(
+            upgrade.content,
+            format!(
+                "---\nformat: {LINE_FORMAT_VERSION}\n---\n\
+                 # Home\n\
+                 - [_] Pay rent (due: 2026-03-01 12:00 PM) (reccurence: monthly on 1st) (id: 123e4567-e89b-12d3-a456-426614174000)\n\
+                 - [x] Call mom (id: 123e4567-e89b-12d3-a456-426614174001)\n\
+                 - [_] Book flights (id: 123e4567-e89b-12d3-a456-426614174002)\n"
+            )
+        );
+
+        let again = upgrade_content(&upgrade.content).expect("upgrade again");
+        assert_eq!(again.from_version, LINE_FORMAT_VERSION);
+        assert!(again.changed_lines.is_empty());
+        assert_eq!(again.content, upgrade.content);
+    }
+
+    #[test]
+    fn repairs_id_spacing_mismatches() {
+        let input = "- [_] A (id:  123e4567-e89b-42d3-a456-426614174000)\n\
+                     - [_] B (id:123e4567-e89b-42d3-a456-426614174001)\n\
+                     - [_] C (id: 123e4567-e89b-42d3-a456-426614174002 )\n\
+                     - [_] D (id: 123e4567-e89b-42d3-a456-426614174003) later\n\
+                     - [_] E (id: 123e4567-e89b-42d3-a456-426614174004)\n\
+                     - [_] F (id: `123e4567-e89b-42d3-a456-426614174005`)\n";
+        // Padding and wrappers are read through; a missing space is not.
+        let mismatches = validate_todo_issues(input)
+            .iter()
+            .filter(|issue| issue.kind == IssueKind::IdMismatch)
+            .map(|issue| issue.line)
+            .collect::<Vec<_>>();
+        assert_eq!(mismatches, [2, 4]);
+
+        let (repaired, lines) = repair_id_segments(input);
+        assert_eq!(lines, [1, 2, 3, 6]);
+        assert!(repaired.contains("- [_] A (id: 123e4567-e89b-42d3-a456-426614174000)\n"));
+        assert!(repaired.contains("- [_] C (id: 123e4567-e89b-42d3-a456-426614174002)\n"));
+        assert!(repaired.contains("- [_] F (id: 123e4567-e89b-42d3-a456-426614174005)\n"));
+
+        let remaining = validate_todo_issues(&repaired);
+        assert_eq!(remaining.len(), 1);
+        assert_eq!(remaining[0].line, 4);
+        assert_eq!(remaining[0].kind, IssueKind::IdMismatch);
+    }
+
+    #[test]
+    fn reassigns_duplicate_ids_after_the_first() {
+        let input = "- [_] Call mom (id: 123e4567-e89b-42d3-a456-426614174000)\n\
+                     - [_] Pay rent (id: 123e4567-e89b-42d3-a456-426614174001)\n\
+                     - [_] Call mom (due: 2026-03-01T12:00:00Z) (id: 123e4567-e89b-42d3-a456-426614174000)\n";
+        assert_eq!(validate_todo_issues(input)[0].kind, IssueKind::DuplicateId);
+
+        let (fixed, reassigned) = reassign_duplicate_ids(input);
+        assert_eq!(reassigned.len(), 1);
+        assert_eq!(reassigned[0].line, 3);
+        assert_eq!(
+            reassigned[0].old.to_string(),
+            "123e4567-e89b-42d3-a456-426614174000"
+        );
+        assert!(fixed.starts_with("- [_] Call mom (id: 123e4567-e89b-42d3-a456-426614174000)\n"));
+        assert!(fixed.ends_with(&format!(
+            "- [_] Call mom (due: 2026-03-01T12:00:00Z) (id: {})\n",
+            reassigned[0].new
+        )));
+        assert!(validate_todo_issues(&fixed).is_empty());
+        assert!(reassign_duplicate_ids(&fixed).1.is_empty());
+    }
+
+    #[test]
+    fn reports_duplicated_due_segment_and_keeps_the_line() {
+        let input = "- [_] X (due: 2026-03-01) (due: 2026-03-02) (id: 123e4567-e89b-12d3-a456-426614174000)\n";
+
+        let issues = validate_todo_issues(input);
+        assert_eq!(issues.len(), 1);
+        assert_eq!(issues[0].kind, IssueKind::DuplicateMetadata);
+        assert!(!issues[0].kind.is_skippable());
+        assert!(issues[0].message.contains("`(due: ...)`"));
+
+        let (formatted, format_issues) = format_todo_content(input);
+        assert_eq!(formatted, input);
+        assert_eq!(format_issues.len(), 1);
+    }
+
+    #[test]
+    fn reports_duplicated_recurrence_segment() {
+        let input = "- [_] X (reccurence: daily) (recurrence: weekly) (id: 123e4567-e89b-12d3-a456-426614174000)\n";
+
+        let issues = validate_todo_issues(input);
+        assert_eq!(issues.len(), 1);
+        assert_eq!(issues[0].kind, IssueKind::DuplicateMetadata);
+        assert!(issues[0].message.contains("`(reccurence: ...)`"));
+    }
+
     #[test]
     fn formats_parsable_todo_lines() {
         let input =
@@ -272,6 +1472,333 @@ mod tests {
         assert!(hydrated.contains("(id: "));
     }
 
+    #[test]
+    fn fmt_expands_tabs_in_note_lines_only() {
+        let content = "- [_] Plan trip (id: 123e4567-e89b-12d3-a456-426614174000)\n\
+                       \tflights booked\n\
+                       \x20\x20\thotel pending\n\
+                       \n\
+                       \tcode block, not a note\n";
+        let (formatted, issues) = format_todo_content(content);
+
+        assert!(issues.is_empty());
+        assert_eq!(
+            formatted,
+            "- [_] Plan trip (id: 123e4567-e89b-12d3-a456-426614174000)\n\
+             \x20\x20\x20\x20flights booked\n\
+             \x20\x20\x20\x20hotel pending\n\
+             \n\
+             \tcode block, not a note\n"
+        );
+    }
+
+    #[test]
+    fn notes_stay_under_their_todo_through_fmt_and_sort() {
+        let _zone = pin_home_zone(chrono_tz::UTC);
+        let content = "- [_] Later (due: 2026-03-02T12:00:00Z) (id: 123e4567-e89b-12d3-a456-426614174000)\n\
+                       \x20\x20- book flights\n\
+                       \x20\x20\x20\x20- window seat\n\
+                       - [_] Sooner   (due: 2026-03-01T12:00:00Z) (id: 123e4567-e89b-12d3-a456-426614174001)\n\
+                       \t\t- call first\n";
+        let parsed = parse_todo_content(content);
+        let later =
+            &parsed.todos_by_id[&Uuid::parse_str("123e4567-e89b-12d3-a456-426614174000").unwrap()];
+        assert_eq!(later.notes(), ["- book flights", "- window seat"]);
+
+        let (formatted, issues) = format_todo_content(content);
+        assert!(issues.is_empty());
+        let sorted = sort_sections(&formatted, SectionSort::Due);
+        assert_eq!(
+            sorted,
+            "- [_] Sooner (due: 2026-03-01 12:00 PM) (id: 123e4567-e89b-12d3-a456-426614174001)\n\
+             \x20\x20\x20\x20- call first\n\
+             - [_] Later (due: 2026-03-02 12:00 PM) (id: 123e4567-e89b-12d3-a456-426614174000)\n\
+             \x20\x20\x20\x20- book flights\n\
+             \x20\x20\x20\x20\x20\x20- window seat\n"
+        );
+        let reread = parse_todo_content(&sorted);
+        for (id, todo) in &parsed.todos_by_id {
+            assert_eq!(reread.todos_by_id[id].notes(), todo.notes());
+        }
+    }
+
+    #[test]
+    fn backups_copy_the_file_and_keep_the_newest() {
+        let root = std::env::temp_dir().join(format!("todo_md_backups_{}", std::process::id()));
+        let _ = fs::remove_dir_all(&root);
+        let backup_dir = root.join("backups");
+        let todo_file = root.join("todo.md");
+        let at = |minute: u32| {
+            DateTime::parse_from_rfc3339(&format!("2026-02-23T18:{minute:02}:00Z"))
+                .expect("valid timestamp")
+                .with_timezone(&Utc)
+        };
+        let content =
+            |n: u32| format!("- [_] Version {n} (id: 123e4567-e89b-12d3-a456-426614174000)\n");
+
+        let first = backup_todo_file(&backup_dir, &todo_file, &content(0), 3, at(0))
+            .expect("backup")
+            .expect("written");
+        assert_eq!(first, backup_dir.join("todo.20260223T180000Z.md"));
+        assert_eq!(fs::read_to_string(&first).expect("read backup"), content(0));
+        // Unchanged since the newest copy: nothing new to keep.
+        assert_eq!(
+            backup_todo_file(&backup_dir, &todo_file, &content(0), 3, at(1)).expect("backup"),
+            None
+        );
+
+        // Another profile's copies share the directory but not the pruning.
+        let other = backup_todo_file(
+            &backup_dir,
+            &root.join("todo-work.md"),
+            &content(9),
+            3,
+            at(0),
+        )
+        .expect("backup")
+        .expect("written");
+        for n in 1..=4 {
+            backup_todo_file(&backup_dir, &todo_file, &content(n), 3, at(n)).expect("backup");
+        }
+
+        let kept = backups_of(&backup_dir, "todo").expect("list backups");
+        assert_eq!(
+            kept.iter()
+                .map(|path| fs::read_to_string(path).expect("read backup"))
+                .collect::<Vec<_>>(),
+            vec![content(2), content(3), content(4)]
+        );
+        assert!(other.exists());
+        assert_eq!(
+            backup_todo_file(&backup_dir, &todo_file, &content(5), 0, at(5)).expect("backup"),
+            None
+        );
+
+        let _ = fs::remove_dir_all(&root);
+    }
+
+    #[test]
+    fn atomic_writes_stage_through_a_temp_file_per_target() {
+        let root = std::env::temp_dir().join(format!("todo_md_atomic_{}", std::process::id()));
+        let _ = fs::remove_dir_all(&root);
+        fs::create_dir_all(&root).expect("create dir");
+        // A leftover from another writer must not be picked up or clobbered.
+        let foreign = root.join("todo.md.tmp");
+        fs::write(&foreign, "someone else's write").expect("write foreign temp");
+
+        write_todo_file_atomic(&root.join("todo-work.md"), "work\n").expect("write todo");
+        write_todo_file_atomic(&root.join("trash-work.md"), "trash\n").expect("write trash");
+
+        assert_eq!(
+            fs::read_to_string(root.join("todo-work.md")).expect("read"),
+            "work\n"
+        );
+        assert_eq!(
+            fs::read_to_string(root.join("trash-work.md")).expect("read"),
+            "trash\n"
+        );
+        assert_eq!(
+            fs::read_to_string(&foreign).expect("read"),
+            "someone else's write"
+        );
+        let leftovers = fs::read_dir(&root)
+            .expect("list dir")
+            .filter_map(|entry| entry.ok())
+            .filter(|entry| entry.path() != foreign)
+            .filter(|entry| entry.file_name().to_string_lossy().ends_with(".tmp"))
+            .count();
+        assert_eq!(leftovers, 0);
+
+        let _ = fs::remove_dir_all(&root);
+    }
+
+    #[test]
+    fn order_follows_the_source_lines() {
+        let parsed = parse_todo_content(
+            "# Later\n\
+             - [_] Zebra (id: 123e4567-e89b-12d3-a456-426614174002)\n\
+             \x20   a note\n\
+             not a todo\n\
+             - [x] Apple (id: 123e4567-e89b-12d3-a456-426614174000)\n\
+             - [_] Mango (id: 123e4567-e89b-12d3-a456-426614174001)\n\
+             - [_] Zebra again (id: 123e4567-e89b-12d3-a456-426614174002)\n",
+        );
+        let id = |suffix: &str| {
+            Uuid::parse_str(&format!("123e4567-e89b-12d3-a456-4266141740{suffix}")).expect("id")
+        };
+
+        assert_eq!(parsed.order, vec![id("02"), id("00"), id("01")]);
+        assert_eq!(parsed.todos_by_id.len(), parsed.order.len());
+        assert_eq!(
+            parsed
+                .todos_in_order()
+                .iter()
+                .map(|todo| todo.name())
+                .collect::<Vec<_>>(),
+            vec!["Zebra again", "Apple", "Mango"]
+        );
+    }
+
+    #[test]
+    fn fmt_writes_open_checkboxes_in_one_style() {
+        let content = "- [ ] Markdown (id: 123e4567-e89b-12d3-a456-426614174000)\n\
+                       - [_] Native (id: 123e4567-e89b-12d3-a456-426614174001)\n\
+                       - [X] Done (id: 123e4567-e89b-12d3-a456-426614174002)\n";
+        let (formatted, issues) = format_todo_content(content);
+
+        assert!(issues.is_empty());
+        assert_eq!(
+            formatted,
+            "- [_] Markdown (id: 123e4567-e89b-12d3-a456-426614174000)\n\
+             - [_] Native (id: 123e4567-e89b-12d3-a456-426614174001)\n\
+             - [x] Done (id: 123e4567-e89b-12d3-a456-426614174002)\n"
+        );
+    }
+
+    #[test]
+    fn fmt_preserves_sections_and_blank_lines() {
+        let _zone = pin_home_zone(chrono_tz::UTC);
+        let content = "# Todos\n\
+                       \n\
+                       ## Work\n\
+                       - [_]   Ship release (due: 2026-03-02T12:00:00Z) (id: 123e4567-e89b-12d3-a456-426614174000)\n\
+                       - [_] File report (due: 2026-03-01T12:00:00Z) (id: 123e4567-e89b-12d3-a456-426614174001)\n\
+                       \n\
+                       \n\
+                       ## Home\n\
+                       - [_] Water plants (id: 123e4567-e89b-12d3-a456-426614174002)\n\
+                       - [_] Pay rent (due: 2026-02-28T12:00:00Z) (id: 123e4567-e89b-12d3-a456-426614174003)\n\
+                       \x20\x20\x20\x20transfer from savings\n";
+        let (formatted, issues) = format_todo_content(content);
+        assert!(issues.is_empty());
+        assert_eq!(
+            formatted,
+            "# Todos\n\
+             \n\
+             ## Work\n\
+             - [_] Ship release (due: 2026-03-02 12:00 PM) (id: 123e4567-e89b-12d3-a456-426614174000)\n\
+             - [_] File report (due: 2026-03-01 12:00 PM) (id: 123e4567-e89b-12d3-a456-426614174001)\n\
+             \n\
+             \n\
+             ## Home\n\
+             - [_] Water plants (id: 123e4567-e89b-12d3-a456-426614174002)\n\
+             - [_] Pay rent (due: 2026-02-28 12:00 PM) (id: 123e4567-e89b-12d3-a456-426614174003)\n\
+             \x20\x20\x20\x20transfer from savings\n"
+        );
+
+        assert_eq!(
+            sort_sections(&formatted, SectionSort::Due),
+            "# Todos\n\
+             \n\
+             ## Work\n\
+             - [_] File report (due: 2026-03-01 12:00 PM) (id: 123e4567-e89b-12d3-a456-426614174001)\n\
+             - [_] Ship release (due: 2026-03-02 12:00 PM) (id: 123e4567-e89b-12d3-a456-426614174000)\n\
+             \n\
+             \n\
+             ## Home\n\
+             - [_] Pay rent (due: 2026-02-28 12:00 PM) (id: 123e4567-e89b-12d3-a456-426614174003)\n\
+             \x20\x20\x20\x20transfer from savings\n\
+             - [_] Water plants (id: 123e4567-e89b-12d3-a456-426614174002)\n"
+        );
+    }
+
+    #[test]
+    fn sort_puts_done_last_then_orders_by_due_and_name() {
+        let content = "## Inbox\n\
+                       - [x] Archived (due: 2026-01-01 12:00 PM) (id: 123e4567-e89b-12d3-a456-426614174000)\n\
+                       - [_] Zebra (id: 123e4567-e89b-12d3-a456-426614174001)\n\
+                       - [_] Apple (id: 123e4567-e89b-12d3-a456-426614174002)\n\
+                       - [_] Later (due: 2026-03-02 12:00 PM) (id: 123e4567-e89b-12d3-a456-426614174003)\n\
+                       - [_] Sooner (due: 2026-03-01 12:00 PM) (id: 123e4567-e89b-12d3-a456-426614174004)\n\
+                       ## Done\n\
+                       - [x] Filed taxes (id: 123e4567-e89b-12d3-a456-426614174005)\n";
+        let sorted = sort_sections(content, SectionSort::DoneLast);
+        let names = sorted
+            .lines()
+            .map(|line| match Todo::try_from_str(line) {
+                Ok(todo) => todo.name(),
+                Err(_) => line.to_string(),
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(
+            names,
+            [
+                "## Inbox",
+                "Sooner",
+                "Later",
+                "Apple",
+                "Zebra",
+                "Archived",
+                "## Done",
+                "Filed taxes"
+            ]
+        );
+        // Only the order changes; every line is kept byte for byte.
+        let mut before = content.lines().collect::<Vec<_>>();
+        let mut after = sorted.lines().collect::<Vec<_>>();
+        before.sort_unstable();
+        after.sort_unstable();
+        assert_eq!(after, before);
+        assert_eq!(sort_sections(&sorted, SectionSort::DoneLast), sorted);
+    }
+
+    #[test]
+    fn section_headers_need_a_space_after_the_hashes() {
+        assert!(is_section_header("## Work"));
+        assert!(is_section_header("#"));
+        assert!(!is_section_header("#errands"));
+        assert!(!is_section_header("####### Too deep"));
+    }
+
+    #[test]
+    fn reordered_metadata_segments_are_valid() {
+        let _zone = pin_home_zone(chrono_tz::UTC);
+        let content = "- [_] Pay rent (id: 123e4567-e89b-12d3-a456-426614174000) (due: 2026-03-01T12:00:00Z)\n";
+        assert!(validate_todo_content(content).is_empty());
+        let (formatted, issues) = format_todo_content(content);
+        assert!(issues.is_empty());
+        assert_eq!(
+            formatted,
+            "- [_] Pay rent (due: 2026-03-01 12:00 PM) (id: 123e4567-e89b-12d3-a456-426614174000)\n"
+        );
+    }
+
+    #[test]
+    fn gitignore_entries_are_added_idempotently() {
+        let temp_dir =
+            std::env::temp_dir().join(format!("todo_md_gitignore_{}", std::process::id()));
+        let _ = fs::create_dir_all(&temp_dir);
+        let gitignore = temp_dir.join(".gitignore");
+        fs::write(&gitignore, "node_modules\n.env").expect("write");
+
+        let entries = [".env", "*.tmp", "backups/"]
+            .iter()
+            .map(|entry| entry.to_string())
+            .collect::<Vec<_>>();
+        ensure_gitignore_entries(&gitignore, &entries).expect("first pass");
+        ensure_gitignore_entries(&gitignore, &entries).expect("second pass");
+
+        let content = fs::read_to_string(&gitignore).expect("read");
+        assert_eq!(content, "node_modules\n.env\n*.tmp\nbackups/\n");
+    }
+
+    #[test]
+    fn regenerates_every_id_and_reports_mapping() {
+        let input = "# Inbox\n- [_] A (id: 123e4567-e89b-12d3-a456-426614174000)\n- [x] B (id: 123e4567-e89b-12d3-a456-426614174001)\n- [ ] C\n";
+        let (regenerated, mapping) = regenerate_todo_ids(input);
+
+        assert_eq!(mapping.len(), 2);
+        assert_eq!(
+            mapping[0].0,
+            Uuid::parse_str("123e4567-e89b-12d3-a456-426614174000").expect("id")
+        );
+        assert!(!regenerated.contains("123e4567-e89b-12d3-a456-42661417400"));
+        assert!(regenerated.contains(&format!("- [_] A (id: {})", mapping[0].1)));
+        assert!(regenerated.contains(&format!("- [x] B (id: {})", mapping[1].1)));
+        assert!(regenerated.starts_with("# Inbox\n"));
+        assert!(regenerated.ends_with("- [ ] C\n"));
+    }
+
     #[test]
     fn formatting_rolls_completed_recurring_todo_forward() {
         let input = "- [x] Water plants (due: 2026-02-23T14:00:00Z) (reccurence: weekly on monday, thursday) (id: 123e4567-e89b-12d3-a456-426614174000)\n";
@@ -287,4 +1814,44 @@ mod tests {
             "2026-02-26T14:00:00+00:00"
         );
     }
+
+    #[test]
+    fn strict_mode_reports_every_silent_loss() {
+        let content = "- [_] Clean (id: 123e4567-e89b-12d3-a456-426614174000)\n\
+            - [_] Pay rent (due: someday soon) (id: 123e4567-e89b-12d3-a456-426614174001)\n\
+            - [_] Water (reccurence: every blue moon) (id: 123e4567-e89b-12d3-a456-426614174002)\n\
+            - [_] Call (due: 2026-03-01T12:00:00Z) (id: 123e4567-e89b-12d3-a456-426614174003).\n\
+            - [_] Fix (id: 123e4567-e89b-12d3-a456-426614174004) (due: 2026-03-01T12:00:00Z)\n\
+            - [_] Read (due: 2026-03-01T12:00:00Z) (due: 2026-03-02T12:00:00Z) (id: 123e4567-e89b-12d3-a456-426614174005)\n\
+            - [not a todo\n";
+
+        let kinds = silent_loss_issues(content)
+            .into_iter()
+            .map(|issue| (issue.line, issue.kind))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            kinds,
+            [
+                (2, IssueKind::InvalidDue),
+                (3, IssueKind::InvalidRecurrence),
+                (4, IssueKind::DroppedPeriod),
+                (5, IssueKind::ReorderedSegments),
+                (6, IssueKind::DuplicateMetadata),
+                (7, IssueKind::Unparseable),
+            ]
+        );
+
+        let error = ensure_lossless(content).expect_err("strict read fails");
+        assert!(error
+            .to_string()
+            .contains("line 4: the trailing `.` is dropped"));
+        assert!(
+            ensure_lossless("- [_] Clean (id: 123e4567-e89b-12d3-a456-426614174000)\n").is_ok()
+        );
+
+        // A canonical rewrite is lossless by definition.
+        let fixed =
+            "- [_] Fix (due: 2026-03-01 12:00 PM) (id: 123e4567-e89b-12d3-a456-426614174004)\n";
+        assert!(silent_loss_issues(fixed).is_empty());
+    }
 }
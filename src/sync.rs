@@ -1,48 +1,235 @@
-use std::path::Path;
-use std::process::{Command, Output};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Output, Stdio};
 use std::{fs, io};
 
 use anyhow::{bail, Context, Result};
-
-use crate::config::{require_remote, AppConfig};
-use crate::diff::{line_diff_summary, semantic_changes, ChangeSet};
+use chrono::{DateTime, SecondsFormat, Utc};
+use serde::Serialize;
+
+use crate::config::{env_example, require_remote, AppConfig, ENV_EXAMPLE_FILE};
+use crate::diff::{line_diff_summary, semantic_changes, ChangeKind, ChangeSet, TodoChange};
+use crate::edit::line_todo_id;
+use crate::history::{self, NamedChange};
+use crate::merge;
 use crate::storage::{
-    ensure_layout, format_todo_content, hydrate_todo_ids, parse_todo_content, read_todo_file,
-    validate_todo_content, write_todo_file_atomic,
+    backup_todo_file, ensure_layout, format_todo_content, hydrate_todo_ids, parse_todo_content,
+    read_todo_file, resolve_id_prefix, validate_todo_issues, write_todo_file_atomic,
+    write_todo_file_checked, IssueKind, ParsedTodoFile, TodoIssue,
 };
+use uuid::Uuid;
 
 #[derive(Debug, Clone)]
 pub struct SyncResult {
     pub committed: bool,
     pub change_set: ChangeSet,
     pub line_summary: String,
+    /// Invalid lines committed verbatim because `skip_invalid` was set.
+    pub skipped: Vec<String>,
+    /// Local commits from an earlier sync whose push never reached origin,
+    /// pushed by this sync.
+    pub pushed_pending: usize,
+    /// The changes were folded into the previous, unpushed sync commit.
+    pub amended: bool,
+    /// Why `TODOS_POST_SYNC_HOOK` failed; a failing hook never fails the sync.
+    pub hook_warning: Option<String>,
+    /// Set when `.env` is tracked by git from an earlier commit.
+    pub env_warning: Option<String>,
+    /// What origin changed since the last shared commit, taken right after
+    /// fetching; set only with [`SyncOptions::report_incoming`].
+    pub incoming: Option<Vec<NamedChange>>,
 }
 
-pub fn setup(config: &AppConfig, remote_override: Option<&str>) -> Result<()> {
-    ensure_layout(&config.config_dir, &config.todo_file, &config.env_file)?;
+/// What `sync --format json` prints.
+#[derive(Debug, Serialize)]
+struct SyncReport<'a> {
+    committed: bool,
+    added: usize,
+    updated: usize,
+    deleted: usize,
+    completed: usize,
+    changes: Vec<&'a TodoChange>,
+    line_summary: &'a str,
+}
 
-    if !config.config_dir.join(".git").exists() {
-        run_git_checked(&config.config_dir, ["init"])?;
+impl SyncResult {
+    /// One JSON object with the commit outcome, the change counts, each
+    /// change in [`ChangeSet::sorted_changes`] order and the line summary.
+    pub fn to_json(&self) -> Result<String> {
+        let report = SyncReport {
+            committed: self.committed,
+            added: self.change_set.added,
+            updated: self.change_set.updated,
+            deleted: self.change_set.deleted,
+            completed: self.change_set.completed,
+            changes: self.change_set.sorted_changes(),
+            line_summary: &self.line_summary,
+        };
+        serde_json::to_string(&report).context("failed to serialize sync result as json")
     }
+}
 
-    run_git_checked(
+#[derive(Debug, Clone, Default)]
+pub struct SyncOptions {
+    /// Commit despite unparseable or id-less todo lines, reporting them instead.
+    /// Conflict markers, duplicate ids and id mismatches still block.
+    pub skip_invalid: bool,
+    /// Commit only the change to the todo whose id starts with this prefix.
+    pub only: Option<String>,
+    /// Leave done-state toggles out of the line diff in the summary.
+    pub exclude_done: bool,
+    /// Author and committer date for the commit this sync creates, e.g. a
+    /// completion date when reconstructing history during a migration.
+    pub commit_date: Option<DateTime<Utc>>,
+    /// Report origin's changes before rebasing onto them.
+    pub report_incoming: bool,
+    /// Amend the previous sync commit instead of adding another, when it has
+    /// not reached origin (see [`can_amend_head`]).
+    pub aggressive: bool,
+}
+
+/// Creates the layout and git repo and wires up the remote, or with `local`
+/// leaves the repo without one. Returns the remote's default branch when it
+/// was adopted because `TODOS_GIT_BRANCH` was unset; always `None` with
+/// `local`.
+pub fn setup(
+    config: &AppConfig,
+    remote_override: Option<&str>,
+    local: bool,
+) -> Result<Option<String>> {
+    ensure_layout(
         &config.config_dir,
-        ["checkout", "-B", config.git_branch.as_str()],
+        &config.todo_file,
+        &config.env_file,
+        &config.gitignore_entries,
     )?;
 
-    let remote = remote_override
-        .map(|value| value.to_string())
-        .or_else(|| config.git_remote.clone());
+    write_env_example(&config.config_dir)?;
 
-    if let Some(remote) = remote {
-        ensure_github_repo_exists(config, &remote)?;
-        ensure_remote(&config.config_dir, "origin", &remote)?;
-        upsert_env_var(&config.env_file, "TODOS_GIT_REMOTE", &remote)?;
+    if !config.config_dir.join(".git").exists() {
+        run_git_checked(&config.config_dir, ["init"])?;
     }
 
+    let mut detected_branch = None;
+    if let Some(remote) = setup_remote(remote_override, config.git_remote.as_deref(), local) {
+        ensure_github_repo_exists(config, remote)?;
+        ensure_remote(&config.config_dir, "origin", remote)?;
+        upsert_env_var(&config.env_file, "TODOS_GIT_REMOTE", remote)?;
+
+        if !config.git_branch_configured
+            && let Some(branch) = remote_default_branch(&config.config_dir, remote)?
+        {
+            upsert_env_var(&config.env_file, "TODOS_GIT_BRANCH", &branch)?;
+            detected_branch = Some(branch);
+        }
+    }
+
+    let branch = detected_branch.as_deref().unwrap_or(&config.git_branch);
+    run_git_checked(&config.config_dir, ["checkout", "-B", branch])?;
+
+    Ok(detected_branch)
+}
+
+/// The remote `setup` wires up: the one given on the command line, else
+/// `TODOS_GIT_REMOTE`. `setup --local` wires none, even when one is
+/// configured, so no GitHub lookup or `origin` change happens.
+fn setup_remote<'a>(
+    remote_override: Option<&'a str>,
+    configured: Option<&'a str>,
+    local: bool,
+) -> Option<&'a str> {
+    if local {
+        return None;
+    }
+    remote_override.or(configured)
+}
+
+/// Rewrites `.env.example` only when the generated reference changed.
+fn write_env_example(config_dir: &Path) -> Result<()> {
+    let path = config_dir.join(ENV_EXAMPLE_FILE);
+    let expected = env_example();
+    if fs::read_to_string(&path).ok().as_deref() != Some(expected.as_str()) {
+        fs::write(&path, expected)
+            .with_context(|| format!("failed to write {}", path.display()))?;
+    }
     Ok(())
 }
 
+/// The branch the remote's `HEAD` points at. `None` when the remote is empty
+/// or unreachable, in which case setup keeps the "main" default.
+fn remote_default_branch(repo_dir: &Path, remote: &str) -> Result<Option<String>> {
+    let output = run_git(repo_dir, ["ls-remote", "--symref", remote, "HEAD"])?;
+    if !output.status.success() {
+        return Ok(None);
+    }
+    Ok(parse_symref_head(&String::from_utf8_lossy(&output.stdout)))
+}
+
+/// Remote branches left behind by an earlier `TODOS_GIT_BRANCH`: anything but
+/// the configured branch whose tip holds the todo file and was made by
+/// `sync`. Other branches on a shared remote are never offered.
+pub fn stale_sync_branches(config: &AppConfig) -> Result<Vec<String>> {
+    let remote = require_remote(config)?;
+    let todo_rel = todo_path_relative_to_repo(config)?;
+    let heads = run_git_checked(&config.config_dir, ["ls-remote", "--heads", remote])?;
+
+    let mut stale = Vec::new();
+    for branch in parse_ls_remote_heads(&heads) {
+        if branch == config.git_branch {
+            continue;
+        }
+        let refspec = format!("refs/heads/{branch}");
+        run_git_checked(
+            &config.config_dir,
+            ["fetch", "--quiet", remote, refspec.as_str()],
+        )?;
+        let has_todo_file = run_git(
+            &config.config_dir,
+            ["cat-file", "-e", format!("FETCH_HEAD:{todo_rel}").as_str()],
+        )?
+        .status
+        .success();
+        let subject = run_git_checked(
+            &config.config_dir,
+            ["log", "-1", "--format=%s", "FETCH_HEAD"],
+        )?;
+        if has_todo_file && is_sync_subject(&subject, config.commit_template.as_deref()) {
+            stale.push(branch);
+        }
+    }
+    Ok(stale)
+}
+
+pub fn delete_remote_branch(config: &AppConfig, branch: &str) -> Result<()> {
+    let remote = require_remote(config)?;
+    run_git_checked(&config.config_dir, ["push", remote, "--delete", branch])?;
+    Ok(())
+}
+
+/// Branch names from `<sha>\trefs/heads/<name>` lines.
+fn parse_ls_remote_heads(output: &str) -> Vec<String> {
+    output
+        .lines()
+        .filter_map(|line| line.split_once('\t'))
+        .filter_map(|(_, name)| name.trim().strip_prefix("refs/heads/"))
+        .map(str::to_string)
+        .collect()
+}
+
+/// Reads `ref: refs/heads/<branch>\tHEAD` from `git ls-remote --symref`.
+fn parse_symref_head(output: &str) -> Option<String> {
+    output.lines().find_map(|line| {
+        let (target, name) = line.strip_prefix("ref: ")?.split_once('\t')?;
+        if name.trim() != "HEAD" {
+            return None;
+        }
+        target
+            .strip_prefix("refs/heads/")
+            .filter(|branch| !branch.is_empty())
+            .map(str::to_string)
+    })
+}
+
 fn upsert_env_var(path: &Path, key: &str, value: &str) -> Result<()> {
     let existing = match fs::read_to_string(path) {
         Ok(content) => content,
@@ -63,12 +250,12 @@ fn upsert_env_var(path: &Path, key: &str, value: &str) -> Result<()> {
             continue;
         }
 
-        if let Some((lhs, _rhs)) = line.split_once('=') {
-            if lhs.trim() == key {
-                lines.push(format!("{key}={value}"));
-                found = true;
-                continue;
-            }
+        if let Some((lhs, _rhs)) = line.split_once('=')
+            && lhs.trim() == key
+        {
+            lines.push(format!("{key}={value}"));
+            found = true;
+            continue;
         }
 
         lines.push(line.to_string());
@@ -131,8 +318,13 @@ fn ensure_github_repo_exists(config: &AppConfig, remote_url: &str) -> Result<()>
     )
 }
 
-pub fn sync(config: &AppConfig) -> Result<SyncResult> {
-    ensure_layout(&config.config_dir, &config.todo_file, &config.env_file)?;
+pub fn sync(config: &AppConfig, options: &SyncOptions) -> Result<SyncResult> {
+    ensure_layout(
+        &config.config_dir,
+        &config.todo_file,
+        &config.env_file,
+        &config.gitignore_entries,
+    )?;
     let remote = require_remote(config)?;
 
     if !config.config_dir.join(".git").exists() {
@@ -142,18 +334,414 @@ pub fn sync(config: &AppConfig) -> Result<SyncResult> {
         );
     }
 
-    run_git_checked(&config.config_dir, ["fetch", "origin"])?;
+    // A copy of todo.md as the user left it, before the rebase or hydration
+    // can rewrite it.
+    let before = read_todo_file(&config.todo_file)?;
+    backup_todo_file(
+        &config.backup_dir,
+        &config.todo_file,
+        &before.content,
+        config.backup_keep,
+        Utc::now(),
+    )?;
+
+    match config.fetch_depth {
+        Some(depth) => {
+            let depth = depth.to_string();
+            run_git_checked(
+                &config.config_dir,
+                ["fetch", "--depth", depth.as_str(), "origin"],
+            )?;
+        }
+        None => {
+            run_git_checked(&config.config_dir, ["fetch", "origin"])?;
+        }
+    }
+    let incoming = options
+        .report_incoming
+        .then(|| history::incoming(config))
+        .transpose()?;
     run_git_checked(
         &config.config_dir,
         ["checkout", "-B", config.git_branch.as_str()],
     )?;
     let todo_rel = todo_path_relative_to_repo(config)?;
     pull_with_recovery(config, &todo_rel)?;
+    let pending = commits_ahead_of_origin(config)?;
+    let mut env_warning = guard_env_file(&config.config_dir, &config.env_file)?;
+    if let Some(profile_env_file) = &config.profile_env_file
+        && env_warning.is_none()
+    {
+        env_warning = guard_env_file(&config.config_dir, profile_env_file)?;
+    }
+
+    // Fast path: the file is byte-for-byte what the last full sync left behind
+    // and nothing else in the repo changed, so there is nothing to hydrate,
+    // validate or diff.
+    let synced_marker = last_synced_marker(config);
+    if options.only.is_none()
+        && fs::read_to_string(&config.todo_file)
+            .is_ok_and(|content| unchanged_since_last_sync(&synced_marker, &content))
+        && run_git_checked(&config.config_dir, ["status", "--porcelain"])?
+            .trim()
+            .is_empty()
+    {
+        let change_set = ChangeSet::default();
+        let mut hook_warning = None;
+        if pending > 0 {
+            run_git_checked(
+                &config.config_dir,
+                ["push", "-u", remote, config.git_branch.as_str()],
+            )?;
+            hook_warning = run_post_sync_hook(config, &change_set, false);
+        }
+        return Ok(SyncResult {
+            committed: false,
+            line_summary: line_diff_summary("", "", options.exclude_done),
+            change_set,
+            skipped: Vec::new(),
+            pushed_pending: pending,
+            amended: false,
+            hook_warning,
+            env_warning,
+            incoming,
+        });
+    }
+
+    // Diff against the amended commit's parent so the message covers both.
+    let amend = options.aggressive && can_amend_head(&config.config_dir, &config.git_branch)?;
+    let base = if amend { "HEAD^" } else { "HEAD" };
+    let previous_content = git_show_or_empty(&config.config_dir, &format!("{base}:{todo_rel}"))?;
+    let mut normalized = normalize_todo_file(&config.todo_file, options.skip_invalid)?;
+    if let Some(hook) = config.pre_sync_hook.as_deref() {
+        run_pre_sync_hook(&config.config_dir, hook, &config.todo_file)?;
+        normalized = normalize_todo_file(&config.todo_file, options.skip_invalid)?;
+    }
+    let current = read_todo_file(&config.todo_file)?;
+    let skipped = normalized.skipped;
+    let previous = parse_todo_content(&previous_content);
+
+    let mut change_set = semantic_changes(&previous, &current);
+    let mut line_summary =
+        line_diff_summary(&previous.content, &current.content, options.exclude_done);
+
+    let scoped_parsed = match options.only.as_deref() {
+        Some(prefix) => {
+            let id = resolve_id_prefix(change_set.changes.iter().map(|change| change.id), prefix)
+                .context("`--only` must name a todo with a local change")?;
+            let scoped = scoped_content(&previous.content, &current.content, id);
+            let scoped_parsed = parse_todo_content(&scoped);
+            change_set = semantic_changes(&previous, &scoped_parsed);
+            line_summary = line_diff_summary(&previous.content, &scoped, options.exclude_done);
+            Some(scoped_parsed)
+        }
+        None => None,
+    };
+    let committed = scoped_parsed.as_ref().unwrap_or(&current);
+
+    let todo_status = run_git_checked(
+        &config.config_dir,
+        ["status", "--porcelain", "--", todo_rel.as_str()],
+    )?;
+    // `--only` publishes a single todo, so companion files wait for a full sync.
+    let companions = match options.only {
+        Some(_) => Vec::new(),
+        None => companion_paths_with_changes(config)?,
+    };
 
-    let previous_content = git_show_or_empty(&config.config_dir, &format!("HEAD:{todo_rel}"))?;
-    let mut current = read_todo_file(&config.todo_file)?;
-    let (hydrated_content, hydrated_count, hydrate_issues) = hydrate_todo_ids(&current.content);
-    if !hydrate_issues.is_empty() {
+    if todo_status.trim().is_empty() && companions.is_empty() {
+        let mut hook_warning = None;
+        if pending > 0 {
+            run_git_checked(
+                &config.config_dir,
+                ["push", "-u", remote, config.git_branch.as_str()],
+            )?;
+            hook_warning = run_post_sync_hook(config, &change_set, false);
+        }
+        if options.only.is_none() {
+            record_synced_content(&synced_marker, &current.content)?;
+        }
+        return Ok(SyncResult {
+            committed: false,
+            change_set,
+            line_summary,
+            skipped,
+            pushed_pending: pending,
+            amended: false,
+            hook_warning,
+            env_warning,
+            incoming,
+        });
+    }
+
+    match &scoped_parsed {
+        Some(scoped) => stage_content(&config.config_dir, &todo_rel, &scoped.content)?,
+        None => {
+            run_git_checked(&config.config_dir, ["add", "--", todo_rel.as_str()])?;
+        }
+    }
+    for companion in &companions {
+        run_git_checked(&config.config_dir, ["add", "--", companion.as_str()])?;
+    }
+
+    let message = commit_message(
+        &change_set,
+        &line_summary,
+        config.commit_template.as_deref(),
+    )?;
+    let body = commit_body(&change_set, &previous, committed);
+    run_git_commit(config, &message, &body, options.commit_date, amend)?;
+    run_git_checked(
+        &config.config_dir,
+        ["push", "-u", remote, config.git_branch.as_str()],
+    )?;
+
+    if options.only.is_none() {
+        record_synced_content(&synced_marker, &current.content)?;
+    }
+    let hook_warning = run_post_sync_hook(config, &change_set, true);
+
+    Ok(SyncResult {
+        committed: true,
+        change_set,
+        line_summary,
+        skipped,
+        pushed_pending: pending - usize::from(amend),
+        amended: amend,
+        hook_warning,
+        env_warning,
+        incoming,
+    })
+}
+
+/// `.env` holds tokens and must never be pushed, whatever `.gitignore` says.
+/// Refuses to sync while `.env` is staged, since `git commit` would include
+/// it, and returns a warning when an earlier commit already tracks it.
+fn guard_env_file(repo_dir: &Path, env_file: &Path) -> Result<Option<String>> {
+    let Ok(relative) = env_file.strip_prefix(repo_dir) else {
+        return Ok(None);
+    };
+    let rel = relative.to_string_lossy().to_string();
+
+    let staged = run_git_checked(
+        repo_dir,
+        ["diff", "--cached", "--name-only", "--", rel.as_str()],
+    )?;
+    if !staged.trim().is_empty() {
+        bail!(
+            "{rel} is staged for commit and would push secrets; run `git rm --cached {rel}` in {} and sync again",
+            repo_dir.display()
+        );
+    }
+
+    let tracked = run_git_checked(repo_dir, ["ls-files", "--", rel.as_str()])?;
+    if tracked.trim().is_empty() {
+        return Ok(None);
+    }
+    Ok(Some(format!(
+        "{rel} is tracked by git, so its contents are in the remote's history; run `git rm --cached {rel}` in {}, sync, and rotate any tokens it held",
+        repo_dir.display()
+    )))
+}
+
+/// Hash of the todo.md content the last full sync committed or found clean.
+/// Kept inside `.git` so it is never tracked or pushed.
+fn last_synced_marker(config: &AppConfig) -> PathBuf {
+    let name = match &config.profile {
+        Some(profile) => format!("todo_md-last-sync-{profile}"),
+        None => "todo_md-last-sync".to_string(),
+    };
+    config.config_dir.join(".git").join(name)
+}
+
+fn unchanged_since_last_sync(marker: &Path, content: &str) -> bool {
+    fs::read_to_string(marker).is_ok_and(|stored| stored.trim() == content_hash(content))
+}
+
+fn record_synced_content(marker: &Path, content: &str) -> Result<()> {
+    fs::write(marker, format!("{}\n", content_hash(content)))
+        .with_context(|| format!("failed to write {}", marker.display()))
+}
+
+/// 64-bit FNV-1a, hex encoded. Stable across builds, unlike `DefaultHasher`.
+fn content_hash(content: &str) -> String {
+    let hash = content
+        .bytes()
+        .fold(0xcbf2_9ce4_8422_2325_u64, |hash, byte| {
+            (hash ^ u64::from(byte)).wrapping_mul(0x0000_0100_0000_01b3)
+        });
+    format!("{hash:016x}")
+}
+
+/// Runs `TODOS_PRE_SYNC_HOOK` through `sh -c` from `repo_dir`. Unlike the
+/// post-sync hook it can veto: a nonzero exit fails the sync before anything
+/// is staged or committed.
+fn run_pre_sync_hook(repo_dir: &Path, hook: &str, todo_file: &Path) -> Result<()> {
+    let output = Command::new("sh")
+        .args(["-c", hook])
+        .current_dir(repo_dir)
+        .env("TODO_FILE", todo_file)
+        .stdin(Stdio::null())
+        .output()
+        .with_context(|| format!("pre-sync hook `{hook}` could not run"))?;
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        let mut message = format!(
+            "pre-sync hook `{hook}` exited with {}; nothing was committed",
+            output.status
+        );
+        if !stderr.trim().is_empty() {
+            message.push_str(&format!("\nstderr:\n{}", stderr.trim()));
+        }
+        bail!(message);
+    }
+    Ok(())
+}
+
+/// Runs `TODOS_POST_SYNC_HOOK` through `sh -c`. Returns a warning instead of
+/// an error so a broken hook can't make a finished sync look failed.
+fn run_post_sync_hook(
+    config: &AppConfig,
+    change_set: &ChangeSet,
+    committed: bool,
+) -> Option<String> {
+    let hook = config.post_sync_hook.as_deref()?;
+    let output = Command::new("sh")
+        .args(["-c", hook])
+        .current_dir(&config.config_dir)
+        .envs(post_sync_hook_env(change_set, committed))
+        .env("TODO_FILE", &config.todo_file)
+        .stdin(Stdio::null())
+        .output();
+    match output {
+        Ok(output) if output.status.success() => None,
+        Ok(output) => {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            let mut warning = format!("post-sync hook `{hook}` exited with {}", output.status);
+            if !stderr.trim().is_empty() {
+                warning.push_str(&format!("\nstderr:\n{}", stderr.trim()));
+            }
+            Some(warning)
+        }
+        Err(error) => Some(format!("post-sync hook `{hook}` could not run: {error}")),
+    }
+}
+
+fn post_sync_hook_env(change_set: &ChangeSet, committed: bool) -> [(&'static str, String); 5] {
+    [
+        ("TODO_ADDED", change_set.added.to_string()),
+        ("TODO_UPDATED", change_set.updated.to_string()),
+        ("TODO_DELETED", change_set.deleted.to_string()),
+        ("TODO_COMPLETED", change_set.completed.to_string()),
+        (
+            "TODO_COMMITTED",
+            if committed { "1" } else { "0" }.to_string(),
+        ),
+    ]
+}
+
+/// Counts local commits not yet on `origin/<branch>`, e.g. left behind when a
+/// previous sync committed but its push failed. A missing remote-tracking
+/// branch counts as nothing pending.
+fn commits_ahead_of_origin(config: &AppConfig) -> Result<usize> {
+    let range = format!("origin/{}..HEAD", config.git_branch);
+    let output = run_git(&config.config_dir, ["rev-list", "--count", range.as_str()])?;
+    if !output.status.success() {
+        return Ok(0);
+    }
+    let count = String::from_utf8_lossy(&output.stdout);
+    count
+        .trim()
+        .parse()
+        .with_context(|| format!("unexpected git rev-list output `{}`", count.trim()))
+}
+
+/// Builds the content committed by `sync --only`: `previous` with just the line
+/// for `id` taken from `current`. An edited todo keeps its previous position,
+/// an added one is appended, and a deleted one is dropped. All other local
+/// edits stay uncommitted in the working tree for a later sync.
+fn scoped_content(previous: &str, current: &str, id: Uuid) -> String {
+    let current_line = current.lines().find(|line| line_todo_id(line) == Some(id));
+
+    let mut replaced = false;
+    let mut lines = Vec::new();
+    for line in previous.lines() {
+        if line_todo_id(line) != Some(id) {
+            lines.push(line.to_string());
+            continue;
+        }
+        replaced = true;
+        if let Some(current_line) = current_line {
+            lines.push(current_line.to_string());
+        }
+    }
+
+    if !replaced && let Some(current_line) = current_line {
+        lines.push(current_line.to_string());
+    }
+
+    let mut scoped = lines.join("\n");
+    if !scoped.is_empty() && (previous.ends_with('\n') || previous.is_empty()) {
+        scoped.push('\n');
+    }
+    scoped
+}
+
+/// Writes `content` as the staged version of `rel_path` without touching the
+/// working tree copy.
+fn stage_content(repo_dir: &Path, rel_path: &str, content: &str) -> Result<()> {
+    let mut child = Command::new("git")
+        .args(["hash-object", "-w", "--stdin"])
+        .current_dir(repo_dir)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .with_context(|| format!("failed to execute git in {}", repo_dir.display()))?;
+    child
+        .stdin
+        .take()
+        .context("git hash-object stdin unavailable")?
+        .write_all(content.as_bytes())
+        .context("failed to write to git hash-object")?;
+    let output = child
+        .wait_with_output()
+        .context("failed to wait for git hash-object")?;
+    if !output.status.success() {
+        bail!(
+            "git hash-object failed\nstderr:\n{}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+
+    let blob = String::from_utf8_lossy(&output.stdout).trim().to_string();
+    let cacheinfo = format!("100644,{blob},{rel_path}");
+    run_git_checked(
+        repo_dir,
+        ["update-index", "--add", "--cacheinfo", cacheinfo.as_str()],
+    )?;
+    Ok(())
+}
+
+/// todo.md content after the git-free part of a sync: ids assigned, lines
+/// normalized and validated. Shared by `sync` and `save`.
+#[derive(Debug, Clone)]
+pub struct NormalizedContent {
+    pub content: String,
+    /// Todo lines that were given an id.
+    pub assigned_ids: usize,
+    /// Lines rewritten into canonical form, beyond getting an id.
+    pub reformatted_lines: usize,
+    /// Invalid lines left verbatim because `skip_invalid` was set.
+    pub skipped: Vec<String>,
+}
+
+/// Hydrates ids, formats and validates `content` without touching disk or
+/// git. Fails on lines that cannot be fixed unless `skip_invalid` allows
+/// leaving them as they are.
+pub fn normalize_content(content: &str, skip_invalid: bool) -> Result<NormalizedContent> {
+    let (hydrated_content, assigned_ids, hydrate_issues) = hydrate_todo_ids(content);
+    if !hydrate_issues.is_empty() && !skip_invalid {
         let details = hydrate_issues
             .iter()
             .take(8)
@@ -165,13 +753,9 @@ pub fn sync(config: &AppConfig) -> Result<SyncResult> {
             details
         );
     }
-    if hydrated_count > 0 {
-        write_todo_file_atomic(&config.todo_file, &hydrated_content)?;
-        current = read_todo_file(&config.todo_file)?;
-    }
 
-    let (formatted_content, format_issues) = format_todo_content(&current.content);
-    if !format_issues.is_empty() {
+    let (formatted_content, format_issues) = format_todo_content(&hydrated_content);
+    if !format_issues.is_empty() && !skip_invalid {
         let details = format_issues
             .iter()
             .take(8)
@@ -183,57 +767,62 @@ pub fn sync(config: &AppConfig) -> Result<SyncResult> {
             details
         );
     }
-    if formatted_content != current.content {
-        write_todo_file_atomic(&config.todo_file, &formatted_content)?;
-        current = read_todo_file(&config.todo_file)?;
-    }
-
-    let validation_issues = validate_todo_content(&current.content);
-    if !validation_issues.is_empty() {
-        let details = validation_issues
+    let reformatted_lines = hydrated_content
+        .lines()
+        .zip(formatted_content.lines())
+        .filter(|(before, after)| before != after)
+        .count();
+
+    let (blocking_issues, skipped_issues) =
+        partition_issues(validate_todo_issues(&formatted_content), skip_invalid);
+    if !blocking_issues.is_empty() {
+        let details = blocking_issues
             .iter()
             .take(8)
-            .cloned()
+            .map(ToString::to_string)
             .collect::<Vec<_>>()
             .join("\n");
-        bail!(
-            "todo.md has invalid content; run `todo_md doctor` and fix issues before sync\n{}",
-            details
-        );
+        let hint = if blocking_issues
+            .iter()
+            .any(|issue| issue.kind == IssueKind::ConflictMarker)
+        {
+            "run `todo_md resolve --interactive` to merge the conflict"
+        } else {
+            "run `todo_md doctor` and fix issues before sync"
+        };
+        bail!("todo.md has invalid content; {hint}\n{details}");
     }
 
-    let previous = parse_todo_content(&previous_content);
-
-    let change_set = semantic_changes(&previous, &current);
-    let line_summary = line_diff_summary(&previous.content, &current.content);
-
-    let todo_status = run_git_checked(
-        &config.config_dir,
-        ["status", "--porcelain", "--", todo_rel.as_str()],
-    )?;
+    Ok(NormalizedContent {
+        content: formatted_content,
+        assigned_ids,
+        reformatted_lines,
+        skipped: skipped_issues.iter().map(ToString::to_string).collect(),
+    })
+}
 
-    if todo_status.trim().is_empty() {
-        return Ok(SyncResult {
-            committed: false,
-            change_set,
-            line_summary,
-        });
+/// Normalizes todo.md in place. The file is rewritten only after the
+/// hydrated content validates, so a sync that bails leaves it as it was.
+fn normalize_todo_file(todo_file: &Path, skip_invalid: bool) -> Result<NormalizedContent> {
+    let original = read_todo_file(todo_file)?;
+    let normalized = normalize_content(&original.content, skip_invalid)?;
+    if normalized.content != original.content {
+        write_todo_file_checked(todo_file, &original, &normalized.content)?;
     }
+    Ok(normalized)
+}
 
-    run_git_checked(&config.config_dir, ["add", "--", todo_rel.as_str()])?;
-
-    let message = commit_message(&change_set, &line_summary);
-    run_git_commit(config, &message)?;
-    run_git_checked(
-        &config.config_dir,
-        ["push", "-u", remote, config.git_branch.as_str()],
-    )?;
-
-    Ok(SyncResult {
-        committed: true,
-        change_set,
-        line_summary,
-    })
+/// Splits validation issues into (blocking, skipped).
+fn partition_issues(
+    issues: Vec<TodoIssue>,
+    skip_invalid: bool,
+) -> (Vec<TodoIssue>, Vec<TodoIssue>) {
+    if !skip_invalid {
+        return (issues, Vec::new());
+    }
+    issues
+        .into_iter()
+        .partition(|issue| !issue.kind.is_skippable())
 }
 
 fn pull_with_recovery(config: &AppConfig, todo_rel: &str) -> Result<()> {
@@ -253,6 +842,9 @@ fn pull_with_recovery(config: &AppConfig, todo_rel: &str) -> Result<()> {
     let stderr = String::from_utf8_lossy(&first.stderr).to_string();
     if !is_untracked_overwrite_pull_error(&stderr) {
         let stdout = String::from_utf8_lossy(&first.stdout);
+        if stdout.contains(&format!("Merge conflict in {todo_rel}")) {
+            return merge_pull_conflicts(config, todo_rel);
+        }
         bail!(
             "git {} failed\nstdout:\n{}\nstderr:\n{}",
             args.join(" "),
@@ -289,6 +881,39 @@ fn pull_with_recovery(config: &AppConfig, todo_rel: &str) -> Result<()> {
     )
 }
 
+/// `pull --rebase` stopped on todo.md: merges it by id, letting the newer
+/// side win fields both sides changed (see [`merge::resolve_conflicts`]), and
+/// continues the rebase. A todo that still diverges, e.g. edited on one side
+/// and deleted on the other, leaves the conflict for `resolve --interactive`.
+fn merge_pull_conflicts(config: &AppConfig, todo_rel: &str) -> Result<()> {
+    loop {
+        let newer = merge::newer_side(config)?;
+        let result = merge::resolve_conflicts(config, Some(newer), |conflict| {
+            bail!(
+                "todo {} was edited on one side and deleted on the other",
+                conflict.id
+            )
+        })
+        .map_err(|error| {
+            anyhow::anyhow!(
+                "pulling stopped on a conflict in {todo_rel} ({error}); run `todo_md resolve --interactive`, then sync again"
+            )
+        })?;
+        if !result.still_rebasing {
+            return Ok(());
+        }
+        let unmerged = run_git_checked(
+            &config.config_dir,
+            ["ls-files", "--unmerged", "--", todo_rel],
+        )?;
+        if unmerged.trim().is_empty() {
+            bail!(
+                "pulling stopped on a conflict outside {todo_rel}; resolve it with git, then sync again"
+            );
+        }
+    }
+}
+
 fn is_untracked_overwrite_pull_error(stderr: &str) -> bool {
     let s = stderr.to_ascii_lowercase();
     s.contains("untracked working tree files would be overwritten by merge")
@@ -333,6 +958,29 @@ fn backup_untracked_if_present(repo_dir: &Path, rel_path: &str) -> Result<bool>
     Ok(true)
 }
 
+/// The URL git has for `origin`, or `None` when there is no repo or no origin.
+pub fn origin_url(config: &AppConfig) -> Result<Option<String>> {
+    if !config.config_dir.join(".git").exists() {
+        return Ok(None);
+    }
+    let output = run_git(&config.config_dir, ["remote", "get-url", "origin"])?;
+    if !output.status.success() {
+        return Ok(None);
+    }
+    let url = String::from_utf8_lossy(&output.stdout).trim().to_string();
+    Ok((!url.is_empty()).then_some(url))
+}
+
+/// Whether two remote URLs name different repositories, ignoring a trailing
+/// slash or `.git` suffix.
+pub fn remotes_differ(configured: &str, origin: &str) -> bool {
+    let normalize = |url: &str| {
+        let url = url.trim().trim_end_matches('/');
+        url.strip_suffix(".git").unwrap_or(url).to_string()
+    };
+    normalize(configured) != normalize(origin)
+}
+
 fn ensure_remote(repo_dir: &Path, name: &str, url: &str) -> Result<()> {
     let list = run_git_checked(repo_dir, ["remote"])?;
     if list.lines().any(|line| line.trim() == name) {
@@ -343,19 +991,60 @@ fn ensure_remote(repo_dir: &Path, name: &str, url: &str) -> Result<()> {
     Ok(())
 }
 
-fn run_git_commit(config: &AppConfig, message: &str) -> Result<()> {
+/// Whether `HEAD` is a sync commit not yet on `origin/<branch>`.
+fn can_amend_head(repo_dir: &Path, branch: &str) -> Result<bool> {
+    let remote_ref = format!("refs/remotes/origin/{branch}");
+    if !run_git(
+        repo_dir,
+        ["rev-parse", "--verify", "--quiet", remote_ref.as_str()],
+    )?
+    .status
+    .success()
+    {
+        return Ok(false);
+    }
+    let Ok(parents) = run_git_checked(repo_dir, ["rev-list", "--parents", "-n", "1", "HEAD"])
+    else {
+        return Ok(false);
+    };
+    if parents.split_whitespace().count() != 2 {
+        return Ok(false);
+    }
+    let subject = run_git_checked(repo_dir, ["log", "-1", "--format=%s", "HEAD"])?;
+    if !subject.starts_with(SYNC_SUBJECT_PREFIX) {
+        return Ok(false);
+    }
+    let pushed = run_git(
+        repo_dir,
+        ["merge-base", "--is-ancestor", "HEAD", remote_ref.as_str()],
+    )?
+    .status
+    .success();
+    Ok(!pushed)
+}
+
+/// Commits with `message` as the subject line and, when non-empty, `body` as
+/// a separate paragraph.
+fn run_git_commit(
+    config: &AppConfig,
+    message: &str,
+    body: &str,
+    date: Option<DateTime<Utc>>,
+    amend: bool,
+) -> Result<()> {
     let mut command = Command::new("git");
     command
         .args(["commit", "-m", message])
         .current_dir(&config.config_dir);
-
-    if let Some(name) = &config.git_author_name {
-        command.env("GIT_AUTHOR_NAME", name);
-        command.env("GIT_COMMITTER_NAME", name);
+    if amend {
+        command.arg("--amend");
     }
-    if let Some(email) = &config.git_author_email {
-        command.env("GIT_AUTHOR_EMAIL", email);
-        command.env("GIT_COMMITTER_EMAIL", email);
+    if !body.is_empty() {
+        command.args(["-m", body]);
+    }
+    apply_git_identity(&mut command, config);
+    if let Some(date) = date {
+        command.envs(commit_date_env(date));
     }
 
     let output = command.output().context("failed to execute git commit")?;
@@ -372,7 +1061,20 @@ fn run_git_commit(config: &AppConfig, message: &str) -> Result<()> {
     );
 }
 
-fn run_git_checked<const N: usize>(repo_dir: &Path, args: [&str; N]) -> Result<String> {
+/// Commits made by todo_md use the configured author for both author and
+/// committer, falling back to git's own config when unset.
+pub(crate) fn apply_git_identity(command: &mut Command, config: &AppConfig) {
+    if let Some(name) = &config.git_author_name {
+        command.env("GIT_AUTHOR_NAME", name);
+        command.env("GIT_COMMITTER_NAME", name);
+    }
+    if let Some(email) = &config.git_author_email {
+        command.env("GIT_AUTHOR_EMAIL", email);
+        command.env("GIT_COMMITTER_EMAIL", email);
+    }
+}
+
+pub(crate) fn run_git_checked<const N: usize>(repo_dir: &Path, args: [&str; N]) -> Result<String> {
     let output = run_git(repo_dir, args)?;
     if output.status.success() {
         return Ok(String::from_utf8_lossy(&output.stdout).to_string());
@@ -388,7 +1090,7 @@ fn run_git_checked<const N: usize>(repo_dir: &Path, args: [&str; N]) -> Result<S
     );
 }
 
-fn run_git<const N: usize>(repo_dir: &Path, args: [&str; N]) -> Result<Output> {
+pub(crate) fn run_git<const N: usize>(repo_dir: &Path, args: [&str; N]) -> Result<Output> {
     Command::new("git")
         .args(args)
         .current_dir(repo_dir)
@@ -396,6 +1098,14 @@ fn run_git<const N: usize>(repo_dir: &Path, args: [&str; N]) -> Result<Output> {
         .with_context(|| format!("failed to execute git in {}", repo_dir.display()))
 }
 
+fn commit_date_env(date: DateTime<Utc>) -> [(&'static str, String); 2] {
+    let date = date.to_rfc3339_opts(SecondsFormat::Secs, true);
+    [
+        ("GIT_AUTHOR_DATE", date.clone()),
+        ("GIT_COMMITTER_DATE", date),
+    ]
+}
+
 fn run_gh<const N: usize>(config: &AppConfig, args: [&str; N]) -> Result<Output> {
     let mut command = Command::new("gh");
     command.args(args).current_dir(&config.config_dir);
@@ -448,7 +1158,7 @@ fn clean_slug(raw: &str) -> Option<String> {
     Some(format!("{owner}/{repo}"))
 }
 
-fn git_show_or_empty(repo_dir: &Path, object: &str) -> Result<String> {
+pub(crate) fn git_show_or_empty(repo_dir: &Path, object: &str) -> Result<String> {
     let output = run_git(repo_dir, ["show", object])?;
     if output.status.success() {
         return Ok(String::from_utf8_lossy(&output.stdout).to_string());
@@ -456,7 +1166,7 @@ fn git_show_or_empty(repo_dir: &Path, object: &str) -> Result<String> {
     Ok(String::new())
 }
 
-fn todo_path_relative_to_repo(config: &AppConfig) -> Result<String> {
+pub(crate) fn todo_path_relative_to_repo(config: &AppConfig) -> Result<String> {
     let relative = config
         .todo_file
         .strip_prefix(&config.config_dir)
@@ -471,20 +1181,487 @@ fn todo_path_relative_to_repo(config: &AppConfig) -> Result<String> {
     Ok(relative.to_string_lossy().to_string())
 }
 
-fn commit_message(change_set: &ChangeSet, line_summary: &str) -> String {
-    format!(
-        "sync todos: +{} ~{} -{} done {} ({})",
-        change_set.added,
-        change_set.updated,
-        change_set.deleted,
-        change_set.completed,
-        line_summary
-    )
+/// Files versioned alongside `todo.md`: `trash.md`, so a soft-deleted todo can
+/// be restored on another machine, `archive.md`, and the generated
+/// `.env.example`. Returns the repo paths of those with changes.
+fn companion_paths_with_changes(config: &AppConfig) -> Result<Vec<String>> {
+    let companions = [
+        config.trash_file.clone(),
+        config.archive_file.clone(),
+        config.config_dir.join(ENV_EXAMPLE_FILE),
+    ];
+    let mut changed = Vec::new();
+    for path in companions {
+        let Ok(relative) = path.strip_prefix(&config.config_dir) else {
+            continue;
+        };
+        let rel = relative.to_string_lossy().to_string();
+        let status = run_git_checked(
+            &config.config_dir,
+            ["status", "--porcelain", "--", rel.as_str()],
+        )?;
+        if !status.trim().is_empty() {
+            changed.push(rel);
+        }
+    }
+    Ok(changed)
+}
+
+const SYNC_SUBJECT_PREFIX: &str = "sync todos: ";
+
+/// The sync commit subject: `template` (`TODOS_COMMIT_TEMPLATE`) when set,
+/// otherwise the default that `can_amend_head` recognizes.
+fn commit_message(
+    change_set: &ChangeSet,
+    line_summary: &str,
+    template: Option<&str>,
+) -> Result<String> {
+    match template {
+        Some(template) => render_commit_template(template, change_set, line_summary),
+        None => Ok(format!(
+            "{SYNC_SUBJECT_PREFIX}+{} ~{} -{} done {} ({})",
+            change_set.added,
+            change_set.updated,
+            change_set.deleted,
+            change_set.completed,
+            line_summary
+        )),
+    }
+}
+
+/// Whether `subject` is one `sync` writes: the default `sync todos: ...`
+/// subject, or `template` (`TODOS_COMMIT_TEMPLATE`) with any text in place of
+/// its placeholders.
+pub fn is_sync_subject(subject: &str, template: Option<&str>) -> bool {
+    subject.starts_with(SYNC_SUBJECT_PREFIX)
+        || template.is_some_and(|template| matches_template(subject, template))
+}
+
+fn matches_template(subject: &str, template: &str) -> bool {
+    let mut literals = Vec::new();
+    let mut rest = template;
+    while let Some(start) = rest.find('{') {
+        let Some(len) = rest[start..].find('}') else {
+            return false;
+        };
+        literals.push(&rest[..start]);
+        rest = &rest[start + len + 1..];
+    }
+    literals.push(rest);
+
+    let [first, middle @ .., last] = literals.as_slice() else {
+        return subject == template;
+    };
+    let Some(mut remaining) = subject
+        .strip_prefix(first)
+        .and_then(|remaining| remaining.strip_suffix(last))
+    else {
+        return false;
+    };
+    for literal in middle {
+        match remaining.find(literal) {
+            Some(idx) => remaining = &remaining[idx + literal.len()..],
+            None => return false,
+        }
+    }
+    true
+}
+
+/// What `undo` reverted.
+#[derive(Debug, Clone)]
+pub struct UndoResult {
+    /// Subject of the sync commit that was reverted.
+    pub subject: String,
+    /// Todo changes the revert made to the working copy.
+    pub change_set: ChangeSet,
+}
+
+/// Reverts `HEAD` with a new commit when it is a sync commit (see
+/// [`is_sync_subject`]). Refuses when todo.md has uncommitted edits, so they
+/// are never clobbered. The revert is pushed by the next sync.
+pub fn undo_last_sync(config: &AppConfig) -> Result<UndoResult> {
+    let repo_dir = &config.config_dir;
+    if !repo_dir.join(".git").exists() {
+        bail!(
+            "{} is not a git repository; nothing to undo",
+            repo_dir.display()
+        );
+    }
+    let todo_rel = todo_path_relative_to_repo(config)?;
+    let dirty = run_git_checked(repo_dir, ["status", "--porcelain", "--", todo_rel.as_str()])?;
+    if !dirty.trim().is_empty() {
+        bail!("{todo_rel} has uncommitted changes; sync or discard them before undoing");
+    }
+
+    let subject = run_git_checked(repo_dir, ["log", "-1", "--format=%s", "HEAD"])?
+        .trim()
+        .to_string();
+    if !is_sync_subject(&subject, config.commit_template.as_deref()) {
+        bail!("HEAD is not a sync commit (`{subject}`); nothing to undo");
+    }
+    let parents = run_git_checked(repo_dir, ["rev-list", "--parents", "-n", "1", "HEAD"])?;
+    if parents.split_whitespace().count() != 2 {
+        bail!("HEAD (`{subject}`) is a merge or root commit; revert it by hand");
+    }
+
+    let before = parse_todo_content(&git_show_or_empty(repo_dir, &format!("HEAD:{todo_rel}"))?);
+    let mut command = Command::new("git");
+    command
+        .args(["revert", "--no-edit", "HEAD"])
+        .current_dir(repo_dir);
+    apply_git_identity(&mut command, config);
+    let output = command.output().context("failed to execute git revert")?;
+    if !output.status.success() {
+        let _ = run_git(repo_dir, ["revert", "--abort"]);
+        bail!(
+            "git revert failed\nstderr:\n{}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+
+    let after = read_todo_file(&config.todo_file)?;
+    Ok(UndoResult {
+        subject,
+        change_set: semantic_changes(&before, &after),
+    })
+}
+
+/// Fills `{added}`, `{updated}`, `{deleted}`, `{completed}` and
+/// `{line_summary}` in a commit template. Any other `{...}` is an error, so a
+/// typo never reaches the history as literal text.
+pub fn render_commit_template(
+    template: &str,
+    change_set: &ChangeSet,
+    line_summary: &str,
+) -> Result<String> {
+    let mut out = String::new();
+    let mut rest = template;
+    while let Some(start) = rest.find('{') {
+        out.push_str(&rest[..start]);
+        let Some(len) = rest[start..].find('}') else {
+            bail!("TODOS_COMMIT_TEMPLATE has an unclosed `{{` in `{template}`");
+        };
+        let value = match &rest[start + 1..start + len] {
+            "added" => change_set.added.to_string(),
+            "updated" => change_set.updated.to_string(),
+            "deleted" => change_set.deleted.to_string(),
+            "completed" => change_set.completed.to_string(),
+            "line_summary" => line_summary.to_string(),
+            other => bail!(
+                "TODOS_COMMIT_TEMPLATE has unknown placeholder `{{{other}}}`; use {{added}}, {{updated}}, {{deleted}}, {{completed}} or {{line_summary}}"
+            ),
+        };
+        out.push_str(&value);
+        rest = &rest[start + len + 1..];
+    }
+    out.push_str(rest);
+    Ok(out)
+}
+
+/// One line per change, e.g. `+ added: <name>` or `✓ completed: <name>`.
+/// Names come from the committed content, or the previous one for deletions.
+fn commit_body(
+    change_set: &ChangeSet,
+    previous: &ParsedTodoFile,
+    current: &ParsedTodoFile,
+) -> String {
+    change_set
+        .sorted_changes()
+        .into_iter()
+        .map(|change| {
+            let (marker, source) = match change.kind {
+                ChangeKind::Added => ("+", current),
+                ChangeKind::Completed => ("✓", current),
+                ChangeKind::Updated => ("~", current),
+                ChangeKind::Deleted => ("-", previous),
+            };
+            let name = source
+                .todos_by_id
+                .get(&change.id)
+                .map_or_else(|| change.id.to_string(), |todo| todo.name().to_string());
+            format!("{marker} {}: {name}", change.kind.as_str())
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::date_parser::pin_home_zone;
+
+    #[test]
+    fn json_result_carries_counts_changes_and_line_summary() {
+        let previous = parse_todo_content(
+            "- [_] Pay rent (id: 123e4567-e89b-12d3-a456-426614174000)\n\
+             - [_] Old idea (id: 123e4567-e89b-12d3-a456-426614174001)\n",
+        );
+        let current = parse_todo_content(
+            "- [x] Pay rent (id: 123e4567-e89b-12d3-a456-426614174000)\n\
+             - [_] Book flights (id: 123e4567-e89b-12d3-a456-426614174002)\n",
+        );
+        let result = SyncResult {
+            committed: true,
+            change_set: semantic_changes(&previous, &current),
+            line_summary: "line diff (+2/-2)".to_string(),
+            skipped: Vec::new(),
+            pushed_pending: 0,
+            amended: false,
+            hook_warning: None,
+            env_warning: None,
+            incoming: None,
+        };
+
+        let value: serde_json::Value =
+            serde_json::from_str(&result.to_json().expect("serialize")).expect("valid json");
+        assert_eq!(value["committed"], true);
+        assert_eq!(value["added"], 1);
+        assert_eq!(value["updated"], 0);
+        assert_eq!(value["deleted"], 1);
+        assert_eq!(value["completed"], 1);
+        assert_eq!(value["line_summary"], "line diff (+2/-2)");
+        let changes = value["changes"]
+            .as_array()
+            .expect("changes array")
+            .iter()
+            .map(|change| {
+                (
+                    change["kind"].as_str().expect("kind"),
+                    change["id"].as_str().expect("id"),
+                )
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(
+            changes,
+            vec![
+                ("added", "123e4567-e89b-12d3-a456-426614174002"),
+                ("completed", "123e4567-e89b-12d3-a456-426614174000"),
+                ("deleted", "123e4567-e89b-12d3-a456-426614174001"),
+            ]
+        );
+        assert_eq!(value["changes"][1]["fields"], serde_json::json!(["done"]));
+    }
+
+    #[test]
+    fn commit_message_has_summary_subject_and_per_change_body() {
+        let previous = parse_todo_content(
+            "- [_] Pay rent (id: 123e4567-e89b-12d3-a456-426614174000)\n\
+             - [_] Old idea (id: 123e4567-e89b-12d3-a456-426614174001)\n",
+        );
+        let current = parse_todo_content(
+            "- [x] Pay rent (id: 123e4567-e89b-12d3-a456-426614174000)\n\
+             - [_] Book flights (id: 123e4567-e89b-12d3-a456-426614174002)\n",
+        );
+        let change_set = semantic_changes(&previous, &current);
+
+        assert_eq!(
+            commit_message(&change_set, "line diff (+2/-2)", None).expect("default"),
+            "sync todos: +1 ~0 -1 done 1 (line diff (+2/-2))"
+        );
+        assert_eq!(
+            commit_message(
+                &change_set,
+                "line diff (+2/-2)",
+                Some("todos: {added} new, {updated} edited, {deleted} gone, {completed} done [{line_summary}]"),
+            )
+            .expect("custom template"),
+            "todos: 1 new, 0 edited, 1 gone, 1 done [line diff (+2/-2)]"
+        );
+        assert_eq!(
+            commit_body(&change_set, &previous, &current),
+            "+ added: Book flights\n✓ completed: Pay rent\n- deleted: Old idea"
+        );
+    }
+
+    #[test]
+    fn recognises_sync_subjects_with_and_without_a_template() {
+        assert!(is_sync_subject(
+            "sync todos: +1 ~0 -0 done 0 (line diff (+1/-0))",
+            None
+        ));
+        assert!(!is_sync_subject("Revert \"sync todos: +1 ~0 -0\"", None));
+        assert!(!is_sync_subject("edit todo.md by hand", None));
+
+        let template = Some("todos: {added} new, {completed} done [{line_summary}]");
+        assert!(is_sync_subject("todos: 2 new, 0 done [+2/-0]", template));
+        assert!(is_sync_subject(
+            "sync todos: +1 ~0 -0 done 0 (line diff (+1/-0))",
+            template
+        ));
+        assert!(!is_sync_subject("todos: 2 new", template));
+        assert!(!is_sync_subject(
+            "my todos: 2 new, 0 done [+2/-0]",
+            template
+        ));
+        assert!(is_sync_subject("nightly sync", Some("nightly sync")));
+    }
+
+    #[test]
+    fn commit_template_rejects_unknown_placeholders() {
+        let change_set = ChangeSet::default();
+        let error =
+            render_commit_template("sync {addded}", &change_set, "").expect_err("typo must fail");
+        assert!(error.to_string().contains("unknown placeholder `{addded}`"));
+        assert!(render_commit_template("sync {added", &change_set, "").is_err());
+    }
+
+    #[test]
+    fn unchanged_content_takes_the_fast_path() {
+        let temp_dir =
+            std::env::temp_dir().join(format!("todo_md_last_sync_{}", std::process::id()));
+        let _ = fs::create_dir_all(&temp_dir);
+        let marker = temp_dir.join("todo_md-last-sync");
+        let _ = fs::remove_file(&marker);
+        let content = "- [_] Pay rent (id: 123e4567-e89b-12d3-a456-426614174000)\n";
+
+        assert!(!unchanged_since_last_sync(&marker, content));
+        record_synced_content(&marker, content).expect("record");
+        assert!(unchanged_since_last_sync(&marker, content));
+        assert!(!unchanged_since_last_sync(
+            &marker,
+            "- [x] Pay rent (id: 123e4567-e89b-12d3-a456-426614174000)\n"
+        ));
+        assert_eq!(content_hash(""), "cbf29ce484222325");
+    }
+
+    #[test]
+    fn failed_validation_leaves_the_file_unhydrated() {
+        let temp_dir =
+            std::env::temp_dir().join(format!("todo_md_normalize_{}", std::process::id()));
+        let _ = fs::create_dir_all(&temp_dir);
+        let path = temp_dir.join("todo.md");
+        // The first line would get an id, but the duplicate ids fail validation.
+        let invalid = "- [_] Fresh task\n\
+                       - [_] Pay rent (id: 123e4567-e89b-12d3-a456-426614174000)\n\
+                       - [_] Pay rent again (id: 123e4567-e89b-12d3-a456-426614174000)\n";
+        fs::write(&path, invalid).expect("write todo file");
+
+        normalize_todo_file(&path, false).expect_err("duplicate ids block");
+        assert_eq!(fs::read_to_string(&path).expect("read"), invalid);
+
+        let valid = "- [_] Fresh task\n\
+                     - [_] Pay rent (id: 123e4567-e89b-12d3-a456-426614174000)\n";
+        fs::write(&path, valid).expect("write todo file");
+        let normalized = normalize_todo_file(&path, false).expect("valid content");
+        assert_eq!(normalized.assigned_ids, 1);
+        assert_eq!(fs::read_to_string(&path).expect("read"), normalized.content);
+
+        let _ = fs::remove_dir_all(temp_dir);
+    }
+
+    #[test]
+    fn lists_branch_names_from_ls_remote_heads() {
+        let output = "0123456789abcdef0123456789abcdef01234567\trefs/heads/main\n\
+                      89abcdef0123456789abcdef0123456789abcdef\trefs/heads/feature/old-todos\n";
+        assert_eq!(
+            parse_ls_remote_heads(output),
+            vec!["main".to_string(), "feature/old-todos".to_string()]
+        );
+        assert!(parse_ls_remote_heads("").is_empty());
+    }
+
+    #[test]
+    fn reads_default_branch_from_ls_remote_symref() {
+        let output =
+            "ref: refs/heads/master\tHEAD\n0123456789abcdef0123456789abcdef01234567\tHEAD\n";
+        assert_eq!(parse_symref_head(output), Some("master".to_string()));
+        assert_eq!(parse_symref_head(""), None);
+        assert_eq!(
+            parse_symref_head("0123456789abcdef0123456789abcdef01234567\tHEAD\n"),
+            None
+        );
+    }
+
+    #[test]
+    fn commit_date_sets_author_and_committer_dates() {
+        let date = DateTime::parse_from_rfc3339("2024-05-01T09:30:00+02:00")
+            .expect("valid date")
+            .with_timezone(&Utc);
+        assert_eq!(
+            commit_date_env(date),
+            [
+                ("GIT_AUTHOR_DATE", "2024-05-01T07:30:00Z".to_string()),
+                ("GIT_COMMITTER_DATE", "2024-05-01T07:30:00Z".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn post_sync_hook_env_carries_change_counts() {
+        let change_set = ChangeSet {
+            added: 2,
+            completed: 1,
+            ..ChangeSet::default()
+        };
+        let env = post_sync_hook_env(&change_set, true);
+
+        assert!(env.contains(&("TODO_ADDED", "2".to_string())));
+        assert!(env.contains(&("TODO_COMPLETED", "1".to_string())));
+        assert!(env.contains(&("TODO_DELETED", "0".to_string())));
+        assert!(env.contains(&("TODO_COMMITTED", "1".to_string())));
+    }
+
+    #[test]
+    fn failing_pre_sync_hook_aborts_before_any_commit() {
+        let repo = std::env::temp_dir().join(format!("todo_md_pre_hook_{}", std::process::id()));
+        let _ = fs::remove_dir_all(&repo);
+        fs::create_dir_all(&repo).expect("create repo dir");
+        let todo_file = repo.join("todo.md");
+        fs::write(
+            &todo_file,
+            "- [_] A (id: 123e4567-e89b-12d3-a456-426614174000)\n",
+        )
+        .expect("write todo.md");
+        Command::new("git")
+            .args(["init", "-q"])
+            .current_dir(&repo)
+            .status()
+            .expect("git init");
+
+        run_pre_sync_hook(
+            &repo,
+            "test -f \"$TODO_FILE\" && pwd > hook_ran",
+            &todo_file,
+        )
+        .expect("passing hook");
+        assert!(repo.join("hook_ran").exists());
+
+        let error = run_pre_sync_hook(&repo, "echo nope >&2; exit 3", &todo_file)
+            .expect_err("failing hook");
+        assert!(error.to_string().contains("nothing was committed"));
+        assert!(error.to_string().contains("nope"));
+        let commits = run_git(&repo, ["rev-list", "--all", "--count"]).expect("rev-list");
+        assert_eq!(String::from_utf8_lossy(&commits.stdout).trim(), "0");
+
+        let _ = fs::remove_dir_all(&repo);
+    }
+
+    #[test]
+    fn remotes_differ_ignores_git_suffix_and_trailing_slash() {
+        assert!(!remotes_differ(
+            "git@github.com:me/todos.git",
+            "git@github.com:me/todos"
+        ));
+        assert!(!remotes_differ("/tmp/remote.git/", "/tmp/remote.git"));
+        assert!(remotes_differ(
+            "git@github.com:me/todos.git",
+            "git@github.com:me/other.git"
+        ));
+    }
+
+    #[test]
+    fn local_setup_wires_no_remote() {
+        let github = "git@github.com:someone/todos.git";
+        assert_eq!(setup_remote(None, Some(github), true), None);
+        assert_eq!(setup_remote(None, None, true), None);
+        assert_eq!(setup_remote(None, Some(github), false), Some(github));
+        // A later `setup <remote-url>` attaches the remote to the local repo.
+        assert_eq!(
+            setup_remote(Some("https://example.com/todos.git"), Some(github), false),
+            Some("https://example.com/todos.git")
+        );
+        assert_eq!(setup_remote(None, None, false), None);
+    }
 
     #[test]
     fn parses_github_slugs_from_common_urls() {
@@ -516,6 +1693,158 @@ mod tests {
         assert!(is_untracked_overwrite_pull_error(stderr));
     }
 
+    #[test]
+    fn skip_invalid_never_skips_conflict_markers() {
+        let content =
+            "<<<<<<< HEAD\n- [_] No id\n- [?] Broken (id: 123e4567-e89b-12d3-a456-426614174000)\n";
+        let issues = crate::storage::validate_todo_issues(content);
+
+        let (blocking, skipped) = partition_issues(issues.clone(), true);
+        assert_eq!(blocking.len(), 1);
+        assert!(blocking[0].message.contains("conflict marker"));
+        assert_eq!(skipped.len(), 2);
+
+        let (blocking, skipped) = partition_issues(issues, false);
+        assert_eq!(blocking.len(), 3);
+        assert!(skipped.is_empty());
+    }
+
+    #[test]
+    fn scoped_content_applies_only_the_selected_change() {
+        let previous = "# Todos\n- [_] A (id: 123e4567-e89b-12d3-a456-426614174000)\n- [_] B (id: 123e4567-e89b-12d3-a456-426614174001)\n";
+        let current = "# Todos\n- [x] A (id: 123e4567-e89b-12d3-a456-426614174000)\n- [_] B renamed (id: 123e4567-e89b-12d3-a456-426614174001)\n- [_] C (id: 123e4567-e89b-12d3-a456-426614174002)\n";
+
+        let a = Uuid::parse_str("123e4567-e89b-12d3-a456-426614174000").expect("id");
+        assert_eq!(
+            scoped_content(previous, current, a),
+            "# Todos\n- [x] A (id: 123e4567-e89b-12d3-a456-426614174000)\n- [_] B (id: 123e4567-e89b-12d3-a456-426614174001)\n"
+        );
+
+        let c = Uuid::parse_str("123e4567-e89b-12d3-a456-426614174002").expect("id");
+        assert!(scoped_content(previous, current, c)
+            .ends_with("- [_] B (id: 123e4567-e89b-12d3-a456-426614174001)\n- [_] C (id: 123e4567-e89b-12d3-a456-426614174002)\n"));
+    }
+
+    #[test]
+    fn scoped_content_drops_deleted_todo() {
+        let previous = "- [_] A (id: 123e4567-e89b-12d3-a456-426614174000)\n- [_] B (id: 123e4567-e89b-12d3-a456-426614174001)\n";
+        let current = "- [_] A edited (id: 123e4567-e89b-12d3-a456-426614174000)\n";
+
+        let b = Uuid::parse_str("123e4567-e89b-12d3-a456-426614174001").expect("id");
+        assert_eq!(
+            scoped_content(previous, current, b),
+            "- [_] A (id: 123e4567-e89b-12d3-a456-426614174000)\n"
+        );
+    }
+
+    #[test]
+    fn refuses_staged_env_and_warns_when_tracked() {
+        let repo = std::env::temp_dir().join(format!("todo_md_env_guard_{}", std::process::id()));
+        let _ = fs::remove_dir_all(&repo);
+        fs::create_dir_all(&repo).expect("create repo dir");
+        let git = |args: &[&str]| {
+            let output = Command::new("git")
+                .args(["-c", "user.name=t", "-c", "user.email=t@t"])
+                .args(args)
+                .current_dir(&repo)
+                .output()
+                .expect("run git");
+            assert!(output.status.success(), "git {args:?} failed");
+        };
+        git(&["init", "-q"]);
+        let env_file = repo.join(".env");
+        fs::write(&env_file, "GITHUB_TOKEN=secret\n").expect("write .env");
+
+        assert_eq!(guard_env_file(&repo, &env_file).expect("untracked"), None);
+
+        git(&["add", "-f", ".env"]);
+        let error = guard_env_file(&repo, &env_file).expect_err("staged .env");
+        assert!(error.to_string().contains("git rm --cached .env"));
+
+        git(&["commit", "-q", "-m", "oops"]);
+        let warning = guard_env_file(&repo, &env_file)
+            .expect("tracked but not staged")
+            .expect("warning");
+        assert!(warning.contains(".env is tracked by git"));
+
+        let _ = fs::remove_dir_all(&repo);
+    }
+
+    #[test]
+    fn amends_only_unpushed_sync_commits() {
+        let root = std::env::temp_dir().join(format!("todo_md_amend_{}", std::process::id()));
+        let _ = fs::remove_dir_all(&root);
+        let (remote, repo) = (root.join("remote.git"), root.join("repo"));
+        fs::create_dir_all(&repo).expect("create repo dir");
+        let git = |args: &[&str]| {
+            let output = Command::new("git")
+                .args(["-c", "user.name=t", "-c", "user.email=t@t"])
+                .args(args)
+                .current_dir(&repo)
+                .output()
+                .expect("run git");
+            assert!(output.status.success(), "git {args:?} failed");
+        };
+        let commit = |subject: &str| {
+            fs::write(repo.join("todo.md"), subject).expect("write todo.md");
+            git(&["add", "todo.md"]);
+            git(&["commit", "-q", "-m", subject]);
+        };
+        git(&["init", "-q", "--bare", remote.to_str().expect("utf-8 path")]);
+        git(&["init", "-q", "-b", "main"]);
+        git(&[
+            "remote",
+            "add",
+            "origin",
+            remote.to_str().expect("utf-8 path"),
+        ]);
+
+        commit("initial");
+        commit("sync todos: +1 ~0 -0 done 0 (line diff (+1/-0))");
+        // No remote-tracking branch yet: nothing proves the commit is unpushed.
+        assert!(!can_amend_head(&repo, "main").expect("no tracking branch"));
+
+        git(&["push", "-q", "-u", "origin", "main"]);
+        assert!(!can_amend_head(&repo, "main").expect("pushed"));
+
+        commit("sync todos: +0 ~1 -0 done 0 (line diff (+1/-1))");
+        assert!(can_amend_head(&repo, "main").expect("unpushed sync commit"));
+
+        commit("hand-written change");
+        assert!(!can_amend_head(&repo, "main").expect("not a sync commit"));
+
+        let _ = fs::remove_dir_all(&root);
+    }
+
+    #[test]
+    fn normalize_content_assigns_ids_and_formats_without_git() {
+        let _zone = pin_home_zone(chrono_tz::UTC);
+        let content = "# Todos\n\
+                       - [ ] New idea\n\
+                       - [_] Pay rent (due: 2026-03-01T12:00:00Z) (id: 123e4567-e89b-12d3-a456-426614174000)\n\
+                       - [_] Call mom (id: 123e4567-e89b-12d3-a456-426614174001)\n";
+
+        let normalized = normalize_content(content, false).expect("normalizes");
+        assert_eq!(normalized.assigned_ids, 1);
+        assert_eq!(normalized.reformatted_lines, 1);
+        assert!(normalized.skipped.is_empty());
+        assert!(validate_todo_issues(&normalized.content).is_empty());
+        assert!(normalized
+            .content
+            .contains("Pay rent (due: 2026-03-01 12:00 PM)"));
+
+        let again = normalize_content(&normalized.content, false).expect("idempotent");
+        assert_eq!(again.content, normalized.content);
+        assert_eq!((again.assigned_ids, again.reformatted_lines), (0, 0));
+    }
+
+    #[test]
+    fn normalize_content_rejects_conflicts_even_when_skipping() {
+        let content = "<<<<<<< HEAD\n- [_] A (id: 123e4567-e89b-12d3-a456-426614174000)\n=======\n>>>>>>> other\n";
+        let error = normalize_content(content, true).expect_err("conflict blocks");
+        assert!(error.to_string().contains("resolve --interactive"));
+    }
+
     #[test]
     fn upserts_env_variable_idempotently() {
         let temp_dir = std::env::temp_dir().join(format!("todo_md_test_{}", std::process::id()));
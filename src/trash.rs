@@ -0,0 +1,155 @@
+use anyhow::{bail, Result};
+use chrono::{DateTime, SecondsFormat, Utc};
+use regex::Regex;
+
+use crate::edit::{add_todo, line_todo_id, remove_todo};
+use crate::storage::resolve_id_prefix;
+use crate::types::Todo;
+use uuid::Uuid;
+
+/// Moves the todo `id` out of `todo_content` into `trash_content`, stamped with
+/// its deletion time. Returns the new todo and trash contents and the todo.
+pub fn trash_todo(
+    todo_content: &str,
+    trash_content: &str,
+    id: Uuid,
+    now: DateTime<Utc>,
+) -> Result<(String, String, Todo)> {
+    let (next_todo, todo) = remove_todo(todo_content, id)?;
+    let next_trash = append_to_trash(trash_content, std::slice::from_ref(&todo), now);
+    Ok((next_todo, next_trash, todo))
+}
+
+/// Appends already-removed todos to `trash_content`, stamped with `now`.
+pub fn append_to_trash(trash_content: &str, todos: &[Todo], now: DateTime<Utc>) -> String {
+    let mut next_trash = trash_content.to_string();
+    if !next_trash.is_empty() && !next_trash.ends_with('\n') {
+        next_trash.push('\n');
+    }
+    for todo in todos {
+        next_trash.push_str(&trash_line(todo, now));
+        next_trash.push('\n');
+    }
+    next_trash
+}
+
+/// Moves the trashed todo whose id starts with `prefix` back to the end of
+/// `todo_content`. Fails if a todo with that id already exists there.
+pub fn restore_todo(
+    todo_content: &str,
+    trash_content: &str,
+    prefix: &str,
+) -> Result<(String, String, Todo)> {
+    let entries = trash_entries(trash_content);
+    let id = resolve_id_prefix(entries.iter().map(|entry| entry.todo.id()), prefix)?;
+    if todo_content
+        .lines()
+        .any(|line| line_todo_id(line) == Some(id))
+    {
+        bail!("todo {id} already exists in todo.md; remove it before restoring");
+    }
+
+    let mut restored = None;
+    let mut lines = Vec::new();
+    for line in trash_content.lines() {
+        match parse_trash_line(line) {
+            Some(entry) if restored.is_none() && entry.todo.id() == id => {
+                restored = Some(entry.todo)
+            }
+            _ => lines.push(line),
+        }
+    }
+    let todo = restored.expect("resolved id is in the trash");
+
+    let mut next_trash = lines.join("\n");
+    if !next_trash.is_empty() {
+        next_trash.push('\n');
+    }
+    Ok((add_todo(todo_content, &todo), next_trash, todo))
+}
+
+#[derive(Debug, Clone)]
+pub struct TrashEntry {
+    pub todo: Todo,
+    pub deleted_at: DateTime<Utc>,
+}
+
+pub fn trash_entries(trash_content: &str) -> Vec<TrashEntry> {
+    trash_content.lines().filter_map(parse_trash_line).collect()
+}
+
+/// A trash line is the todo line followed by `(deleted: <rfc3339>)`.
+fn trash_line(todo: &Todo, deleted_at: DateTime<Utc>) -> String {
+    format!(
+        "{} (deleted: {})",
+        todo.to_line(),
+        deleted_at.to_rfc3339_opts(SecondsFormat::Secs, true)
+    )
+}
+
+fn parse_trash_line(line: &str) -> Option<TrashEntry> {
+    let deleted_re =
+        Regex::new(r"^(?P<todo>.+) \(deleted: (?P<at>[^)]+)\)$").expect("valid trash regex");
+    let captures = deleted_re.captures(line.trim())?;
+    let deleted_at = DateTime::parse_from_rfc3339(&captures["at"])
+        .ok()?
+        .with_timezone(&Utc);
+    let todo_line = &captures["todo"];
+    line_todo_id(todo_line)?;
+    Some(TrashEntry {
+        todo: Todo::try_from_str(todo_line).ok()?,
+        deleted_at,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ID: &str = "123e4567-e89b-42d3-a456-426614174000";
+
+    fn now() -> DateTime<Utc> {
+        DateTime::parse_from_rfc3339("2026-02-23T18:00:00Z")
+            .expect("valid now")
+            .with_timezone(&Utc)
+    }
+
+    #[test]
+    fn trashed_todo_can_be_restored() {
+        let todo_content = format!("# Todos\n- [_] Oops (id: {ID})\n");
+        let id = Uuid::parse_str(ID).expect("id");
+
+        let (todo_after_rm, trash, _) = trash_todo(&todo_content, "", id, now()).expect("trash");
+        assert_eq!(todo_after_rm, "# Todos\n");
+        assert_eq!(
+            trash,
+            format!("- [_] Oops (id: {ID}) (deleted: 2026-02-23T18:00:00Z)\n")
+        );
+        let entries = trash_entries(&trash);
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].deleted_at, now());
+
+        let (restored, trash_after, todo) =
+            restore_todo(&todo_after_rm, &trash, "123e").expect("restore");
+        assert_eq!(restored, todo_content);
+        assert_eq!(trash_after, "");
+        assert_eq!(todo.name(), "Oops");
+    }
+
+    #[test]
+    fn restore_refuses_to_duplicate_an_id() {
+        let trash = format!("- [_] Oops (id: {ID}) (deleted: 2026-02-23T18:00:00Z)\n");
+        let todo_content = format!("- [_] Oops again (id: {ID})\n");
+        assert!(restore_todo(&todo_content, &trash, ID).is_err());
+    }
+
+    #[test]
+    fn purge_removes_without_touching_the_trash() {
+        let todo_content = format!("- [_] Gone (id: {ID})\n");
+        let (after, todo) =
+            remove_todo(&todo_content, Uuid::parse_str(ID).expect("id")).expect("purge");
+        assert_eq!(after, "");
+        assert_eq!(todo.name(), "Gone");
+        assert!(trash_entries("").is_empty());
+    }
+}
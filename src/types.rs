@@ -1,7 +1,11 @@
-use crate::date_parser::parse_human_datetime;
-use crate::recurrence_parser::{next_due_date_utc, parse_reccurence};
+use crate::date_parser::{
+    configured_checkbox_style, configured_due_style, configured_recurrence_style,
+    format_relative_due, in_home_zone, parse_human_datetime, DueStyle,
+};
+use crate::recurrence_parser::{parse_reccurence, rollover_due_date, split_recurrence_end};
 use chrono::prelude::*;
 use regex::Regex;
+use std::collections::{HashMap, HashSet};
 use uuid::*;
 
 #[derive(Debug, Clone)]
@@ -9,9 +13,22 @@ pub struct Todo {
     id: Uuid,
     done: bool,
     due_date: Option<DateTime<Utc>>,
+    /// Defer date: the todo is not actionable before this.
+    scheduled: Option<DateTime<Utc>>,
     recurence: Option<Reccurence>,
-    created_at: DateTime<Utc>,
-    updated_at: DateTime<Utc>,
+    /// Written after the rule, e.g. `weekly on monday until 2026-06-01`.
+    recurrence_end: Option<RecurrenceEnd>,
+    /// Reference into another system, e.g. `JIRA-123`, from `(ext: ...)`.
+    /// The UUID stays the canonical key.
+    external_id: Option<String>,
+    priority: Option<Priority>,
+    /// Indented lines under the todo, trimmed. They are stored beneath the
+    /// todo line rather than in it, so `to_line` leaves them out.
+    notes: Vec<String>,
+    /// From `(created: ...)` and `(updated: ...)`; `None` for lines written
+    /// before these were stored, which read as now.
+    created_at: Option<DateTime<Utc>>,
+    updated_at: Option<DateTime<Utc>>,
     name: String,
 }
 
@@ -20,9 +37,158 @@ pub enum Reccurence {
     Daily,
     Weekly(Vec<DaysOfWeek>),
     Monthly(Option<u32>),
+    /// First or last Monday-Friday of each month; holidays are not considered.
+    MonthlyWorkday(WorkdayPosition),
+    /// Every three months, clamped to short months like `Monthly(None)`.
+    Quarterly,
+    /// The last day of each month, whatever its length. Unlike a clamped
+    /// `Monthly(Some(day))` written for a short month, it snaps back to the
+    /// 31st.
+    MonthlyLastDay,
+    /// A weekday of every month, e.g. the first Monday.
+    MonthlyWeekday {
+        ordinal: WeekdayOrdinal,
+        weekday: DaysOfWeek,
+    },
     Yearly,
+    /// A weekday of a fixed month, e.g. the last Monday of May. Computed
+    /// afresh each year, so unlike `Yearly` it never needs clamping.
+    YearlyWeekday {
+        month: u32,
+        ordinal: WeekdayOrdinal,
+        weekday: DaysOfWeek,
+    },
+    /// "every 3 days": a fixed step from the due date. Month steps clamp to
+    /// short months like `Monthly(None)`.
+    Interval {
+        every: u32,
+        unit: IntervalUnit,
+    },
 }
 
+/// When a recurring todo stops rolling forward. Completing the last
+/// occurrence marks it done.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RecurrenceEnd {
+    /// `until 2026-06-01`: no occurrence after this day, in the home zone.
+    Until(NaiveDate),
+    /// `x10`: occurrences left, counting the current one.
+    Count(u32),
+}
+
+impl RecurrenceEnd {
+    pub fn as_str(self) -> String {
+        match self {
+            RecurrenceEnd::Until(date) => format!("until {}", date.format("%Y-%m-%d")),
+            RecurrenceEnd::Count(count) => format!("x{count}"),
+        }
+    }
+
+    /// Whether the series continues to an occurrence due at `next_due`.
+    fn allows(self, next_due: DateTime<Utc>) -> bool {
+        match self {
+            RecurrenceEnd::Until(date) => in_home_zone(next_due).date_naive() <= date,
+            RecurrenceEnd::Count(count) => count > 1,
+        }
+    }
+
+    /// The end condition after one occurrence is completed.
+    fn after_occurrence(self) -> RecurrenceEnd {
+        match self {
+            RecurrenceEnd::Count(count) => RecurrenceEnd::Count(count.saturating_sub(1)),
+            until => until,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum IntervalUnit {
+    Day,
+    Week,
+    Month,
+}
+
+impl IntervalUnit {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            IntervalUnit::Day => "day",
+            IntervalUnit::Week => "week",
+            IntervalUnit::Month => "month",
+        }
+    }
+}
+
+/// Triage level from `(priority: ...)`. `p1`/`p2`/`p3` and short forms are
+/// read as aliases and written back as `high`, `medium` or `low`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Priority {
+    High,
+    Medium,
+    Low,
+}
+
+impl Priority {
+    pub fn parse(raw: &str) -> Option<Priority> {
+        match raw.trim().to_lowercase().as_str() {
+            "high" | "h" | "p1" => Some(Priority::High),
+            "medium" | "med" | "m" | "p2" => Some(Priority::Medium),
+            "low" | "l" | "p3" => Some(Priority::Low),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Priority::High => "high",
+            Priority::Medium => "medium",
+            Priority::Low => "low",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WorkdayPosition {
+    First,
+    Last,
+}
+
+/// Which occurrence of a weekday within its month.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WeekdayOrdinal {
+    First,
+    Second,
+    Third,
+    Fourth,
+    Last,
+}
+
+impl WeekdayOrdinal {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            WeekdayOrdinal::First => "first",
+            WeekdayOrdinal::Second => "second",
+            WeekdayOrdinal::Third => "third",
+            WeekdayOrdinal::Fourth => "fourth",
+            WeekdayOrdinal::Last => "last",
+        }
+    }
+}
+
+pub const MONTH_NAMES: [&str; 12] = [
+    "january",
+    "february",
+    "march",
+    "april",
+    "may",
+    "june",
+    "july",
+    "august",
+    "september",
+    "october",
+    "november",
+    "december",
+];
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum DaysOfWeek {
     Monday,
@@ -36,74 +202,194 @@ pub enum DaysOfWeek {
 
 impl Todo {
     pub fn new(name: String) -> Todo {
+        let name = sort_trailing_tags(&normalize_name(&name));
         Todo {
             id: Uuid::new_v4(),
             done: false,
             due_date: None,
+            scheduled: None,
             recurence: None,
-            created_at: Utc::now(),
-            updated_at: Utc::now(),
-            name: name,
+            recurrence_end: None,
+            external_id: None,
+            priority: None,
+            notes: Vec::new(),
+            created_at: Some(Utc::now()),
+            updated_at: Some(Utc::now()),
+            name,
         }
     }
 
+    /// Builds a todo from already-validated fields, e.g. when importing.
+    pub fn from_parts(
+        id: Uuid,
+        name: String,
+        done: bool,
+        due_date: Option<DateTime<Utc>>,
+        scheduled: Option<DateTime<Utc>>,
+        recurence: Option<Reccurence>,
+    ) -> Todo {
+        Todo {
+            id,
+            done,
+            due_date,
+            scheduled,
+            recurence,
+            ..Todo::new(name)
+        }
+    }
+
+    #[allow(clippy::should_implement_trait)]
     pub fn from_str(line: &str) -> Todo {
         Self::try_from_str(line).expect("todo line does not match expected format")
     }
 
+    /// Parses `- [ ] name (due: ...) (scheduled: ...) (reccurence: ...) (priority: ...) (created: ...) (updated: ...) (id: ...)`. Only the
+    /// leading checkbox and the trailing metadata segments are anchored, so
+    /// names may contain brackets and parentheses such as `[[wikilinks]]` or
+    /// `[text](url)`.
     pub fn try_from_str(line: &str) -> Result<Todo, String> {
         let line = line.trim();
 
-        let todo_regex = Regex::new(
-            r"^- \[(?P<done>[xX_ ])\] (?P<name>.+?)(?: \(due: (?P<due_date>[^)]+)\))?(?: \((?:reccurence|recurrence): (?P<reccurence>[^)]+)\))?(?: \(id: (?P<id>[0-9a-fA-F-]{36})\))?\.?$",
+        let todo_regex = Regex::new(r"^- \[(?P<done>[xX_ ])\] (?P<rest>.+)$")
+            .expect("todo parser regex must be valid");
+        let segment_regex = Regex::new(
+            r" \((?P<key>due|scheduled|reccurence|recurrence|priority|ext|created|updated|id): (?P<value>[^)]+)\)$",
         )
-        .expect("todo parser regex must be valid");
+        .expect("segment regex must be valid");
 
         let captures = todo_regex
             .captures(line)
             .ok_or_else(|| "todo line does not match expected format".to_string())?;
 
-        let mut todo = Todo::new(captures["name"].trim().to_string());
+        // Metadata segments trail the name in any order. They are peeled off
+        // the end one at a time; a repeated key or a malformed id stops the
+        // scan, leaving it and everything before it in the name.
+        let rest = captures.name("rest").map_or("", |m| m.as_str());
+        let mut rest = rest
+            .strip_suffix('.')
+            .filter(|stripped| !stripped.is_empty())
+            .unwrap_or(rest);
+        let mut segments: HashMap<&str, &str> = HashMap::new();
+        while let Some(segment) = segment_regex.captures(rest) {
+            let key = match segment.name("key").map_or("", |m| m.as_str()) {
+                "recurrence" => "reccurence",
+                key => key,
+            };
+            let value = segment.name("value").map_or("", |m| m.as_str());
+            if segments.contains_key(key) || (key == "id" && !is_id_shaped(unwrap_id(value))) {
+                break;
+            }
+            segments.insert(key, value);
+            rest = &rest[..segment.get(0).map_or(rest.len(), |m| m.start())];
+        }
+
+        let mut todo = Todo::new(rest.trim().to_string());
         todo.done = matches!(&captures["done"], "x" | "X");
 
-        if let Some(due_date_match) = captures.name("due_date") {
-            if let Some(parsed_due_date) = parse_human_datetime(due_date_match.as_str(), Utc::now())
-            {
-                todo.due_date = Some(parsed_due_date);
-            }
+        if let Some(due_date) = segments.get("due")
+            && let Some(parsed_due_date) = parse_human_datetime(due_date, Utc::now())
+        {
+            todo.due_date = Some(parsed_due_date);
         }
 
-        if let Some(reccurence_match) = captures.name("reccurence") {
-            todo.recurence = parse_reccurence(reccurence_match.as_str(), Local::now());
+        if let Some(scheduled) = segments.get("scheduled")
+            && let Some(parsed_scheduled) = parse_human_datetime(scheduled, Utc::now())
+        {
+            todo.scheduled = Some(parsed_scheduled);
         }
 
-        if let Some(id_match) = captures.name("id") {
-            if let Ok(parsed_id) = Uuid::parse_str(id_match.as_str()) {
-                todo.id = parsed_id;
-            }
+        if let Some(reccurence) = segments.get("reccurence")
+            && let Some((rule, end)) = split_recurrence_end(reccurence)
+        {
+            todo.recurence = parse_reccurence(rule, in_home_zone(Utc::now()));
+            todo.recurrence_end = end.filter(|_| todo.recurence.is_some());
+        }
+
+        todo.priority = segments
+            .get("priority")
+            .and_then(|priority| Priority::parse(priority));
+
+        todo.external_id = segments
+            .get("ext")
+            .map(|ext| ext.trim().to_string())
+            .filter(|ext| !ext.is_empty());
+
+        if let Some(id) = segments.get("id")
+            && let Ok(parsed_id) = Uuid::parse_str(unwrap_id(id))
+        {
+            todo.id = parsed_id;
         }
 
         if todo.done {
             todo.complete();
         }
 
-        todo.updated_at = Utc::now();
+        let stamp = |key: &str| {
+            segments
+                .get(key)
+                .and_then(|value| DateTime::parse_from_rfc3339(value.trim()).ok())
+                .map(|value| value.to_utc())
+        };
+        todo.created_at = stamp("created");
+        todo.updated_at = stamp("updated");
         Ok(todo)
     }
 
     pub fn to_line(&self) -> String {
-        let mut line = format!("- [{}] {}", if self.done { "x" } else { "_" }, self.name);
+        self.to_line_with(configured_checkbox_style())
+    }
+
+    /// [`Todo::to_line`] with open todos written in `checkbox` style.
+    pub fn to_line_with(&self, checkbox: CheckboxStyle) -> String {
+        let marker = if self.done {
+            "x"
+        } else {
+            checkbox.open_marker()
+        };
+        let mut line = format!("- [{marker}] {}", self.name);
 
         if let Some(due_date) = self.due_date {
-            let local_due = due_date.with_timezone(&Local);
+            let relative = (!self.done && configured_due_style() == DueStyle::Relative)
+                .then(|| format_relative_due(due_date, Utc::now()))
+                .flatten();
+            match relative {
+                Some(phrase) => line.push_str(&format!(" (due: {phrase})")),
+                None => {
+                    let local_due = in_home_zone(due_date);
+                    line.push_str(&format!(
+                        " (due: {})",
+                        local_due.format("%Y-%m-%d %I:%M %p")
+                    ));
+                }
+            }
+        }
+
+        if let Some(scheduled) = self.scheduled {
+            let local_scheduled = in_home_zone(scheduled);
             line.push_str(&format!(
-                " (due: {})",
-                local_due.format("%Y-%m-%d %I:%M %p")
+                " (scheduled: {})",
+                local_scheduled.format("%Y-%m-%d %I:%M %p")
             ));
         }
 
-        if let Some(reccurence) = self.recurence() {
-            line.push_str(&format!(" (reccurence: {})", reccurence.as_str()));
+        if let Some(reccurence) = self.recurrence_text() {
+            line.push_str(&format!(" (reccurence: {reccurence})"));
+        }
+
+        if let Some(priority) = self.priority {
+            line.push_str(&format!(" (priority: {})", priority.as_str()));
+        }
+
+        if let Some(external_id) = &self.external_id {
+            line.push_str(&format!(" (ext: {external_id})"));
+        }
+
+        if let Some(created_at) = self.created_at {
+            line.push_str(&format!(" (created: {})", rfc3339_seconds(created_at)));
+        }
+
+        if let Some(updated_at) = self.updated_at {
+            line.push_str(&format!(" (updated: {})", rfc3339_seconds(updated_at)));
         }
 
         line.push_str(&format!(" (id: {})", self.id));
@@ -114,18 +400,72 @@ impl Todo {
         self.id
     }
 
+    /// Where completing at `now` moves the due date; `None` when the todo is
+    /// not recurring or this was its last occurrence.
+    pub fn next_occurrence(&self, now: DateTime<Utc>) -> Option<DateTime<Utc>> {
+        let next_due = rollover_due_date(self.due_date?, self.recurence.as_ref()?, now)?;
+        self.recurrence_end
+            .is_none_or(|end| end.allows(next_due))
+            .then_some(next_due)
+    }
+
     pub fn complete(&mut self) {
-        if let (Some(reccurence), Some(due_date)) = (&self.recurence, self.due_date) {
-            if let Some(next_due) = next_due_date_utc(due_date, reccurence) {
-                self.due_date = Some(next_due);
-                self.done = false;
-                self.updated_at = Utc::now();
-                return;
-            }
+        if let Some(due_date) = self.due_date
+            && let Some(next_due) = self.next_occurrence(Utc::now())
+        {
+            // Keep the defer date the same distance ahead of the due date.
+            self.scheduled = self
+                .scheduled
+                .map(|scheduled| scheduled + (next_due - due_date));
+            self.due_date = Some(next_due);
+            self.recurrence_end = self.recurrence_end.map(RecurrenceEnd::after_occurrence);
+            self.done = false;
+            self.updated_at = Some(Utc::now());
+            return;
         }
 
         self.done = true;
-        self.updated_at = Utc::now();
+        self.updated_at = Some(Utc::now());
+    }
+
+    pub fn set_name(&mut self, name: String) {
+        self.name = sort_trailing_tags(&normalize_name(&name));
+        self.updated_at = Some(Utc::now());
+    }
+
+    pub fn set_due_date(&mut self, due_date: Option<DateTime<Utc>>) {
+        self.due_date = due_date;
+        self.updated_at = Some(Utc::now());
+    }
+
+    pub fn set_scheduled(&mut self, scheduled: Option<DateTime<Utc>>) {
+        self.scheduled = scheduled;
+        self.updated_at = Some(Utc::now());
+    }
+
+    pub fn set_recurence(&mut self, recurence: Option<Reccurence>) {
+        self.recurence = recurence;
+        self.updated_at = Some(Utc::now());
+    }
+
+    pub fn set_recurrence_end(&mut self, recurrence_end: Option<RecurrenceEnd>) {
+        self.recurrence_end = recurrence_end;
+        self.updated_at = Some(Utc::now());
+    }
+
+    pub fn set_external_id(&mut self, external_id: Option<String>) {
+        self.external_id = external_id;
+        self.updated_at = Some(Utc::now());
+    }
+
+    pub fn set_priority(&mut self, priority: Option<Priority>) {
+        self.priority = priority;
+        self.updated_at = Some(Utc::now());
+    }
+
+    pub fn set_notes(&mut self, notes: Vec<String>) {
+        self.notes = notes;
+        self.updated_at = Some(Utc::now());
     }
 
     pub fn done(&self) -> bool {
@@ -136,32 +476,213 @@ impl Todo {
         self.due_date
     }
 
+    pub fn scheduled(&self) -> Option<DateTime<Utc>> {
+        self.scheduled
+    }
+
     pub fn recurence(&self) -> Option<&Reccurence> {
         self.recurence.as_ref()
     }
 
+    pub fn recurrence_end(&self) -> Option<RecurrenceEnd> {
+        self.recurrence_end
+    }
+
+    /// The rule with its end condition, as written in `(reccurence: ...)`.
+    pub fn recurrence_text(&self) -> Option<String> {
+        let rule = self.recurence.as_ref()?.as_str();
+        Some(match self.recurrence_end {
+            Some(end) => format!("{rule} {}", end.as_str()),
+            None => rule,
+        })
+    }
+
+    pub fn external_id(&self) -> Option<&str> {
+        self.external_id.as_deref()
+    }
+
+    pub fn priority(&self) -> Option<Priority> {
+        self.priority
+    }
+
+    pub fn notes(&self) -> &[String] {
+        &self.notes
+    }
+
     pub fn created_at(&self) -> DateTime<Utc> {
-        self.created_at
+        self.created_at.unwrap_or_else(Utc::now)
     }
 
     pub fn updated_at(&self) -> DateTime<Utc> {
+        self.updated_at.unwrap_or_else(Utc::now)
+    }
+
+    /// `updated_at` as written in the line, without the fallback to now.
+    pub fn recorded_updated_at(&self) -> Option<DateTime<Utc>> {
         self.updated_at
     }
 
+    /// `created_at` as written in the line, without the fallback to now.
+    pub fn recorded_created_at(&self) -> Option<DateTime<Utc>> {
+        self.created_at
+    }
+
+    /// Replaces both timestamps, e.g. to carry them over to a merged todo.
+    pub fn set_timestamps(
+        &mut self,
+        created_at: Option<DateTime<Utc>>,
+        updated_at: Option<DateTime<Utc>>,
+    ) {
+        self.created_at = created_at;
+        self.updated_at = updated_at;
+    }
+
     pub fn name(&self) -> String {
         self.name.clone()
     }
+
+    /// Someday/maybe items carry a `#someday` tag in their name. They are
+    /// parked, so they are never overdue and stay out of the default list.
+    pub fn is_someday(&self) -> bool {
+        self.has_tag(SOMEDAY_TAG)
+    }
+
+    /// Current-focus items carry a `#pinned` tag; `list` shows them first
+    /// whatever their due date.
+    pub fn is_pinned(&self) -> bool {
+        self.has_tag(PINNED_TAG)
+    }
+
+    /// Every `#tag` in the name, without the `#`, sorted and deduplicated
+    /// ignoring case.
+    pub fn tags(&self) -> Vec<String> {
+        let mut tags = self
+            .name
+            .split_whitespace()
+            .filter_map(tag_word)
+            .map(str::to_string)
+            .collect::<Vec<_>>();
+        tags.sort_by_key(|tag| tag.to_lowercase());
+        tags.dedup_by(|a, b| a.to_lowercase() == b.to_lowercase());
+        tags
+    }
+
+    /// Case-insensitive; the leading `#` on `tag` is optional.
+    pub fn has_tag(&self, tag: &str) -> bool {
+        let tag = tag.strip_prefix('#').unwrap_or(tag).to_lowercase();
+        self.name
+            .split_whitespace()
+            .filter_map(tag_word)
+            .any(|own| own.to_lowercase() == tag)
+    }
+}
+
+/// The id inside `(id: ...)` without padding or the backticks and quotes
+/// editors and merges sometimes wrap it in.
+/// `(created: ...)` and `(updated: ...)` values, e.g. `2026-02-23T18:00:00Z`.
+fn rfc3339_seconds(value: DateTime<Utc>) -> String {
+    value.to_rfc3339_opts(SecondsFormat::Secs, true)
+}
+
+pub(crate) fn unwrap_id(value: &str) -> &str {
+    value
+        .trim()
+        .trim_matches(|ch| matches!(ch, '`' | '"' | '\''))
+        .trim()
+}
+
+/// 36 hex digits and dashes; anything else in `(id: ...)` stays in the name.
+fn is_id_shaped(value: &str) -> bool {
+    value.len() == 36 && value.chars().all(|ch| ch.is_ascii_hexdigit() || ch == '-')
+}
+
+pub const SOMEDAY_TAG: &str = "#someday";
+pub const PINNED_TAG: &str = "#pinned";
+
+/// House style for writing recurrences back out (`TODOS_RECURRENCE_STYLE`).
+/// The parser reads both.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum RecurrenceStyle {
+    /// "monthly on 1st", and Monday to Friday as "weekdays".
+    #[default]
+    Compact,
+    /// "monthly on the 1st", and every weekday spelled out.
+    Verbose,
+}
+
+impl RecurrenceStyle {
+    pub fn parse(raw: &str) -> Option<RecurrenceStyle> {
+        match raw.trim().to_ascii_lowercase().as_str() {
+            "compact" => Some(RecurrenceStyle::Compact),
+            "verbose" => Some(RecurrenceStyle::Verbose),
+            _ => None,
+        }
+    }
+}
+
+/// How open todos are written (`TODOS_CHECKBOX_STYLE`). The parser reads
+/// `- [_]` and the Markdown `- [ ]` alike.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum CheckboxStyle {
+    /// `- [_]`, which survives editors that trim `[ ]` to `[]`.
+    #[default]
+    Underscore,
+    /// `- [ ]`, which GitHub and other Markdown tools render as a checkbox.
+    Space,
 }
 
+impl CheckboxStyle {
+    pub fn parse(raw: &str) -> Option<CheckboxStyle> {
+        match raw.trim().to_ascii_lowercase().as_str() {
+            "underscore" | "_" => Some(CheckboxStyle::Underscore),
+            "space" | "markdown" => Some(CheckboxStyle::Space),
+            _ => None,
+        }
+    }
+
+    fn open_marker(self) -> &'static str {
+        match self {
+            CheckboxStyle::Underscore => "_",
+            CheckboxStyle::Space => " ",
+        }
+    }
+}
+
+pub const WEEKDAYS: [DaysOfWeek; 5] = [
+    DaysOfWeek::Monday,
+    DaysOfWeek::Tuesday,
+    DaysOfWeek::Wednesday,
+    DaysOfWeek::Thursday,
+    DaysOfWeek::Friday,
+];
+
 impl Reccurence {
-    fn as_str(&self) -> String {
+    /// The rule in the configured [`RecurrenceStyle`].
+    pub fn as_str(&self) -> String {
+        self.as_str_with(configured_recurrence_style())
+    }
+
+    pub fn as_str_with(&self, style: RecurrenceStyle) -> String {
         match self {
             Reccurence::Daily => "daily".to_string(),
             Reccurence::Weekly(days) => {
-                if days.len() == 7 {
-                    "weekly".to_string()
+                let mut unique = Vec::new();
+                for day in days {
+                    if !unique.contains(day) {
+                        unique.push(*day);
+                    }
+                }
+                // Bare "weekly" reads back as a single day, so every day of
+                // the week is written as what it means.
+                if unique.len() == 7 {
+                    "daily".to_string()
+                } else if style == RecurrenceStyle::Compact
+                    && unique.len() == WEEKDAYS.len()
+                    && WEEKDAYS.iter().all(|day| unique.contains(day))
+                {
+                    "weekdays".to_string()
                 } else {
-                    let day_list = days
+                    let day_list = unique
                         .iter()
                         .map(DaysOfWeek::as_str)
                         .collect::<Vec<_>>()
@@ -169,13 +690,86 @@ impl Reccurence {
                     format!("weekly on {day_list}")
                 }
             }
-            Reccurence::Monthly(Some(day)) => format!("monthly on {}", ordinal_day(*day)),
+            Reccurence::Monthly(Some(day)) => match style {
+                RecurrenceStyle::Compact => format!("monthly on {}", ordinal_day(*day)),
+                RecurrenceStyle::Verbose => format!("monthly on the {}", ordinal_day(*day)),
+            },
             Reccurence::Monthly(None) => "monthly".to_string(),
+            Reccurence::MonthlyWorkday(WorkdayPosition::First) => {
+                "monthly on the first workday".to_string()
+            }
+            Reccurence::MonthlyWorkday(WorkdayPosition::Last) => {
+                "monthly on the last workday".to_string()
+            }
+            Reccurence::MonthlyLastDay => "monthly on the last day".to_string(),
+            Reccurence::Quarterly => "quarterly".to_string(),
+            Reccurence::MonthlyWeekday { ordinal, weekday } => {
+                format!("monthly on the {} {}", ordinal.as_str(), weekday.as_str())
+            }
             Reccurence::Yearly => "yearly".to_string(),
+            Reccurence::YearlyWeekday {
+                month,
+                ordinal,
+                weekday,
+            } => format!(
+                "yearly on the {} {} of {}",
+                ordinal.as_str(),
+                weekday.as_str(),
+                MONTH_NAMES[*month as usize - 1]
+            ),
+            Reccurence::Interval { every, unit } => format!(
+                "every {every} {}{}",
+                unit.as_str(),
+                if *every == 1 { "" } else { "s" }
+            ),
         }
     }
 }
 
+/// Names are trimmed and runs of whitespace collapse to one space, as
+/// markdown renders them anyway. Two machines whose lines differ only in
+/// spacing therefore read the same name and see no change.
+pub fn normalize_name(name: &str) -> String {
+    name.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+/// Sorts the run of tags that ends a normalized name and drops repeats,
+/// including tags already used earlier in the name. Tags inside the text are
+/// left where they are.
+fn sort_trailing_tags(name: &str) -> String {
+    let words = name.split(' ').collect::<Vec<_>>();
+    let Some(last_word) = words.iter().rposition(|word| tag_word(word).is_none()) else {
+        return name.to_string();
+    };
+    let (text, trailing) = words.split_at(last_word + 1);
+    let mut seen = text
+        .iter()
+        .filter_map(|word| tag_word(word))
+        .map(str::to_lowercase)
+        .collect::<HashSet<_>>();
+    let mut tags = trailing
+        .iter()
+        .copied()
+        .filter(|word| tag_word(word).is_some_and(|tag| seen.insert(tag.to_lowercase())))
+        .collect::<Vec<_>>();
+    tags.sort_by_key(|word| word.to_lowercase());
+    text.iter()
+        .chain(&tags)
+        .copied()
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// `#` then a letter, then letters, digits, `_`, `-` or `/`. A leading letter
+/// keeps issue references like `#123` out of the tags.
+fn tag_word(word: &str) -> Option<&str> {
+    let tag = word.strip_prefix('#')?;
+    let mut chars = tag.chars();
+    (chars.next()?.is_alphabetic()
+        && chars.all(|ch| ch.is_alphanumeric() || matches!(ch, '_' | '-' | '/')))
+    .then_some(tag)
+}
+
 fn ordinal_day(day: u32) -> String {
     let suffix = match day % 100 {
         11..=13 => "th",
@@ -206,6 +800,163 @@ impl DaysOfWeek {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::date_parser::pin_home_zone;
+
+    #[test]
+    fn parses_metadata_segments_in_any_order() {
+        let canonical = Todo::from_str(
+            "- [_] Pay rent (due: 2026-03-01T12:00:00Z) (reccurence: monthly) (id: 123e4567-e89b-12d3-a456-426614174000)",
+        );
+        let reordered = Todo::from_str(
+            "- [_] Pay rent (id: 123e4567-e89b-12d3-a456-426614174000) (recurrence: monthly) (due: 2026-03-01T12:00:00Z)",
+        );
+
+        assert_eq!(reordered.name(), "Pay rent");
+        assert_eq!(reordered.id(), canonical.id());
+        assert_eq!(reordered.due_date(), canonical.due_date());
+        assert_eq!(reordered.recurence(), canonical.recurence());
+        assert_eq!(reordered.to_line(), canonical.to_line());
+    }
+
+    #[test]
+    fn external_ids_round_trip() {
+        let _zone = pin_home_zone(chrono_tz::UTC);
+        let line = "- [_] Fix login (due: 2026-03-01 12:00 PM) (ext: JIRA-123) (id: 123e4567-e89b-12d3-a456-426614174000)";
+        let todo = Todo::from_str(line);
+
+        assert_eq!(todo.name(), "Fix login");
+        assert_eq!(todo.external_id(), Some("JIRA-123"));
+        assert_eq!(todo.to_line(), line);
+
+        let reordered = Todo::from_str(
+            "- [_] Fix login (ext: JIRA-123) (id: 123e4567-e89b-12d3-a456-426614174000) (due: 2026-03-01 12:00 PM)",
+        );
+        assert_eq!(reordered.to_line(), line);
+    }
+
+    #[test]
+    fn priority_aliases_are_written_back_canonically() {
+        let _zone = pin_home_zone(chrono_tz::UTC);
+        let line = "- [_] Fix login (due: 2026-03-01 12:00 PM) (priority: high) (ext: JIRA-123) (id: 123e4567-e89b-12d3-a456-426614174000)";
+        let todo = Todo::from_str(line);
+        assert_eq!(todo.priority(), Some(Priority::High));
+        assert_eq!(todo.to_line(), line);
+
+        let alias = Todo::from_str(
+            "- [_] Fix login (priority: P1) (due: 2026-03-01 12:00 PM) (ext: JIRA-123) (id: 123e4567-e89b-12d3-a456-426614174000)",
+        );
+        assert_eq!(alias.to_line(), line);
+        assert_eq!(Priority::parse("med"), Some(Priority::Medium));
+        assert_eq!(Priority::parse("p3"), Some(Priority::Low));
+        assert_eq!(Priority::parse("urgent"), None);
+
+        let plain = "- [_] Fix login (id: 123e4567-e89b-12d3-a456-426614174000)";
+        assert_eq!(Todo::from_str(plain).priority(), None);
+        assert_eq!(Todo::from_str(plain).to_line(), plain);
+    }
+
+    #[test]
+    fn trailing_tags_are_sorted_and_deduplicated() {
+        let _zone = pin_home_zone(chrono_tz::UTC);
+        let todo = Todo::from_str(
+            "- [_] Fix #api deploy #work #Urgent #work #API (due: 2026-03-01 12:00 PM) (id: 123e4567-e89b-12d3-a456-426614174000)",
+        );
+        assert_eq!(todo.name(), "Fix #api deploy #Urgent #work");
+        assert_eq!(todo.tags(), ["api", "Urgent", "work"]);
+        assert!(todo.has_tag("#urgent"));
+        assert!(todo.has_tag("API"));
+        assert!(!todo.has_tag("home"));
+        assert_eq!(Todo::from_str(&todo.to_line()).to_line(), todo.to_line());
+
+        let issue = Todo::from_str("- [_] Close #123 (id: 123e4567-e89b-12d3-a456-426614174000)");
+        assert!(issue.tags().is_empty());
+        assert_eq!(issue.name(), "Close #123");
+    }
+
+    #[test]
+    fn repeated_or_malformed_segments_stay_in_the_name() {
+        let repeated = Todo::from_str(
+            "- [_] Call (due: 2026-03-01T12:00:00Z) (id: 123e4567-e89b-12d3-a456-426614174000) (due: 2026-03-02T12:00:00Z)",
+        );
+        assert_eq!(repeated.name(), "Call (due: 2026-03-01T12:00:00Z)");
+        assert_eq!(
+            repeated.id().to_string(),
+            "123e4567-e89b-12d3-a456-426614174000"
+        );
+
+        let bad_id = Todo::from_str("- [_] Call (due: 2026-03-01T12:00:00Z) (id: not-a-uuid)");
+        assert_eq!(
+            bad_id.name(),
+            "Call (due: 2026-03-01T12:00:00Z) (id: not-a-uuid)"
+        );
+        assert!(bad_id.due_date().is_none());
+    }
+
+    #[test]
+    fn wrapped_and_padded_ids_are_read_through() {
+        for line in [
+            "- [_] Call (id: `123e4567-e89b-12d3-a456-426614174000`)",
+            "- [_] Call (id: \"123e4567-e89b-12d3-a456-426614174000\")",
+            "- [_] Call (id:  123e4567-e89b-12d3-a456-426614174000 )",
+        ] {
+            let todo = Todo::from_str(line);
+            assert_eq!(todo.name(), "Call", "{line}");
+            assert_eq!(
+                todo.to_line(),
+                "- [_] Call (id: 123e4567-e89b-12d3-a456-426614174000)",
+                "{line}"
+            );
+        }
+    }
+
+    #[test]
+    fn recurrence_styles_write_compact_or_verbose_forms() {
+        let monthly = Reccurence::Monthly(Some(1));
+        let weekdays = Reccurence::Weekly(vec![
+            DaysOfWeek::Friday,
+            DaysOfWeek::Monday,
+            DaysOfWeek::Tuesday,
+            DaysOfWeek::Wednesday,
+            DaysOfWeek::Thursday,
+        ]);
+
+        assert_eq!(
+            monthly.as_str_with(RecurrenceStyle::Compact),
+            "monthly on 1st"
+        );
+        assert_eq!(
+            monthly.as_str_with(RecurrenceStyle::Verbose),
+            "monthly on the 1st"
+        );
+        assert_eq!(weekdays.as_str_with(RecurrenceStyle::Compact), "weekdays");
+        assert_eq!(
+            weekdays.as_str_with(RecurrenceStyle::Verbose),
+            "weekly on friday, monday, tuesday, wednesday, thursday"
+        );
+
+        // Both styles read back as the same rule.
+        for style in [RecurrenceStyle::Compact, RecurrenceStyle::Verbose] {
+            for rule in [&monthly, &weekdays] {
+                let line = format!(
+                    "- [_] Chore (reccurence: {}) (id: 123e4567-e89b-12d3-a456-426614174000)",
+                    rule.as_str_with(style)
+                );
+                let parsed = Todo::from_str(&line);
+                let parsed = parsed.recurence().expect("recurrence");
+                match (parsed, rule) {
+                    (Reccurence::Weekly(a), Reccurence::Weekly(b)) => {
+                        assert!(a.len() == b.len() && a.iter().all(|day| b.contains(day)));
+                    }
+                    _ => assert_eq!(parsed, rule),
+                }
+            }
+        }
+        assert_eq!(
+            RecurrenceStyle::parse("Verbose"),
+            Some(RecurrenceStyle::Verbose)
+        );
+        assert_eq!(RecurrenceStyle::parse("short"), None);
+    }
 
     #[test]
     fn parses_weekly_reccurence_with_days() {
@@ -251,6 +1002,20 @@ mod tests {
         );
     }
 
+    #[test]
+    fn name_whitespace_is_collapsed_so_machines_converge() {
+        let spaced =
+            Todo::from_str("- [_]  Pay   rent\tnow  (id: 123e4567-e89b-12d3-a456-426614174000)");
+        let canonical =
+            Todo::from_str("- [_] Pay rent now (id: 123e4567-e89b-12d3-a456-426614174000)");
+        assert_eq!(spaced.name(), "Pay rent now");
+        assert_eq!(spaced.to_line(), canonical.to_line());
+
+        let mut renamed = canonical.clone();
+        renamed.set_name("  Pay  rent ".to_string());
+        assert_eq!(renamed.name(), "Pay rent");
+    }
+
     #[test]
     fn completed_recurring_item_rolls_due_date_forward() {
         let todo = Todo::from_str(
@@ -286,6 +1051,144 @@ mod tests {
         );
     }
 
+    #[test]
+    fn counted_recurrence_ends_after_its_last_occurrence() {
+        let mut todo = Todo::from_str(
+            "- [_] Physio exercises (due: 2026-02-23T14:00:00Z) (reccurence: daily x2) (id: 123e4567-e89b-12d3-a456-426614174000)",
+        );
+        assert_eq!(todo.recurrence_end(), Some(RecurrenceEnd::Count(2)));
+
+        todo.complete();
+        assert!(!todo.done());
+        assert_eq!(todo.recurrence_text().as_deref(), Some("daily x1"));
+        assert!(todo.to_line().contains("(reccurence: daily x1)"));
+
+        todo.complete();
+        assert!(todo.done());
+        assert_eq!(
+            todo.due_date().expect("due date").to_rfc3339(),
+            "2026-02-24T14:00:00+00:00"
+        );
+    }
+
+    #[test]
+    fn recurrence_until_a_date_stops_rolling_after_it() {
+        let line = "- [_] Team standup (due: 2026-05-25T14:00:00Z) (reccurence: weekly on monday until 2026-06-01) (id: 123e4567-e89b-12d3-a456-426614174000)";
+        let mut todo = Todo::from_str(line);
+        assert_eq!(
+            todo.recurrence_end(),
+            Some(RecurrenceEnd::Until(
+                NaiveDate::from_ymd_opt(2026, 6, 1).expect("date")
+            ))
+        );
+        assert_eq!(Todo::from_str(&todo.to_line()).to_line(), todo.to_line());
+
+        todo.complete();
+        assert!(!todo.done());
+        assert_eq!(
+            todo.due_date().expect("due date").to_rfc3339(),
+            "2026-06-01T14:00:00+00:00"
+        );
+
+        todo.complete();
+        assert!(todo.done());
+
+        let malformed = Todo::from_str(
+            "- [_] Team standup (reccurence: daily x0) (id: 123e4567-e89b-12d3-a456-426614174000)",
+        );
+        assert_eq!(malformed.recurence(), None);
+    }
+
+    #[test]
+    fn workday_reccurence_round_trips() {
+        let line = "- [_] Run payroll (reccurence: monthly on the first workday) (id: 123e4567-e89b-12d3-a456-426614174000)";
+        let todo = Todo::from_str(line);
+        assert_eq!(
+            todo.recurence(),
+            Some(&Reccurence::MonthlyWorkday(WorkdayPosition::First))
+        );
+        assert_eq!(todo.to_line(), line);
+    }
+
+    #[test]
+    fn scheduled_date_round_trips() {
+        let line = "- [_] File taxes (due: 2026-04-15 05:00 PM) (scheduled: 2026-04-01 09:00 AM) (id: 123e4567-e89b-12d3-a456-426614174000)";
+        let todo = Todo::from_str(line);
+
+        assert!(todo.scheduled().is_some());
+        assert_eq!(todo.name(), "File taxes");
+        assert_eq!(todo.to_line(), line);
+    }
+
+    #[test]
+    fn reads_every_checkbox_marker_and_writes_the_chosen_style() {
+        let id = "(id: 123e4567-e89b-12d3-a456-426614174000)";
+        for (marker, done) in [("_", false), (" ", false), ("x", true), ("X", true)] {
+            let todo = Todo::from_str(&format!("- [{marker}] Water plants {id}"));
+            assert_eq!(todo.done(), done, "marker `{marker}`");
+            assert_eq!(todo.name(), "Water plants");
+        }
+
+        let open = Todo::from_str(&format!("- [ ] Water plants {id}"));
+        assert_eq!(
+            open.to_line_with(CheckboxStyle::Underscore),
+            format!("- [_] Water plants {id}")
+        );
+        assert_eq!(
+            open.to_line_with(CheckboxStyle::Space),
+            format!("- [ ] Water plants {id}")
+        );
+        let done = Todo::from_str(&format!("- [X] Water plants {id}"));
+        assert_eq!(
+            done.to_line_with(CheckboxStyle::Space),
+            format!("- [x] Water plants {id}")
+        );
+        assert_eq!(CheckboxStyle::parse("Space"), Some(CheckboxStyle::Space));
+        assert_eq!(CheckboxStyle::parse("[ ]"), None);
+    }
+
+    #[test]
+    fn timestamps_round_trip() {
+        let line = "- [_] File taxes (priority: high) (created: 2026-02-01T08:00:00Z) (updated: 2026-02-20T17:30:00Z) (id: 123e4567-e89b-12d3-a456-426614174000)";
+        let todo = Todo::from_str(line);
+
+        assert_eq!(todo.created_at().to_rfc3339(), "2026-02-01T08:00:00+00:00");
+        assert_eq!(todo.updated_at().to_rfc3339(), "2026-02-20T17:30:00+00:00");
+        assert_eq!(todo.to_line(), line);
+
+        let mut edited = todo.clone();
+        edited.set_priority(Some(Priority::Low));
+        assert_eq!(edited.recorded_created_at(), todo.recorded_created_at());
+        assert!(edited.updated_at() > todo.updated_at());
+    }
+
+    #[test]
+    fn lines_without_timestamps_stay_without_them() {
+        let line = "- [_] File taxes (id: 123e4567-e89b-12d3-a456-426614174000)";
+        let todo = Todo::from_str(line);
+
+        assert_eq!(todo.recorded_created_at(), None);
+        assert!(todo.updated_at() <= Utc::now());
+        assert_eq!(todo.to_line(), line);
+        assert!(Todo::new("Fresh".to_string())
+            .to_line()
+            .contains("(created: "));
+    }
+
+    #[test]
+    fn recurring_rollover_moves_scheduled_with_due() {
+        let _zone = pin_home_zone(chrono_tz::UTC);
+        let mut todo = Todo::from_str(
+            "- [_] Report (due: 2026-03-06T17:00:00Z) (scheduled: 2026-03-04T09:00:00Z) (reccurence: weekly on friday) (id: 123e4567-e89b-12d3-a456-426614174000)",
+        );
+        todo.complete();
+
+        assert_eq!(
+            todo.scheduled().expect("scheduled").to_rfc3339(),
+            "2026-03-11T09:00:00+00:00"
+        );
+    }
+
     #[test]
     fn parses_monthly_on_specific_day() {
         let todo = Todo::from_str(
@@ -314,6 +1217,62 @@ mod tests {
         assert!(todo.due_date().is_some());
     }
 
+    #[test]
+    fn wikilink_in_name_round_trips() {
+        let line = "- [_] Review [[Project X]] notes (reccurence: daily) (id: 123e4567-e89b-12d3-a456-426614174000)";
+        let todo = Todo::from_str(line);
+
+        assert_eq!(todo.name(), "Review [[Project X]] notes");
+        assert_eq!(todo.recurence(), Some(&Reccurence::Daily));
+        assert_eq!(todo.to_line(), line);
+    }
+
+    #[test]
+    fn markdown_link_in_name_round_trips() {
+        let line = "- [x] Read [the spec](https://example.com/spec_(v2)) (id: 123e4567-e89b-12d3-a456-426614174000)";
+        let todo = Todo::from_str(line);
+
+        assert_eq!(
+            todo.name(),
+            "Read [the spec](https://example.com/spec_(v2))"
+        );
+        assert!(todo.done());
+        assert_eq!(todo.to_line(), line);
+    }
+
+    #[test]
+    fn nested_and_trailing_parentheses_in_name_round_trip() {
+        let _zone = pin_home_zone(chrono_tz::UTC);
+        let nested = "- [_] Call bank (ask about fees (and the card)) (due: 2026-03-01 12:00 PM) (id: 123e4567-e89b-12d3-a456-426614174000)";
+        let todo = Todo::from_str(nested);
+        assert_eq!(todo.name(), "Call bank (ask about fees (and the card))");
+        assert!(todo.due_date().is_some());
+        assert_eq!(todo.to_line(), nested);
+
+        // Metadata is peeled from the right in any order; an unknown key such
+        // as `note:` stays part of the name.
+        let todo = Todo::from_str(
+            "- [_] Call bank (note: before noon) (id: 123e4567-e89b-12d3-a456-426614174000) (reccurence: monthly)",
+        );
+        assert_eq!(todo.name(), "Call bank (note: before noon)");
+        assert_eq!(todo.recurence(), Some(&Reccurence::Monthly(None)));
+        assert_eq!(
+            todo.to_line(),
+            "- [_] Call bank (note: before noon) (reccurence: monthly) (id: 123e4567-e89b-12d3-a456-426614174000)"
+        );
+        assert_eq!(Todo::from_str(&todo.to_line()).name(), todo.name());
+    }
+
+    #[test]
+    fn link_at_start_of_name_is_not_a_checkbox() {
+        let line = "- [_] [[Inbox]] triage (id: 123e4567-e89b-12d3-a456-426614174000)";
+        let todo = Todo::from_str(line);
+
+        assert!(!todo.done());
+        assert_eq!(todo.name(), "[[Inbox]] triage");
+        assert_eq!(todo.to_line(), line);
+    }
+
     #[test]
     fn serializes_due_date_in_human_local_format() {
         let todo = Todo::from_str(
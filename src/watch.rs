@@ -0,0 +1,136 @@
+use std::fs;
+use std::path::Path;
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError};
+use std::time::Duration;
+
+use anyhow::{Context, Result};
+use notify::{Event, EventKind, RecursiveMode, Watcher};
+
+/// Quiet period after the last write before `watch` syncs, so an editor's
+/// burst of saves becomes one commit.
+pub const DEBOUNCE: Duration = Duration::from_secs(2);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Signal {
+    Changed,
+    Stop,
+}
+
+/// Calls `on_change` each time writes to `todo_file` have been quiet for
+/// `debounce`, until Ctrl-C. The parent directory is watched rather than the
+/// file, since editors and `write_todo_file_atomic` replace it by rename. A
+/// settled change that leaves the content as `on_change` last saw it, such
+/// as sync normalizing the file itself, is skipped. Errors from `on_change`
+/// stop the watch, so it should handle anything transient itself.
+pub fn watch_todo_file(
+    todo_file: &Path,
+    debounce: Duration,
+    mut on_change: impl FnMut() -> Result<()>,
+) -> Result<()> {
+    let dir = todo_file
+        .parent()
+        .context("todo file has no parent directory to watch")?;
+    let name = todo_file.file_name().map(ToOwned::to_owned);
+    let (tx, rx) = mpsc::channel();
+
+    let stop = tx.clone();
+    ctrlc::set_handler(move || {
+        let _ = stop.send(Signal::Stop);
+    })
+    .context("failed to install the Ctrl-C handler")?;
+
+    let mut watcher = notify::recommended_watcher(move |event: notify::Result<Event>| {
+        let Ok(event) = event else {
+            return;
+        };
+        if !matches!(event.kind, EventKind::Access(_))
+            && event
+                .paths
+                .iter()
+                .any(|path| path.file_name().map(ToOwned::to_owned) == name)
+        {
+            let _ = tx.send(Signal::Changed);
+        }
+    })
+    .context("failed to start the file watcher")?;
+    watcher
+        .watch(dir, RecursiveMode::NonRecursive)
+        .with_context(|| format!("failed to watch {}", dir.display()))?;
+
+    let mut last_seen = fs::read_to_string(todo_file).ok();
+    debounce_signals(&rx, debounce, || {
+        let current = fs::read_to_string(todo_file).ok();
+        if current.is_some() && current == last_seen {
+            return Ok(());
+        }
+        on_change()?;
+        last_seen = fs::read_to_string(todo_file).ok();
+        Ok(())
+    })
+}
+
+/// Runs `on_settled` once per burst of `Changed` signals, after `debounce`
+/// passes without another. Returns on `Stop` or when every sender is gone; a
+/// pending burst is dropped rather than acted on mid-shutdown.
+fn debounce_signals(
+    rx: &Receiver<Signal>,
+    debounce: Duration,
+    mut on_settled: impl FnMut() -> Result<()>,
+) -> Result<()> {
+    while let Ok(Signal::Changed) = rx.recv() {
+        loop {
+            match rx.recv_timeout(debounce) {
+                Ok(Signal::Changed) => continue,
+                Ok(Signal::Stop) | Err(RecvTimeoutError::Disconnected) => return Ok(()),
+                Err(RecvTimeoutError::Timeout) => break,
+            }
+        }
+        on_settled()?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::thread;
+
+    #[test]
+    fn bursts_of_changes_settle_into_one_call() {
+        let (tx, rx) = mpsc::channel();
+        let sender = thread::spawn(move || {
+            for _ in 0..3 {
+                tx.send(Signal::Changed).expect("send");
+                thread::sleep(Duration::from_millis(5));
+            }
+            thread::sleep(Duration::from_millis(200));
+            tx.send(Signal::Changed).expect("send");
+            thread::sleep(Duration::from_millis(200));
+            tx.send(Signal::Stop).expect("send");
+        });
+
+        let mut calls = 0;
+        debounce_signals(&rx, Duration::from_millis(50), || {
+            calls += 1;
+            Ok(())
+        })
+        .expect("debounce");
+        sender.join().expect("sender");
+        assert_eq!(calls, 2);
+    }
+
+    #[test]
+    fn stop_drops_a_pending_burst() {
+        let (tx, rx) = mpsc::channel();
+        tx.send(Signal::Changed).expect("send");
+        tx.send(Signal::Stop).expect("send");
+
+        let mut calls = 0;
+        debounce_signals(&rx, Duration::from_secs(5), || {
+            calls += 1;
+            Ok(())
+        })
+        .expect("debounce");
+        assert_eq!(calls, 0);
+    }
+}